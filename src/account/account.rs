@@ -1,14 +1,21 @@
 use crate::{
-    account::{AccountSettings, AccountSummary, CorrectState, Id, Operation, State},
+    account::{
+        AccountSettings, AccountSummary, CorrectState, Corruption, Id, Operation, Outcome, State,
+    },
     commit::Payload,
 };
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize)]
+use std::collections::BTreeSet;
+
+use talk::crypto::{primitives::hash::Hash, KeyCard};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct Account {
     height: u64,
     state: State,
+    rotations: Vec<(u64, KeyCard)>,
 }
 
 impl Account {
@@ -16,6 +23,44 @@ impl Account {
         Account {
             height: 0,
             state: State::Correct(CorrectState::new(id, settings)),
+            rotations: Vec::new(),
+        }
+    }
+
+    pub fn height(&self) -> u64 {
+        self.height
+    }
+
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+
+    /// Returns the motions supported by `self` (a corrupted account supports no motion).
+    pub fn motions(&self) -> BTreeSet<Hash> {
+        match &self.state {
+            State::Correct(state) => state.motions().clone(),
+            State::Corrupted(_) => BTreeSet::new(),
+        }
+    }
+
+    /// Returns the `KeyCard` that signs `self`'s `Prepare` at `height` (along with
+    /// the height it signs from), if rotated by a `Rotate` below `height` (otherwise,
+    /// the `KeyCard` is the one originally assigned to `self`). The result is exact
+    /// only if `self.height() + 1 >= height`: replicas settle for `self.height()`
+    /// being within `pipeline_width` of `height` (see `AccountSettings`).
+    pub fn rotation(&self, height: u64) -> Option<(u64, &KeyCard)> {
+        self.rotations
+            .iter()
+            .rev()
+            .find(|(since, _)| *since <= height)
+            .map(|(since, keycard)| (*since, keycard))
+    }
+
+    /// Returns the evidence of `self`'s corruption (if `self` is corrupted).
+    pub fn corruption(&self) -> Option<&Corruption> {
+        match &self.state {
+            State::Correct(_) => None,
+            State::Corrupted(state) => Some(state.corruption()),
         }
     }
 
@@ -23,35 +68,68 @@ impl Account {
         AccountSummary {
             height: self.height,
             state: self.state.summarize(),
+            rotation: self
+                .rotations
+                .last()
+                .map(|(since, keycard)| (*since, keycard.identity())),
         }
     }
 
-    pub fn applicable(&self, height: u64) -> bool {
+    /// Returns whether or not a `Payload` at `height` can be applied to `self`,
+    /// either immediately or once the (pipelined, see `AccountSettings`) heights
+    /// preceding it are applied.
+    pub fn applicable(&self, height: u64, settings: &AccountSettings) -> bool {
+        height <= self.height + settings.pipeline_width.max(1)
+    }
+
+    /// Returns whether or not a `Payload` at `height` can be applied to `self` immediately.
+    pub fn ready(&self, height: u64) -> bool {
         height <= self.height + 1
     }
 
+    /// Applies `payload` to `self`, returning whether or not `self` was correct
+    /// after `payload`, along with the `Outcome` of `payload` (if `payload` was
+    /// not previously applied).
     pub fn apply(
         &mut self,
         payload: &Payload,
         dependency: Option<&Operation>,
         settings: &AccountSettings,
-    ) -> bool {
+    ) -> (bool, Option<Outcome>) {
         if payload.height() <= self.height {
-            return payload.height() < self.height || self.state.is_correct();
+            let correct = payload.height() < self.height || self.state.is_correct();
+            return (correct, None);
         }
 
+        self.height += 1;
+
+        // `Operation`s on a corrupted account have no effect (and are excepted
+        // on the grounds of the original corruption)
         let result = match &mut self.state {
             State::Correct(state) => state.apply(payload.operation(), dependency, settings),
-            State::Corrupted(_) => unreachable!(),
+            State::Corrupted(state) => {
+                let error = state.corruption().error().clone();
+                return (false, Some(Outcome::Failure(error)));
+            }
         };
 
-        self.height += 1;
-
         match result {
-            Ok(()) => true,
-            Err(_) => {
-                self.state.corrupt();
-                false
+            Ok(()) => {
+                if let Operation::Rotate(rotate) = payload.operation() {
+                    self.rotations
+                        .push((payload.height() + 1, rotate.keycard().clone()));
+                }
+
+                (true, Some(Outcome::Success))
+            }
+            Err(error) => {
+                let error = error.top().clone();
+
+                let corruption =
+                    Corruption::new(payload.entry(), payload.operation().clone(), error.clone());
+
+                self.state.corrupt(corruption);
+                (false, Some(Outcome::Failure(error)))
             }
         }
     }
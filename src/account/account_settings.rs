@@ -1,9 +1,20 @@
-#[derive(Debug, Clone)]
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
 pub(crate) struct AccountSettings {
     // `initial_balance` should only be set to a non-zero value
     // for the sake of testing / benchmarking: use at own risk!
     pub initial_balance: u64,
     pub supports_capacity: usize,
+    // Maximum number of heights an account can have prepared
+    // (but not yet committed) at any time (pipelining is opt-in:
+    // by default, a height is prepared only once the previous
+    // one is committed). Replicas tell the `KeyCard` of a height
+    // from any commit within `pipeline_width` below it: a rotated
+    // `KeyCard` can be honoured for up to `pipeline_width - 1`
+    // heights past its rotation
+    pub pipeline_width: u64,
 }
 
 impl Default for AccountSettings {
@@ -11,6 +22,7 @@ impl Default for AccountSettings {
         AccountSettings {
             initial_balance: 0,
             supports_capacity: 8,
+            pipeline_width: 1,
         }
     }
 }
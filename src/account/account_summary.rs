@@ -2,8 +2,13 @@ use crate::account::StateSummary;
 
 use serde::{Deserialize, Serialize};
 
+use talk::crypto::Identity;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub(crate) struct AccountSummary {
     pub height: u64,
     pub state: StateSummary,
+    // Latest rotation (see `Account::rotation`): the height it signs from,
+    // along with the `Identity` of the rotated `KeyCard`
+    pub rotation: Option<(u64, Identity)>,
 }
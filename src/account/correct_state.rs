@@ -1,14 +1,14 @@
 use crate::{
     account::{
-        operations::{Abandon, Deposit, Support, Withdraw},
-        AccountSettings, CorruptedState, Id, Operation, OperationError,
+        operations::{Abandon, Deposit, Support, Transfer, Withdraw},
+        AccountSettings, CorruptedState, Corruption, Id, Operation, OperationError,
     },
     crypto::Identify,
 };
 
 use doomstack::{here, Doom, ResultExt, Top};
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use std::collections::BTreeSet;
 
@@ -16,7 +16,7 @@ use talk::crypto::primitives::hash::{self, Hash};
 
 use zebra::map::Set;
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct CorrectState {
     id: Id,
     balance: u64,
@@ -24,7 +24,7 @@ pub(crate) struct CorrectState {
     motions: BTreeSet<Hash>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct Deposits {
     slot: u64,
     root: Option<Hash>,
@@ -43,6 +43,10 @@ impl CorrectState {
         }
     }
 
+    pub fn motions(&self) -> &BTreeSet<Hash> {
+        &self.motions
+    }
+
     pub fn apply(
         &mut self,
         operation: &Operation,
@@ -54,6 +58,9 @@ impl CorrectState {
             Operation::Deposit(deposit) => self.apply_deposit(deposit, dependency.unwrap()),
             Operation::Support(support) => self.apply_support(support, settings),
             Operation::Abandon(abandon) => self.apply_abandon(abandon),
+            Operation::Transfer(transfer) => self.apply_transfer(transfer),
+            // The new `KeyCard` is tracked by `Account` (see `Account::rotation`)
+            Operation::Rotate(_) => Ok(()),
         }
     }
 
@@ -67,6 +74,32 @@ impl CorrectState {
         Ok(())
     }
 
+    fn apply_transfer(&mut self, transfer: &Transfer) -> Result<(), Top<OperationError>> {
+        // Beneficiaries must be distinct, so that each `Deposit` refers to a single leg
+        let beneficiaries = transfer
+            .legs()
+            .iter()
+            .map(Withdraw::beneficiary)
+            .collect::<BTreeSet<_>>();
+
+        if transfer.legs().is_empty() || beneficiaries.len() != transfer.legs().len() {
+            return OperationError::MalformedTransfer.fail().spot(here!());
+        }
+
+        let amount = transfer
+            .amount()
+            .ok_or(OperationError::MalformedTransfer.into_top())
+            .spot(here!())?;
+
+        if self.balance < amount {
+            return OperationError::Overdraft.fail().spot(here!());
+        }
+
+        self.balance -= amount;
+
+        Ok(())
+    }
+
     fn apply_deposit(
         &mut self,
         deposit: &Deposit,
@@ -74,6 +107,10 @@ impl CorrectState {
     ) -> Result<(), Top<OperationError>> {
         let withdraw = match dependency {
             Operation::Withdraw(withdraw) => withdraw,
+            Operation::Transfer(transfer) => transfer
+                .leg(self.id)
+                .ok_or(OperationError::IllegitimateDeposit.into_top())
+                .spot(here!())?,
             _ => {
                 return OperationError::UnexpectedDependency.fail().spot(here!());
             }
@@ -144,8 +181,8 @@ impl CorrectState {
         Ok(())
     }
 
-    pub fn corrupted(&self) -> CorruptedState {
-        CorruptedState::new(self.id)
+    pub fn corrupted(&self, corruption: Corruption) -> CorruptedState {
+        CorruptedState::new(self.id, corruption)
     }
 }
 
@@ -160,3 +197,111 @@ impl Identify for Deposits {
         hash::hash(&self).unwrap()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::account::Entry;
+
+    fn state(id: Id, balance: u64) -> CorrectState {
+        CorrectState::new(
+            id,
+            &AccountSettings {
+                initial_balance: balance,
+                ..Default::default()
+            },
+        )
+    }
+
+    fn apply(state: &mut CorrectState, operation: &Operation) -> Result<(), OperationError> {
+        state
+            .apply(operation, None, &Default::default())
+            .map_err(|error| error.top().clone())
+    }
+
+    #[test]
+    fn transfer() {
+        let mut sender = state(0, 100);
+
+        apply(
+            &mut sender,
+            &Operation::transfer(vec![(1, 0, 30), (2, 0, 50)]),
+        )
+        .unwrap();
+        assert_eq!(sender.balance, 20);
+    }
+
+    #[test]
+    fn malformed_transfers() {
+        let mut sender = state(0, 100);
+
+        // No legs
+        assert_eq!(
+            apply(&mut sender, &Operation::transfer(Vec::new())),
+            Err(OperationError::MalformedTransfer)
+        );
+
+        // Duplicate beneficiaries
+        assert_eq!(
+            apply(
+                &mut sender,
+                &Operation::transfer(vec![(1, 0, 10), (1, 0, 20)])
+            ),
+            Err(OperationError::MalformedTransfer)
+        );
+
+        // Legs overflow
+        assert_eq!(
+            apply(
+                &mut sender,
+                &Operation::transfer(vec![(1, 0, u64::MAX), (2, 0, 1)])
+            ),
+            Err(OperationError::MalformedTransfer)
+        );
+
+        // Legs exceed balance
+        assert_eq!(
+            apply(
+                &mut sender,
+                &Operation::transfer(vec![(1, 0, 60), (2, 0, 50)])
+            ),
+            Err(OperationError::Overdraft)
+        );
+
+        assert_eq!(sender.balance, 100);
+    }
+
+    #[test]
+    fn transfer_deposits() {
+        let transfer = Operation::transfer(vec![(1, 0, 30), (2, 0, 50)]);
+        let entry = Entry { id: 0, height: 1 };
+
+        // Each beneficiary deposits its own leg
+        let mut beneficiary = state(2, 0);
+
+        beneficiary
+            .apply(
+                &Operation::deposit(entry, None, true),
+                Some(&transfer),
+                &Default::default(),
+            )
+            .unwrap();
+
+        assert_eq!(beneficiary.balance, 50);
+
+        // A `Transfer` without a leg paying the depositor cannot be deposited
+        let mut stranger = state(3, 0);
+
+        let error = stranger
+            .apply(
+                &Operation::deposit(entry, None, true),
+                Some(&transfer),
+                &Default::default(),
+            )
+            .unwrap_err();
+
+        assert_eq!(*error.top(), OperationError::IllegitimateDeposit);
+        assert_eq!(stranger.balance, 0);
+    }
+}
@@ -1,14 +1,19 @@
-use crate::account::Id;
+use crate::account::{Corruption, Id};
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct CorruptedState {
     id: Id,
+    corruption: Corruption,
 }
 
 impl CorruptedState {
-    pub fn new(id: Id) -> Self {
-        CorruptedState { id }
+    pub fn new(id: Id, corruption: Corruption) -> Self {
+        CorruptedState { id, corruption }
+    }
+
+    pub fn corruption(&self) -> &Corruption {
+        &self.corruption
     }
 }
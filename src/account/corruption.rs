@@ -0,0 +1,38 @@
+use crate::account::{Entry, Id, Operation, OperationError};
+
+use serde::{Deserialize, Serialize};
+
+/// Evidence of why an account was corrupted: the `Operation` at `entry`
+/// failed with `error`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Corruption {
+    entry: Entry,
+    operation: Operation,
+    error: OperationError,
+}
+
+impl Corruption {
+    pub(crate) fn new(entry: Entry, operation: Operation, error: OperationError) -> Self {
+        Corruption {
+            entry,
+            operation,
+            error,
+        }
+    }
+
+    pub fn id(&self) -> Id {
+        self.entry.id
+    }
+
+    pub fn entry(&self) -> Entry {
+        self.entry
+    }
+
+    pub fn operation(&self) -> &Operation {
+        &self.operation
+    }
+
+    pub fn error(&self) -> &OperationError {
+        &self.error
+    }
+}
@@ -5,7 +5,7 @@ use crate::account::Id;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub(crate) struct Entry {
+pub struct Entry {
     pub id: Id,
     pub height: u64,
 }
@@ -1,9 +1,13 @@
 use doomstack::Doom;
 
-#[derive(Doom)]
-pub(crate) enum OperationError {
+use serde::{Deserialize, Serialize};
+
+#[derive(Doom, Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum OperationError {
     #[doom(description("Overdraft"))]
     Overdraft,
+    #[doom(description("Malformed transfer"))]
+    MalformedTransfer,
     #[doom(description("Unexpected dependency"))]
     UnexpectedDependency,
     #[doom(description("Illegitimate deposit"))]
@@ -2,7 +2,7 @@ use crate::crypto::Identify;
 
 use talk::crypto::primitives::hash::{self, Hash};
 
-pub(crate) type Id = u64;
+pub type Id = u64;
 
 impl Identify for Id {
     fn identifier(&self) -> Hash {
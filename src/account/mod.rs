@@ -3,14 +3,16 @@ mod account_settings;
 mod account_summary;
 mod correct_state;
 mod corrupted_state;
+mod corruption;
 mod entry;
 mod errors;
 mod id;
 mod operation;
+mod outcome;
 mod state;
 mod state_summary;
 
-pub(crate) mod operations;
+pub mod operations;
 
 #[allow(unused_imports)]
 pub(crate) use account::Account;
@@ -19,9 +21,11 @@ pub(crate) use account_settings::AccountSettings;
 pub(crate) use account_summary::AccountSummary;
 pub(crate) use correct_state::CorrectState;
 pub(crate) use corrupted_state::CorruptedState;
-pub(crate) use entry::Entry;
-pub(crate) use errors::OperationError;
-pub(crate) use id::Id;
-pub(crate) use operation::Operation;
+pub use corruption::Corruption;
+pub use entry::Entry;
+pub use errors::OperationError;
+pub use id::Id;
+pub use operation::Operation;
+pub(crate) use outcome::Outcome;
 pub(crate) use state::State;
 pub(crate) use state_summary::StateSummary;
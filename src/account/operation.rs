@@ -1,6 +1,6 @@
 use crate::{
     account::{
-        operations::{Abandon, Deposit, Support, Withdraw},
+        operations::{Abandon, Deposit, Rotate, Support, Transfer, Withdraw},
         Entry, Id,
     },
     crypto::Identify,
@@ -8,16 +8,21 @@ use crate::{
 
 use serde::{Deserialize, Serialize};
 
-use talk::crypto::primitives::hash::{self, Hash};
+use talk::crypto::{
+    primitives::hash::{self, Hash},
+    KeyCard,
+};
 
 use zebra::map::Set;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub(crate) enum Operation {
+pub enum Operation {
     Withdraw(Withdraw),
     Deposit(Deposit),
     Support(Support),
     Abandon(Abandon),
+    Transfer(Transfer),
+    Rotate(Rotate),
 }
 
 impl Operation {
@@ -25,6 +30,16 @@ impl Operation {
         Operation::Withdraw(Withdraw::new(beneficiary, slot, amount))
     }
 
+    /// Builds a `Transfer` from `(beneficiary, slot, amount)` legs.
+    pub fn transfer<L>(legs: L) -> Self
+    where
+        L: IntoIterator<Item = (Id, u64, u64)>,
+    {
+        Operation::Transfer(Transfer::new(legs.into_iter().map(
+            |(beneficiary, slot, amount)| Withdraw::new(beneficiary, slot, amount),
+        )))
+    }
+
     pub fn deposit(withdraw: Entry, deposits: Option<&Set<Entry>>, collect: bool) -> Self {
         Operation::Deposit(Deposit::new(withdraw, deposits, collect))
     }
@@ -37,12 +52,32 @@ impl Operation {
         Operation::Abandon(Abandon::new(motion))
     }
 
+    pub fn rotate(keycard: KeyCard) -> Self {
+        Operation::Rotate(Rotate::new(keycard))
+    }
+
     pub fn dependency(&self) -> Option<Entry> {
         match self {
             Operation::Withdraw(withdraw) => withdraw.dependency(),
             Operation::Deposit(deposit) => deposit.dependency(),
             Operation::Support(support) => support.dependency(),
             Operation::Abandon(abandon) => abandon.dependency(),
+            Operation::Transfer(transfer) => transfer.dependency(),
+            Operation::Rotate(rotate) => rotate.dependency(),
+        }
+    }
+
+    /// Returns the `Withdraw` paying `beneficiary`, i.e., what a `Deposit` of
+    /// `beneficiary` depending on `self` deposits: either `self` (if `self` is a
+    /// `Withdraw` to `beneficiary`) or the leg of `self` paying `beneficiary`
+    /// (if `self` is a `Transfer`).
+    pub fn leg(&self, beneficiary: Id) -> Option<&Withdraw> {
+        match self {
+            Operation::Withdraw(withdraw) if withdraw.beneficiary() == beneficiary => {
+                Some(withdraw)
+            }
+            Operation::Transfer(transfer) => transfer.leg(beneficiary),
+            _ => None,
         }
     }
 }
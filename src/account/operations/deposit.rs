@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 use zebra::map::Set;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub(crate) struct Deposit {
+pub struct Deposit {
     withdraw: Entry,
     exclusion: Option<Set<Entry>>,
     collect: bool,
@@ -1,9 +1,13 @@
 mod abandon;
 mod deposit;
+mod rotate;
 mod support;
+mod transfer;
 mod withdraw;
 
-pub(crate) use abandon::Abandon;
-pub(crate) use deposit::Deposit;
-pub(crate) use support::Support;
-pub(crate) use withdraw::Withdraw;
+pub use abandon::Abandon;
+pub use deposit::Deposit;
+pub use rotate::Rotate;
+pub use support::Support;
+pub use transfer::Transfer;
+pub use withdraw::Withdraw;
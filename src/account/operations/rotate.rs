@@ -0,0 +1,26 @@
+use crate::account::Entry;
+
+use serde::{Deserialize, Serialize};
+
+use talk::crypto::KeyCard;
+
+/// Replaces the `KeyCard` of the account: all `Prepare`s above the height of
+/// the `Rotate` must be signed by `keycard`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rotate {
+    keycard: KeyCard,
+}
+
+impl Rotate {
+    pub fn new(keycard: KeyCard) -> Self {
+        Rotate { keycard }
+    }
+
+    pub fn keycard(&self) -> &KeyCard {
+        &self.keycard
+    }
+
+    pub fn dependency(&self) -> Option<Entry> {
+        None
+    }
+}
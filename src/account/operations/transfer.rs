@@ -0,0 +1,44 @@
+use crate::account::{operations::Withdraw, Entry, Id};
+
+use serde::{Deserialize, Serialize};
+
+/// Pays several beneficiaries at once: each leg is a `Withdraw`, and the
+/// sender is debited the sum of all legs. A `Deposit` depending on a
+/// `Transfer` deposits the (only) leg whose beneficiary is the depositor.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Transfer {
+    legs: Vec<Withdraw>,
+}
+
+impl Transfer {
+    pub fn new<L>(legs: L) -> Self
+    where
+        L: IntoIterator<Item = Withdraw>,
+    {
+        Transfer {
+            legs: legs.into_iter().collect(),
+        }
+    }
+
+    pub fn legs(&self) -> &[Withdraw] {
+        &self.legs
+    }
+
+    /// Returns the leg paying `beneficiary` (if any).
+    pub fn leg(&self, beneficiary: Id) -> Option<&Withdraw> {
+        self.legs
+            .iter()
+            .find(|leg| leg.beneficiary() == beneficiary)
+    }
+
+    /// Returns the sum of all legs (`None` on overflow).
+    pub fn amount(&self) -> Option<u64> {
+        self.legs
+            .iter()
+            .try_fold(0u64, |amount, leg| amount.checked_add(leg.amount()))
+    }
+
+    pub fn dependency(&self) -> Option<Entry> {
+        None
+    }
+}
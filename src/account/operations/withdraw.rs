@@ -3,7 +3,7 @@ use crate::account::{Entry, Id};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub(crate) struct Withdraw {
+pub struct Withdraw {
     beneficiary: Id,
     slot: u64,
     amount: u64,
@@ -0,0 +1,11 @@
+use crate::account::OperationError;
+
+use serde::{Deserialize, Serialize};
+
+/// The result of applying a `Payload` to its `Account` for the first time.
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) enum Outcome {
+    Success,
+    // The `Account` was corrupted by the `OperationError` (of this or an earlier `Payload`)
+    Failure(OperationError),
+}
@@ -1,11 +1,11 @@
 use crate::{
-    account::{CorrectState, CorruptedState, StateSummary},
+    account::{CorrectState, CorruptedState, Corruption, StateSummary},
     crypto::Identify,
 };
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) enum State {
     Correct(CorrectState),
     Corrupted(CorruptedState),
@@ -33,9 +33,9 @@ impl State {
         }
     }
 
-    pub fn corrupt(&mut self) {
+    pub fn corrupt(&mut self, corruption: Corruption) {
         if let State::Correct(state) = self {
-            *self = State::Corrupted(state.corrupted());
+            *self = State::Corrupted(state.corrupted(corruption));
         }
     }
 }
@@ -0,0 +1,29 @@
+use carbon::node::{self, BrokerKind};
+
+use std::{env, path::PathBuf, process};
+
+#[tokio::main]
+async fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+
+    let (kind, path) = match args.as_slice() {
+        [kind, path] => (kind, PathBuf::from(path)),
+        _ => {
+            eprintln!("Usage: carbon-broker <signup|prepare|commit|pipeline> <config.toml>");
+            process::exit(2);
+        }
+    };
+
+    let kind = match kind.parse::<BrokerKind>() {
+        Ok(kind) => kind,
+        Err(error) => {
+            eprintln!("{:?}", error);
+            process::exit(2);
+        }
+    };
+
+    if let Err(error) = node::run_broker(kind, &path).await {
+        eprintln!("{:?}", error);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,23 @@
+use std::{env, path::PathBuf, process};
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+
+    let (count, directory) = match args.as_slice() {
+        [count, directory] => match count.parse::<usize>() {
+            Ok(count) => (count, PathBuf::from(directory)),
+            Err(_) => usage(),
+        },
+        _ => usage(),
+    };
+
+    if let Err(error) = carbon::node::generate_keys(count, &directory) {
+        eprintln!("{:?}", error);
+        process::exit(1);
+    }
+}
+
+fn usage() -> ! {
+    eprintln!("Usage: carbon-keygen <count> <directory>");
+    process::exit(2);
+}
@@ -0,0 +1,17 @@
+use std::{env, path::PathBuf, process};
+
+#[tokio::main]
+async fn main() {
+    let path = match env::args().nth(1) {
+        Some(path) => PathBuf::from(path),
+        None => {
+            eprintln!("Usage: carbon-replica <config.toml>");
+            process::exit(2);
+        }
+    };
+
+    if let Err(error) = carbon::node::run_replica(&path).await {
+        eprintln!("{:?}", error);
+        process::exit(1);
+    }
+}
@@ -1,5 +1,8 @@
 use crate::{
-    brokers::commit::{Broker, BrokerFailure, Brokerage, Submission, UnzippedBrokerages},
+    brokers::commit::{
+        broker_settings::BrokerTaskSettings, Broker, BrokerFailure, Brokerage, Submission,
+        UnzippedBrokerages,
+    },
     commit::CompletionProof,
     data::PingBoard,
     processing::messages::CommitRequest,
@@ -25,11 +28,12 @@ enum PublishError {
 }
 
 impl Broker {
-    pub(in crate::brokers::commit::broker) async fn broker(
+    pub(in crate::brokers) async fn broker(
         view: View,
         ping_board: PingBoard,
         connector: Arc<SessionConnector>,
         brokerages: Vec<Brokerage>,
+        settings: BrokerTaskSettings,
     ) {
         // Unzip `brokerages` into its components
 
@@ -45,17 +49,27 @@ impl Broker {
 
         // Orchestrate submission to obtain `BatchCompletion`
 
-        let batch_completion =
-            Broker::orchestrate(view.clone(), ping_board, connector.clone(), submission)
-                .await
-                .map_err(|_| BrokerFailure::Error);
+        let batch_completion = Broker::orchestrate(
+            view.clone(),
+            ping_board,
+            connector.clone(),
+            submission,
+            settings,
+        )
+        .await
+        .map_err(|_| BrokerFailure::Error);
 
-        // Dispatch appropriate `CompletionProof` to all `serve` tasks
+        // Dispatch appropriate `CompletionProof` to all `serve` tasks (deferred
+        // `Payload`s were not applied, and must be submitted again)
 
         for (index, completion_inlet) in completion_inlets.into_iter().enumerate() {
-            let completion_proof = batch_completion.clone().map(|batch_completion| {
+            let completion_proof = batch_completion.clone().and_then(|batch_completion| {
+                if batch_completion.defers(payloads.items()[index].id()) {
+                    return Err(BrokerFailure::Throttle { retry_after: None });
+                }
+
                 let inclusion = payloads.prove(index);
-                CompletionProof::new(batch_completion, inclusion)
+                Ok(CompletionProof::new(batch_completion, inclusion))
             });
 
             let _ = completion_inlet.send(completion_proof);
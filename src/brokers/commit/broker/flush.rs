@@ -1,10 +1,10 @@
 use crate::{
-    brokers::commit::{Broker, BrokerFailure, Brokerage},
+    brokers::commit::{broker_settings::BrokerTaskSettings, Broker, BrokerFailure, Brokerage},
     data::{PingBoard, Sponge},
     view::View,
 };
 
-use std::sync::Arc;
+use std::{sync::Arc, time::Instant};
 
 use talk::{net::SessionConnector, sync::fuse::Fuse};
 
@@ -14,6 +14,7 @@ impl Broker {
         brokerage_sponge: Arc<Sponge<Brokerage>>,
         ping_board: PingBoard,
         connector: Arc<SessionConnector>,
+        settings: BrokerTaskSettings,
     ) {
         let fuse = Fuse::new();
 
@@ -26,9 +27,13 @@ impl Broker {
             let view = view.clone();
             let ping_board = ping_board.clone();
             let connector = connector.clone();
+            let brokerage_sponge = brokerage_sponge.clone();
+            let settings = settings.clone();
 
             fuse.spawn(async move {
-                Broker::broker(view, ping_board, connector, brokerages).await;
+                let start = Instant::now();
+                Broker::broker(view, ping_board, connector, brokerages, settings).await;
+                brokerage_sponge.observe(start.elapsed());
             });
         }
     }
@@ -53,7 +58,7 @@ impl Broker {
                 if Some(brokerage.request.id()) == previous {
                     let _ = brokerage
                         .completion_inlet
-                        .send(Err(BrokerFailure::Throttle));
+                        .send(Err(BrokerFailure::Throttle { retry_after: None }));
                     None
                 } else {
                     previous = Some(brokerage.request.id());
@@ -1,55 +1,124 @@
 use crate::{
-    brokers::commit::{brokerage::Brokerage, Broker, Request},
-    data::Sponge,
+    account::{Entry, Id},
+    brokers::commit::{
+        broker_settings::FrontendTaskSettings, brokerage::Brokerage, Broker, BrokerFailure,
+        Outcome, Request,
+    },
+    commit::CompletionProof,
+    data::{Admission, ResultCache, Sponge},
     discovery::Client,
+    transport::{Connection, Transport},
 };
 
 use doomstack::{here, Doom, ResultExt, Top};
 
-use std::sync::Arc;
+use futures::future;
 
-use talk::{net::PlainConnection, sync::fuse::Fuse};
+use std::{sync::Arc, time::Duration};
 
-use tokio::{net::TcpListener, sync::oneshot};
+use talk::{crypto::KeyChain, sync::fuse::Fuse};
+
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::{
+        oneshot::{self, Receiver},
+        watch::Sender,
+    },
+    time,
+};
+
+type Results = ResultCache<Entry, Arc<Outcome>>;
 
 #[derive(Doom)]
 enum ServeError {
+    #[doom(description("Failed to accept the connection"))]
+    AcceptFailed,
     #[doom(description("Connection error"))]
     ConnectionError,
     #[doom(description("Request invalid"))]
     RequestInvalid,
     #[doom(description("`Brokerage` forfeited (most likely, the `Broker` is shutting down)"))]
-    #[doom(wrap(request_forfeited))]
-    BrokerageForfeited { source: oneshot::error::RecvError },
+    BrokerageForfeited,
 }
 
 impl Broker {
     pub(in crate::brokers::commit::broker) async fn listen(
+        keychain: KeyChain,
         discovery: Arc<Client>,
         brokerage_sponge: Arc<Sponge<Brokerage>>,
         listener: TcpListener,
+        settings: FrontendTaskSettings,
     ) {
+        let keychain = Arc::new(keychain);
+        let results = Arc::new(Results::new(settings.result_cache_capacity));
+        let admission = Arc::new(Admission::new(settings.admission_settings.clone()));
+
         let fuse = Fuse::new();
 
         loop {
             if let Ok((stream, _)) = listener.accept().await {
-                let connection: PlainConnection = stream.into();
+                // Connections in excess of `max_connections` are throttled, then closed
+                // unserved (a refusal outlasting `retry_after` is abandoned: by then,
+                // the client might as well retry)
+
+                let connection_permit = match admission.connect() {
+                    Ok(permit) => permit,
+                    Err(retry_after) => {
+                        let keychain = keychain.clone();
+                        let transport = settings.transport;
+
+                        fuse.spawn(async move {
+                            let refusal =
+                                Broker::refuse(keychain.as_ref(), stream, transport, retry_after);
+
+                            let _ = time::timeout(retry_after, refusal).await;
+                        });
+
+                        continue;
+                    }
+                };
 
+                let keychain = keychain.clone();
                 let discovery = discovery.clone();
                 let brokerage_sponge = brokerage_sponge.clone();
+                let results = results.clone();
+                let admission = admission.clone();
+                let settings = settings.clone();
 
                 fuse.spawn(async move {
-                    let _ = Broker::serve(discovery, brokerage_sponge, connection).await;
+                    let _ = Broker::serve(
+                        keychain.as_ref(),
+                        discovery,
+                        brokerage_sponge,
+                        results,
+                        admission,
+                        stream,
+                        settings,
+                    )
+                    .await;
+
+                    drop(connection_permit);
                 });
             }
         }
     }
 
     async fn serve(
+        keychain: &KeyChain,
         discovery: Arc<Client>,
         brokerage_sponge: Arc<Sponge<Brokerage>>,
-        mut connection: PlainConnection,
+        results: Arc<Results>,
+        admission: Arc<Admission<Id>>,
+        stream: TcpStream,
+        settings: FrontendTaskSettings,
     ) -> Result<(), Top<ServeError>> {
+        // `Request`s carry their own proof of authorization (the `Commit` of a
+        // signed `Prepare`): the client's `KeyCard`, if any, is irrelevant
+
+        let (mut connection, _) = Connection::accept(stream, keychain, settings.transport)
+            .await
+            .pot(ServeError::AcceptFailed, here!())?;
+
         // Receive and validate `Request`
 
         let request = connection
@@ -61,7 +130,39 @@ impl Broker {
             .validate(discovery.as_ref())
             .pot(ServeError::RequestInvalid, here!())?;
 
-        // Build and submit `Brokerage` to `brokerage_sponge`
+        let entry = request.commit.payload().entry();
+
+        // Throttle clients submitting `Request`s in excess of their rate
+
+        if let Err(retry_after) = admission.submit(entry.id) {
+            return Broker::throttle(connection, retry_after).await;
+        }
+
+        // If a `Request` for the same `Entry` and commitment was previously
+        // submitted (e.g., by a client whose connection dropped), attach to
+        // its `Outcome`. Otherwise, build and submit a new `Brokerage`.
+
+        let commitment = request.commit.payload().prepare().commitment();
+
+        let (outcome, outcome_inlet) = Outcome::new(commitment);
+
+        let (outcome, fresh) = results.attach_or_insert(entry, Arc::new(outcome), |cached| {
+            cached.commitment == commitment
+        });
+
+        if !fresh {
+            return Broker::attend(connection, outcome).await;
+        }
+
+        // Throttle new `Brokerage`s in excess of `max_brokerages` (see `prepare::Broker`)
+
+        let brokerage_permit = match admission.broker() {
+            Ok(permit) => permit,
+            Err(retry_after) => {
+                results.remove_if(&entry, |cached| Arc::ptr_eq(cached, &outcome));
+                return Broker::throttle(connection, retry_after).await;
+            }
+        };
 
         let (completion_inlet, completion_outlet) = oneshot::channel();
 
@@ -72,13 +173,97 @@ impl Broker {
 
         brokerage_sponge.push(brokerage);
 
+        // The `Brokerage` is tracked to completion even if the client's connection drops
+
+        let tracked = outcome.clone();
+
+        let track = async {
+            Broker::track(
+                results.as_ref(),
+                entry,
+                tracked,
+                outcome_inlet,
+                completion_outlet,
+            )
+            .await;
+
+            // The `Brokerage` is no longer outstanding
+            drop(brokerage_permit);
+        };
+
+        let attend = Broker::attend(connection, outcome);
+
+        let (_, result) = future::join(track, attend).await;
+        result
+    }
+
+    async fn refuse(
+        keychain: &KeyChain,
+        stream: TcpStream,
+        transport: Transport,
+        retry_after: Duration,
+    ) -> Result<(), Top<ServeError>> {
+        let (connection, _) = Connection::accept(stream, keychain, transport)
+            .await
+            .pot(ServeError::AcceptFailed, here!())?;
+
+        Broker::throttle(connection, retry_after).await
+    }
+
+    async fn throttle(
+        mut connection: Connection,
+        retry_after: Duration,
+    ) -> Result<(), Top<ServeError>> {
+        let failure = BrokerFailure::Throttle {
+            retry_after: Some(retry_after),
+        };
+
+        connection
+            .send::<Result<CompletionProof, BrokerFailure>>(&Err(failure))
+            .await
+            .pot(ServeError::ConnectionError, here!())?;
+
+        // Throttling is not a shortcoming of `serve`, and should not result in an `Err`
+        Ok(())
+    }
+
+    async fn track(
+        results: &Results,
+        entry: Entry,
+        outcome: Arc<Outcome>,
+        outcome_inlet: Sender<Option<Result<CompletionProof, BrokerFailure>>>,
+        completion_outlet: Receiver<Result<CompletionProof, BrokerFailure>>,
+    ) {
+        // Forward `CompletionProof` from the `broker` task to all attached
+        // `serve` tasks (`outcome_inlet` is dropped on forfeit)
+
+        let completed = match completion_outlet.await {
+            Ok(completion) => {
+                let completed = completion.is_ok();
+                let _ = outcome_inlet.send(Some(completion));
+
+                completed
+            }
+            Err(_) => false,
+        };
+
+        // Failed or forfeited `Brokerage`s are not cached: resubmissions are brokered anew
+
+        if !completed {
+            results.remove_if(&entry, |cached| Arc::ptr_eq(cached, &outcome));
+        }
+    }
+
+    async fn attend(
+        mut connection: Connection,
+        outcome: Arc<Outcome>,
+    ) -> Result<(), Top<ServeError>> {
         // Wait for `Completion` from `broker` task
 
-        let completion = completion_outlet
+        let completion = outcome
+            .completion()
             .await
-            .map_err(ServeError::request_forfeited)
-            .map_err(Doom::into_top)
-            .spot(here!())?;
+            .ok_or(ServeError::BrokerageForfeited.into_top().spot(here!()))?;
 
         // Send `commit` to the served client (note that `commit` is a `Result<Completion, Failure>`)
 
@@ -1,4 +1,5 @@
 use crate::{
+    brokers::commit::{BrokerSettings, BrokerSettingsComponents},
     crypto::Identify,
     data::{PingBoard, Sponge},
     discovery::Client,
@@ -10,6 +11,7 @@ use doomstack::{here, Doom, ResultExt, Top};
 use std::{net::SocketAddr, sync::Arc};
 
 use talk::{
+    crypto::{Identity, KeyChain},
     link::context::ConnectDispatcher,
     net::{Connector, SessionConnector},
     sync::fuse::Fuse,
@@ -22,6 +24,7 @@ use tokio::{
 
 pub(crate) struct Broker {
     address: SocketAddr,
+    identity: Identity,
     _fuse: Fuse,
 }
 
@@ -34,15 +37,24 @@ pub(crate) enum BrokerError {
 
 impl Broker {
     pub async fn new<A, C>(
+        keychain: KeyChain,
         discovery: Arc<Client>,
         view: View,
         address: A,
         connector: C,
+        settings: BrokerSettings,
     ) -> Result<Self, Top<BrokerError>>
     where
         A: ToSocketAddrs,
         C: Connector,
     {
+        let BrokerSettingsComponents {
+            frontend: frontend_settings,
+            flush: flush_settings,
+            broker: broker_settings,
+            ping: ping_settings,
+        } = settings.into_components();
+
         let listener = TcpListener::bind(address)
             .await
             .map_err(BrokerError::initialize_failed)
@@ -59,9 +71,10 @@ impl Broker {
         let context = format!("{:?}::processor::commit", view.identifier());
         let connector = Arc::new(SessionConnector::new(dispatcher.register(context)));
 
-        let brokerage_sponge = Arc::new(Sponge::new(Default::default())); // TODO: Add settings
+        let brokerage_sponge = Arc::new(Sponge::new(flush_settings.brokerage_sponge_settings));
         let ping_board = PingBoard::new(&view);
 
+        let identity = keychain.keycard().identity();
         let fuse = Fuse::new();
 
         {
@@ -69,7 +82,14 @@ impl Broker {
             let brokerage_sponge = brokerage_sponge.clone();
 
             fuse.spawn(async move {
-                Broker::listen(discovery, brokerage_sponge, listener).await;
+                Broker::listen(
+                    keychain,
+                    discovery,
+                    brokerage_sponge,
+                    listener,
+                    frontend_settings,
+                )
+                .await;
             });
         }
 
@@ -79,19 +99,30 @@ impl Broker {
             let connector = connector.clone();
 
             fuse.spawn(async move {
-                Broker::flush(view, brokerage_sponge, ping_board, connector).await;
+                Broker::flush(
+                    view,
+                    brokerage_sponge,
+                    ping_board,
+                    connector,
+                    broker_settings,
+                )
+                .await;
             });
         }
 
         for replica in view.members().keys().copied() {
             let ping_board = ping_board.clone();
             let connector = connector.clone();
+            let ping_settings = ping_settings.clone();
 
-            fuse.spawn(async move { Broker::ping(ping_board, connector, replica).await });
+            fuse.spawn(
+                async move { Broker::ping(ping_board, connector, replica, ping_settings).await },
+            );
         }
 
         Ok(Broker {
             address,
+            identity,
             _fuse: fuse,
         })
     }
@@ -99,6 +130,10 @@ impl Broker {
     pub fn address(&self) -> SocketAddr {
         self.address
     }
+
+    pub fn identity(&self) -> Identity {
+        self.identity
+    }
 }
 
 mod broker;
@@ -112,7 +147,7 @@ mod tests {
     use std::time::Duration;
 
     use crate::{
-        account::{Entry, Operation},
+        account::{AccountSettings, Entry, Operation},
         brokers::{
             commit::{BrokerFailure, Request},
             prepare::{
@@ -123,7 +158,8 @@ mod tests {
             test::System,
         },
         commit::{Commit, CommitProof, Completion, CompletionProof, Payload},
-        prepare::BatchCommit,
+        prepare::{BatchCommit, Continuity},
+        processing::{processor_settings::Commit as CommitSettings, ProcessorSettings},
         signup::{IdAssignment, IdRequest, SignupSettings},
     };
 
@@ -141,6 +177,7 @@ mod tests {
             mut signup_brokers,
             mut prepare_brokers,
             mut commit_brokers,
+            ..
         } = System::setup(4, 1, 1, 1).await;
 
         let client_keychain = KeyChain::random();
@@ -192,6 +229,8 @@ mod tests {
         let request = PrepareRequest::new(
             &client_keychain,
             assignment.clone(),
+            None,
+            None,
             prepare.height(),
             prepare.commitment(),
         );
@@ -256,11 +295,14 @@ mod tests {
 
         let prepare = payload.prepare();
 
-        // Prepare
+        // Prepare (members that did not complete height 1 yet need a `Continuity`
+        // to establish that height 2 is within their pipeline window)
 
         let request = PrepareRequest::new(
             &client_keychain,
             assignment.clone(),
+            None,
+            Some(Continuity::Completion(withdrawal.clone())),
             prepare.height(),
             prepare.commitment(),
         );
@@ -315,4 +357,128 @@ mod tests {
 
         tokio::time::sleep(Duration::from_secs(10)).await;
     }
+
+    #[tokio::test]
+    async fn initial_balance() {
+        let settings = ProcessorSettings {
+            commit: CommitSettings {
+                account_settings: AccountSettings {
+                    initial_balance: 100,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let System {
+            view,
+            discovery_server: _discovery_server,
+            discovery_client,
+            processors,
+            mut signup_brokers,
+            mut prepare_brokers,
+            mut commit_brokers,
+            ..
+        } = System::setup_with_settings(4, 1, 1, 1, settings).await;
+
+        let client_keychain = KeyChain::random();
+
+        // Brokers
+
+        let signup_broker = signup_brokers.remove(0);
+        let prepare_broker = prepare_brokers.remove(0);
+        let commit_broker = commit_brokers.remove(0);
+
+        // Signup
+
+        let allocator_identity = processors[0].0.keycard().identity();
+
+        let request = IdRequest::new(
+            &client_keychain,
+            &view,
+            allocator_identity,
+            SignupSettings::default().work_difficulty,
+        );
+
+        let stream = TcpStream::connect(signup_broker.address()).await.unwrap();
+        let mut connection: PlainConnection = stream.into();
+
+        connection.send(&request).await.unwrap();
+
+        let assignment = connection
+            .receive::<Result<IdAssignment, SignupBrokerFailure>>()
+            .await
+            .unwrap()
+            .unwrap();
+
+        // Withdraw (would overdraft with the default `initial_balance`)
+
+        let payload = Payload::new(
+            Entry {
+                id: assignment.id(),
+                height: 1,
+            },
+            Operation::withdraw(assignment.id(), 0, 60),
+        );
+
+        let prepare = payload.prepare();
+
+        // Prepare
+
+        let request = PrepareRequest::new(
+            &client_keychain,
+            assignment.clone(),
+            None,
+            None,
+            prepare.height(),
+            prepare.commitment(),
+        );
+
+        let stream = TcpStream::connect(prepare_broker.address()).await.unwrap();
+        let mut connection: PlainConnection = stream.into();
+
+        connection.send(&request).await.unwrap();
+
+        let inclusion = connection
+            .receive::<Result<PrepareInclusion, PrepareBrokerFailure>>()
+            .await
+            .unwrap()
+            .unwrap();
+
+        let reduction_shard = inclusion
+            .certify_reduction(&client_keychain, request.prepare())
+            .unwrap();
+
+        connection.send(&reduction_shard).await.unwrap();
+
+        let batch_commit = connection
+            .receive::<Result<BatchCommit, PrepareBrokerFailure>>()
+            .await
+            .unwrap()
+            .unwrap();
+
+        let commit_proof = CommitProof::new(batch_commit, inclusion.proof);
+        let commit = Commit::new(commit_proof, payload.clone());
+
+        // Commit
+
+        let request = Request::new(commit, None);
+
+        let stream = TcpStream::connect(commit_broker.address()).await.unwrap();
+        let mut connection: PlainConnection = stream.into();
+
+        connection.send(&request).await.unwrap();
+
+        let completion_proof = connection
+            .receive::<Result<CompletionProof, BrokerFailure>>()
+            .await
+            .unwrap()
+            .unwrap();
+
+        let withdrawal = Completion::new(completion_proof, payload);
+
+        // `withdrawal` is not in its `BatchCompletion`'s exceptions
+        withdrawal.validate(discovery_client.as_ref()).unwrap();
+    }
 }
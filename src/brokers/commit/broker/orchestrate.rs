@@ -1,5 +1,5 @@
 use crate::{
-    brokers::commit::{submission::Submission, Broker},
+    brokers::commit::{broker_settings::BrokerTaskSettings, submission::Submission, Broker},
     commit::{
         BatchCompletion, BatchCompletionAggregator, BatchCompletionShard, CommitProof, Completion,
         WitnessStatement,
@@ -12,7 +12,7 @@ use crate::{
 
 use doomstack::{here, Doom, ResultExt, Top};
 
-use std::{collections::HashMap, sync::Arc, time::Duration};
+use std::{collections::HashMap, sync::Arc};
 
 use talk::{
     crypto::{
@@ -56,6 +56,7 @@ struct CompletionCollector {
     view: View,
     root: Hash,
     aggregator: BatchCompletionAggregator,
+    shards: usize,
     errors: usize,
 }
 
@@ -97,6 +98,7 @@ impl Broker {
         ping_board: PingBoard,
         connector: Arc<SessionConnector>,
         submission: Submission,
+        settings: BrokerTaskSettings,
     ) -> Result<BatchCompletion, Top<OrchestrateError>> {
         // Submit a `submit` slave for each replica in `view`
 
@@ -149,7 +151,7 @@ impl Broker {
         // Wait (or timeout) for the fastest plurality of slaves to produce witness shards
 
         let _ = time::timeout(
-            Duration::from_secs(1), // TODO: Add settings
+            settings.optimistic_witness_timeout,
             witness_collector.progress(&mut update_outlet),
         )
         .await;
@@ -483,6 +485,7 @@ impl CompletionCollector {
             view,
             root,
             aggregator,
+            shards: 0,
             errors,
         }
     }
@@ -492,7 +495,10 @@ impl CompletionCollector {
     }
 
     fn failed(&self) -> bool {
+        // Shards (correctly) disagreeing on exceptions or deferred `Id`s
+        // might prevent a quorum even if every member responds
         self.errors >= self.view.plurality()
+            || self.shards + self.errors >= self.view.members().len()
     }
 
     async fn run(
@@ -506,6 +512,7 @@ impl CompletionCollector {
                 (replica, Update::CompletionShard(shard)) => {
                     let keycard = self.view.members().get(&replica).unwrap().clone();
                     self.aggregator.add(&keycard, shard);
+                    self.shards += 1;
                 }
                 (_, Update::Error) => {
                     self.errors += 1;
@@ -1,5 +1,5 @@
 use crate::{
-    brokers::commit::Broker,
+    brokers::commit::{broker_settings::PingTaskSettings, Broker},
     data::PingBoard,
     processing::messages::{CommitRequest, CommitResponse},
 };
@@ -30,6 +30,7 @@ impl Broker {
         board: PingBoard,
         connector: Arc<SessionConnector>,
         replica: Identity,
+        settings: PingTaskSettings,
     ) {
         loop {
             let start = Instant::now();
@@ -65,7 +66,7 @@ impl Broker {
             let ping = ping.unwrap_or(Duration::MAX);
             board.submit(replica, ping);
 
-            time::sleep(Duration::from_secs(60)).await; // TODO: Add settings
+            time::sleep(settings.ping_interval).await;
         }
     }
 }
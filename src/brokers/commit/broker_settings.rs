@@ -0,0 +1,89 @@
+use crate::{
+    data::{AdmissionSettings, SpongeSettings},
+    transport::Transport,
+};
+
+use serde::Deserialize;
+
+use std::time::Duration;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub(crate) struct BrokerSettings {
+    pub transport: Transport,
+    pub result_cache_capacity: usize,
+
+    pub admission_settings: AdmissionSettings,
+
+    pub brokerage_sponge_settings: SpongeSettings,
+
+    pub optimistic_witness_timeout: Duration,
+
+    pub ping_interval: Duration,
+}
+
+pub(in crate::brokers::commit) struct BrokerSettingsComponents {
+    pub frontend: FrontendTaskSettings,
+    pub flush: FlushTaskSettings,
+    pub broker: BrokerTaskSettings,
+    pub ping: PingTaskSettings,
+}
+#[derive(Debug, Clone)]
+pub(in crate::brokers::commit) struct FrontendTaskSettings {
+    pub transport: Transport,
+    pub result_cache_capacity: usize,
+    pub admission_settings: AdmissionSettings,
+}
+
+#[derive(Debug, Clone)]
+pub(in crate::brokers::commit) struct FlushTaskSettings {
+    pub brokerage_sponge_settings: SpongeSettings,
+}
+
+#[derive(Debug, Clone)]
+pub(in crate::brokers) struct BrokerTaskSettings {
+    pub optimistic_witness_timeout: Duration,
+}
+
+#[derive(Debug, Clone)]
+pub(in crate::brokers::commit) struct PingTaskSettings {
+    pub ping_interval: Duration,
+}
+
+impl BrokerSettings {
+    pub(in crate::brokers::commit) fn into_components(self) -> BrokerSettingsComponents {
+        BrokerSettingsComponents {
+            frontend: FrontendTaskSettings {
+                transport: self.transport,
+                result_cache_capacity: self.result_cache_capacity,
+                admission_settings: self.admission_settings,
+            },
+            flush: FlushTaskSettings {
+                brokerage_sponge_settings: self.brokerage_sponge_settings,
+            },
+            broker: BrokerTaskSettings {
+                optimistic_witness_timeout: self.optimistic_witness_timeout,
+            },
+            ping: PingTaskSettings {
+                ping_interval: self.ping_interval,
+            },
+        }
+    }
+}
+
+impl Default for BrokerSettings {
+    fn default() -> Self {
+        BrokerSettings {
+            transport: Transport::Secure,
+            result_cache_capacity: 65536,
+
+            admission_settings: Default::default(),
+
+            brokerage_sponge_settings: Default::default(),
+
+            optimistic_witness_timeout: Duration::from_secs(1),
+
+            ping_interval: Duration::from_secs(60),
+        }
+    }
+}
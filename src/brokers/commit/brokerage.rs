@@ -8,7 +8,7 @@ use tokio::sync::oneshot::Sender;
 
 type CompletionInlet = Sender<Result<CompletionProof, BrokerFailure>>;
 
-pub(in crate::brokers::commit) struct Brokerage {
+pub(in crate::brokers) struct Brokerage {
     pub request: Request,
     pub completion_inlet: CompletionInlet,
 }
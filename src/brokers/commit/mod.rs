@@ -1,14 +1,22 @@
 mod broker;
 mod broker_failure;
+mod broker_settings;
 mod brokerage;
+mod outcome;
 mod request;
 mod submission;
 
-use brokerage::{Brokerage, UnzippedBrokerages};
+use broker_settings::BrokerSettingsComponents;
+use brokerage::UnzippedBrokerages;
+use outcome::Outcome;
 use submission::Submission;
 
+pub(in crate::brokers) use broker_settings::BrokerTaskSettings;
+pub(in crate::brokers) use brokerage::Brokerage;
+
 #[allow(unused_imports)]
 pub(crate) use broker::Broker;
 
 pub(crate) use broker_failure::BrokerFailure;
+pub(crate) use broker_settings::BrokerSettings;
 pub(crate) use request::Request;
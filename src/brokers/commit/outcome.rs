@@ -0,0 +1,45 @@
+use crate::{brokers::commit::BrokerFailure, commit::CompletionProof};
+
+use talk::crypto::primitives::hash::Hash;
+
+use tokio::sync::watch::{self, Receiver, Sender};
+
+type CompletionInlet = Sender<Option<Result<CompletionProof, BrokerFailure>>>;
+type CompletionOutlet = Receiver<Option<Result<CompletionProof, BrokerFailure>>>;
+
+/// The (possibly pending) outcome of a `Brokerage`, shared by all `serve`
+/// tasks attached to it (i.e., the original submission and its resubmissions).
+pub(in crate::brokers::commit) struct Outcome {
+    pub commitment: Hash,
+    completion_outlet: CompletionOutlet,
+}
+
+impl Outcome {
+    pub fn new(commitment: Hash) -> (Self, CompletionInlet) {
+        let (completion_inlet, completion_outlet) = watch::channel(None);
+
+        let outcome = Outcome {
+            commitment,
+            completion_outlet,
+        };
+
+        (outcome, completion_inlet)
+    }
+
+    /// Waits for the `Brokerage`'s `CompletionProof` (`None` if the `Brokerage` was forfeited).
+    pub async fn completion(&self) -> Option<Result<CompletionProof, BrokerFailure>> {
+        let mut completion_outlet = self.completion_outlet.clone();
+
+        loop {
+            let completion = completion_outlet.borrow().clone();
+
+            if completion.is_some() {
+                return completion;
+            }
+
+            // If `completion_outlet.changed()` fails, the corresponding inlet
+            // was dropped without ever being fed a value
+            completion_outlet.changed().await.ok()?;
+        }
+    }
+}
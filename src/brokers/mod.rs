@@ -1,4 +1,5 @@
 pub(crate) mod commit;
+pub(crate) mod pipeline;
 pub(crate) mod prepare;
 pub(crate) mod signup;
 
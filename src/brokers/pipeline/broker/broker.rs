@@ -0,0 +1,172 @@
+use crate::{
+    brokers::{
+        commit::{
+            Broker as CommitBroker, BrokerFailure as CommitBrokerFailure,
+            Brokerage as CommitBrokerage, Request as CommitRequest,
+        },
+        pipeline::{broker_settings::BrokerTaskSettings, Broker, Brokerage, Request},
+        prepare::{
+            Broker as PrepareBroker, BrokerFailure as PrepareBrokerFailure,
+            Brokerage as PrepareBrokerage, Reduction,
+        },
+    },
+    commit::{Commit, CommitProof, Completion, CompletionProof, Payload},
+    data::PingBoard,
+    discovery::Client,
+    prepare::BatchCommit,
+    view::View,
+};
+
+use futures::future;
+
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use talk::net::SessionConnector;
+
+use tokio::sync::oneshot::{self, Receiver, Sender};
+
+// Everything needed to move a `Brokerage` from prepare to commit
+struct Relay {
+    payload: Payload,
+    dependency: Option<Completion>,
+    reduction_outlet: Receiver<Result<Reduction, PrepareBrokerFailure>>,
+    reduction_inlet: Sender<Result<Reduction, PrepareBrokerFailure>>,
+    commit_outlet: Receiver<Result<BatchCommit, PrepareBrokerFailure>>,
+    completion_inlet: Sender<Result<CompletionProof, CommitBrokerFailure>>,
+}
+
+impl Broker {
+    pub(in crate::brokers::pipeline::broker) async fn broker(
+        discovery: Arc<Client>,
+        view: View,
+        ping_board: PingBoard,
+        prepare_connector: Arc<SessionConnector>,
+        commit_connector: Arc<SessionConnector>,
+        query_connector: Arc<SessionConnector>,
+        brokerages: Vec<Brokerage>,
+        settings: BrokerTaskSettings,
+    ) -> Duration {
+        // Split each element of `brokerages` into a prepare `Brokerage` (whose
+        // `Reduction` and `BatchCommit` are intercepted) and a `Relay`
+
+        let mut prepare_brokerages = Vec::with_capacity(brokerages.len());
+        let mut relays = Vec::with_capacity(brokerages.len());
+
+        for brokerage in brokerages {
+            let Brokerage {
+                request:
+                    Request {
+                        prepare,
+                        payload,
+                        dependency,
+                    },
+                reduction_inlet,
+                completion_inlet,
+            } = brokerage;
+
+            let (relay_inlet, reduction_outlet) = oneshot::channel();
+            let (commit_inlet, commit_outlet) = oneshot::channel();
+
+            prepare_brokerages.push(PrepareBrokerage {
+                request: prepare,
+                reduction_inlet: relay_inlet,
+                commit_inlet,
+            });
+
+            relays.push(Relay {
+                payload,
+                dependency,
+                reduction_outlet,
+                reduction_inlet,
+                commit_outlet,
+                completion_inlet,
+            });
+        }
+
+        // Orchestrate prepare, then commit the resulting batch as a whole (returning
+        // the time spent orchestrating replicas, as opposed to waiting on clients)
+
+        let prepare = PrepareBroker::broker(
+            discovery,
+            view.clone(),
+            ping_board.clone(),
+            prepare_connector,
+            query_connector,
+            prepare_brokerages,
+            settings.prepare,
+        );
+
+        let commit = async {
+            // `Relay`s resolve together, as the prepare `broker` task dispatches
+            // its (unique) `BatchCommit` to all prepare `Brokerage`s at once
+            let commit_brokerages = future::join_all(relays.into_iter().map(Broker::relay))
+                .await
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>();
+
+            if commit_brokerages.is_empty() {
+                return Duration::ZERO;
+            }
+
+            let start = Instant::now();
+
+            CommitBroker::broker(
+                view.clone(),
+                ping_board.clone(),
+                commit_connector,
+                commit_brokerages,
+                settings.commit,
+            )
+            .await;
+
+            start.elapsed()
+        };
+
+        let (prepare_latency, commit_latency) = future::join(prepare, commit).await;
+        prepare_latency + commit_latency
+    }
+
+    async fn relay(relay: Relay) -> Option<CommitBrokerage> {
+        let Relay {
+            payload,
+            dependency,
+            reduction_outlet,
+            reduction_inlet,
+            commit_outlet,
+            completion_inlet,
+        } = relay;
+
+        // Forward `Reduction` to the appropriate `serve` task, retaining a copy of
+        // the `Inclusion` proof (needed to later build a `CommitProof`). If the
+        // prepare `broker` task fails, it does not produce a `BatchCommit`.
+
+        let reduction = reduction_outlet.await.ok()?;
+
+        let proof = reduction
+            .as_ref()
+            .ok()
+            .map(|reduction| reduction.inclusion.proof.clone());
+
+        let _ = reduction_inlet.send(reduction);
+        let proof = proof?;
+
+        let batch_commit = match commit_outlet.await.ok()? {
+            Ok(batch_commit) => batch_commit,
+            Err(_) => {
+                let _ = completion_inlet.send(Err(CommitBrokerFailure::Error));
+                return None;
+            }
+        };
+
+        let commit = Commit::new(CommitProof::new(batch_commit, proof), payload);
+
+        Some(CommitBrokerage {
+            request: CommitRequest::new(commit, dependency),
+            completion_inlet,
+        })
+    }
+}
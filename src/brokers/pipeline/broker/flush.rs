@@ -0,0 +1,85 @@
+use crate::{
+    brokers::{
+        pipeline::{broker_settings::BrokerTaskSettings, Broker, Brokerage},
+        prepare::BrokerFailure as PrepareBrokerFailure,
+    },
+    data::{PingBoard, Sponge},
+    discovery::Client,
+    view::View,
+};
+
+use std::sync::Arc;
+
+use talk::{net::SessionConnector, sync::fuse::Fuse};
+
+impl Broker {
+    pub(in crate::brokers::pipeline::broker) async fn flush(
+        discovery: Arc<Client>,
+        view: View,
+        brokerage_sponge: Arc<Sponge<Brokerage>>,
+        ping_board: PingBoard,
+        prepare_connector: Arc<SessionConnector>,
+        commit_connector: Arc<SessionConnector>,
+        query_connector: Arc<SessionConnector>,
+        settings: BrokerTaskSettings,
+    ) {
+        let fuse = Fuse::new();
+
+        loop {
+            // Remark: `brokerage_sponge.flush()` always returns a non-empty
+            // `Vec<Brokerage>`. Because `Broker::prepare` only filters `Id`
+            // duplicates, it never produces an empty output on a non-empty input.
+            let brokerages = Broker::prepare(brokerage_sponge.flush().await);
+
+            let discovery = discovery.clone();
+            let view = view.clone();
+            let ping_board = ping_board.clone();
+            let prepare_connector = prepare_connector.clone();
+            let commit_connector = commit_connector.clone();
+            let query_connector = query_connector.clone();
+            let brokerage_sponge = brokerage_sponge.clone();
+            let settings = settings.clone();
+
+            fuse.spawn(async move {
+                let latency = Broker::broker(
+                    discovery,
+                    view,
+                    ping_board,
+                    prepare_connector,
+                    commit_connector,
+                    query_connector,
+                    brokerages,
+                    settings,
+                )
+                .await;
+
+                brokerage_sponge.observe(latency);
+            });
+        }
+    }
+
+    fn prepare(mut brokerages: Vec<Brokerage>) -> Vec<Brokerage> {
+        // Sort `brokerages` by requestor
+
+        brokerages.sort_by_key(|brokerage| brokerage.request.id());
+
+        // Deduplicate and fail `brokerages` by requestor (see `prepare::Broker::prepare`)
+
+        let mut previous = None;
+
+        brokerages
+            .into_iter()
+            .filter_map(|brokerage| {
+                if Some(brokerage.request.id()) == previous {
+                    let _ = brokerage
+                        .reduction_inlet
+                        .send(Err(PrepareBrokerFailure::Throttle { retry_after: None }));
+                    None
+                } else {
+                    previous = Some(brokerage.request.id());
+                    Some(brokerage)
+                }
+            })
+            .collect()
+    }
+}
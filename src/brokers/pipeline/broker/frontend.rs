@@ -0,0 +1,269 @@
+use crate::{
+    account::Id,
+    brokers::{
+        pipeline::{
+            broker_settings::FrontendTaskSettings, Broker, BrokerFailure, Brokerage, Request,
+        },
+        prepare::{Inclusion, Reduction},
+    },
+    commit::CompletionProof,
+    data::{Admission, Sponge},
+    discovery::Client,
+    prepare::ReductionStatement,
+    transport::{Connection, Transport},
+};
+
+use doomstack::{here, Doom, ResultExt, Top};
+
+use std::{sync::Arc, time::Duration};
+
+use talk::{
+    crypto::{primitives::multi::Signature as MultiSignature, KeyChain},
+    sync::fuse::Fuse,
+};
+
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::oneshot,
+    time,
+};
+
+#[derive(Doom)]
+enum ServeError {
+    #[doom(description("Failed to accept the connection"))]
+    AcceptFailed,
+    #[doom(description("Connection error"))]
+    ConnectionError,
+    #[doom(description("Request invalid"))]
+    RequestInvalid,
+    #[doom(description("Client authenticated with a foreign `KeyCard`"))]
+    ForeignClient,
+    #[doom(description("`Brokerage` forfeited (most likely, the `Broker` is shutting down)"))]
+    #[doom(wrap(request_forfeited))]
+    BrokerageForfeited { source: oneshot::error::RecvError },
+    #[doom(description("Reduction shard invalid"))]
+    ReductionShardInvalid,
+}
+
+impl Broker {
+    pub(in crate::brokers::pipeline::broker) async fn listen(
+        keychain: KeyChain,
+        discovery: Arc<Client>,
+        brokerage_sponge: Arc<Sponge<Brokerage>>,
+        listener: TcpListener,
+        settings: FrontendTaskSettings,
+    ) {
+        let keychain = Arc::new(keychain);
+        let admission = Arc::new(Admission::new(settings.admission_settings.clone()));
+
+        let fuse = Fuse::new();
+
+        loop {
+            if let Ok((stream, _)) = listener.accept().await {
+                // Connections in excess of `max_connections` are throttled, then closed
+                // unserved (a refusal outlasting `retry_after` is abandoned: by then,
+                // the client might as well retry)
+
+                let connection_permit = match admission.connect() {
+                    Ok(permit) => permit,
+                    Err(retry_after) => {
+                        let keychain = keychain.clone();
+                        let transport = settings.transport;
+
+                        fuse.spawn(async move {
+                            let refusal =
+                                Broker::refuse(keychain.as_ref(), stream, transport, retry_after);
+
+                            let _ = time::timeout(retry_after, refusal).await;
+                        });
+
+                        continue;
+                    }
+                };
+
+                let keychain = keychain.clone();
+                let discovery = discovery.clone();
+                let brokerage_sponge = brokerage_sponge.clone();
+                let admission = admission.clone();
+                let settings = settings.clone();
+
+                fuse.spawn(async move {
+                    let _ = Broker::serve(
+                        keychain.as_ref(),
+                        discovery,
+                        brokerage_sponge,
+                        admission,
+                        stream,
+                        settings,
+                    )
+                    .await;
+
+                    drop(connection_permit);
+                });
+            }
+        }
+    }
+
+    async fn serve(
+        keychain: &KeyChain,
+        discovery: Arc<Client>,
+        brokerage_sponge: Arc<Sponge<Brokerage>>,
+        admission: Arc<Admission<Id>>,
+        stream: TcpStream,
+        settings: FrontendTaskSettings,
+    ) -> Result<(), Top<ServeError>> {
+        let (mut connection, client) = Connection::accept(stream, keychain, settings.transport)
+            .await
+            .pot(ServeError::AcceptFailed, here!())?;
+
+        // Receive and validate `Request`
+
+        let request = connection
+            .receive::<Request>()
+            .await
+            .pot(ServeError::ConnectionError, here!())?;
+
+        request
+            .validate(discovery.as_ref())
+            .pot(ServeError::RequestInvalid, here!())?;
+
+        // If required, the client must authenticate as the account it operates
+
+        if settings.authenticate_clients
+            && client.map(|client| client.identity()) != Some(request.prepare.keycard().identity())
+        {
+            return ServeError::ForeignClient.fail().spot(here!());
+        }
+
+        // Throttle clients submitting `Request`s in excess of their rate, and new
+        // `Brokerage`s in excess of `max_brokerages` (the permit is held until
+        // the `Brokerage` completes, or the served client goes away)
+
+        if let Err(retry_after) = admission.submit(request.id()) {
+            return Broker::throttle(connection, retry_after).await;
+        }
+
+        let _brokerage_permit = match admission.broker() {
+            Ok(permit) => permit,
+            Err(retry_after) => return Broker::throttle(connection, retry_after).await,
+        };
+
+        // Build and submit `Brokerage` to `brokerage_sponge`
+
+        let keycard = request.prepare.keycard().clone(); // Needed to later verify the client's reduction shard
+
+        let (reduction_inlet, reduction_outlet) = oneshot::channel();
+        let (completion_inlet, completion_outlet) = oneshot::channel();
+
+        let brokerage = Brokerage {
+            request,
+            reduction_inlet,
+            completion_inlet,
+        };
+
+        brokerage_sponge.push(brokerage);
+
+        // Wait for `Reduction` from `broker` task
+
+        let reduction = reduction_outlet
+            .await
+            .map_err(ServeError::request_forfeited)
+            .map_err(Doom::into_top)
+            .spot(here!())?;
+
+        // If `reduction` is `Err`, forward `BrokerFailure` to the served client,
+        // otherwise explode `reduction`'s fields
+
+        let Reduction {
+            index,
+            inclusion,
+            reduction_sponge,
+        } = match reduction {
+            Ok(reduction) => reduction,
+            Err(failure) => {
+                connection
+                    .send::<Result<Inclusion, BrokerFailure>>(&Err(failure.into()))
+                    .await
+                    .pot(ServeError::ConnectionError, here!())?;
+
+                // Successfully delivering a `BrokerFailure` to the served client is not a
+                // shortcoming of `serve`, and should not result in an `Err`
+                return Ok(());
+            }
+        };
+
+        let root = inclusion.root(); // Needed to later verify the client's reduction shard
+
+        // Trade `inclusion` for a (valid) reduction shard
+
+        connection
+            .send::<Result<Inclusion, BrokerFailure>>(&Ok(inclusion))
+            .await
+            .pot(ServeError::ConnectionError, here!())?;
+
+        let reduction_shard = connection
+            .receive::<MultiSignature>()
+            .await
+            .pot(ServeError::ConnectionError, here!())?;
+
+        reduction_shard
+            .verify([&keycard], &ReductionStatement::new(root))
+            .pot(ServeError::ReductionShardInvalid, here!())?;
+
+        // Submit `reduction_shard` to `reduction_sponge`
+
+        let _ = reduction_sponge.push((index, reduction_shard));
+
+        // Wait for `CompletionProof` from `broker` task (the `broker` task
+        // prepares and commits the served client's `Payload` in one go)
+
+        let completion = completion_outlet
+            .await
+            .map_err(ServeError::request_forfeited)
+            .map_err(Doom::into_top)
+            .spot(here!())?
+            .map_err(BrokerFailure::from);
+
+        // Send `completion` to the served client (note that `completion`
+        // is a `Result<CompletionProof, BrokerFailure>`)
+
+        connection
+            .send::<Result<CompletionProof, BrokerFailure>>(&completion)
+            .await
+            .pot(ServeError::ConnectionError, here!())?;
+
+        // Successfully delivering a `BrokerFailure` to the served client is not a shortcoming
+        // of `serve`, and should not result in an `Err` (see above)
+        Ok(())
+    }
+
+    async fn refuse(
+        keychain: &KeyChain,
+        stream: TcpStream,
+        transport: Transport,
+        retry_after: Duration,
+    ) -> Result<(), Top<ServeError>> {
+        let (connection, _) = Connection::accept(stream, keychain, transport)
+            .await
+            .pot(ServeError::AcceptFailed, here!())?;
+
+        Broker::throttle(connection, retry_after).await
+    }
+
+    async fn throttle(
+        mut connection: Connection,
+        retry_after: Duration,
+    ) -> Result<(), Top<ServeError>> {
+        let failure = BrokerFailure::Throttle {
+            retry_after: Some(retry_after),
+        };
+
+        connection
+            .send::<Result<Inclusion, BrokerFailure>>(&Err(failure))
+            .await
+            .pot(ServeError::ConnectionError, here!())?;
+
+        // Throttling is not a shortcoming of `serve`, and should not result in an `Err`
+        Ok(())
+    }
+}
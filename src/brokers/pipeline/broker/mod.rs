@@ -0,0 +1,160 @@
+use crate::{
+    brokers::{
+        pipeline::{BrokerSettings, BrokerSettingsComponents, Brokerage},
+        prepare::Broker as PrepareBroker,
+    },
+    crypto::Identify,
+    data::{PingBoard, Sponge},
+    discovery::Client,
+    view::View,
+};
+
+use doomstack::{here, Doom, ResultExt, Top};
+
+use std::{net::SocketAddr, sync::Arc};
+
+use talk::{
+    crypto::{Identity, KeyChain},
+    link::context::ConnectDispatcher,
+    net::{Connector, SessionConnector},
+    sync::fuse::Fuse,
+};
+
+use tokio::{
+    io,
+    net::{TcpListener, ToSocketAddrs},
+};
+
+/// Drives each `Brokerage` through both prepare and commit: `Brokerage`s are
+/// committed in the same batches as they are prepared.
+pub(crate) struct Broker {
+    address: SocketAddr,
+    identity: Identity,
+    _fuse: Fuse,
+}
+
+#[derive(Doom)]
+pub(crate) enum BrokerError {
+    #[doom(description("Failed to initialize broker: {}", source))]
+    #[doom(wrap(initialize_failed))]
+    InitializeFailed { source: io::Error },
+}
+
+impl Broker {
+    pub async fn new<A, C>(
+        keychain: KeyChain,
+        discovery: Arc<Client>,
+        view: View,
+        address: A,
+        connector: C,
+        settings: BrokerSettings,
+    ) -> Result<Self, Top<BrokerError>>
+    where
+        A: ToSocketAddrs,
+        C: Connector,
+    {
+        let BrokerSettingsComponents {
+            frontend: frontend_settings,
+            flush: flush_settings,
+            broker: broker_settings,
+            ping: ping_settings,
+        } = settings.into_components();
+
+        let listener = TcpListener::bind(address)
+            .await
+            .map_err(BrokerError::initialize_failed)
+            .map_err(Doom::into_top)
+            .spot(here!())?;
+
+        let address = listener
+            .local_addr()
+            .map_err(BrokerError::initialize_failed)
+            .map_err(Doom::into_top)
+            .spot(here!())?;
+
+        let dispatcher = ConnectDispatcher::new(connector);
+
+        let prepare_context = format!("{:?}::processor::prepare", view.identifier());
+        let prepare_connector =
+            Arc::new(SessionConnector::new(dispatcher.register(prepare_context)));
+
+        let commit_context = format!("{:?}::processor::commit", view.identifier());
+        let commit_connector = Arc::new(SessionConnector::new(dispatcher.register(commit_context)));
+
+        let query_context = format!("{:?}::processor::query", view.identifier());
+        let query_connector = Arc::new(SessionConnector::new(dispatcher.register(query_context)));
+
+        let brokerage_sponge = Arc::new(Sponge::new(flush_settings.brokerage_sponge_settings));
+        let ping_board = PingBoard::new(&view);
+
+        let identity = keychain.keycard().identity();
+        let fuse = Fuse::new();
+
+        {
+            let discovery = discovery.clone();
+            let brokerage_sponge = brokerage_sponge.clone();
+
+            fuse.spawn(async move {
+                Broker::listen(
+                    keychain,
+                    discovery,
+                    brokerage_sponge,
+                    listener,
+                    frontend_settings,
+                )
+                .await;
+            });
+        }
+
+        {
+            let view = view.clone();
+            let ping_board = ping_board.clone();
+            let prepare_connector = prepare_connector.clone();
+
+            fuse.spawn(async move {
+                Broker::flush(
+                    discovery,
+                    view,
+                    brokerage_sponge,
+                    ping_board,
+                    prepare_connector,
+                    commit_connector,
+                    query_connector,
+                    broker_settings,
+                )
+                .await;
+            });
+        }
+
+        // Replicas are pinged (and ranked) once, over the prepare context,
+        // for both prepare and commit orchestration
+
+        for replica in view.members().keys().copied() {
+            let ping_board = ping_board.clone();
+            let prepare_connector = prepare_connector.clone();
+            let ping_settings = ping_settings.clone();
+
+            fuse.spawn(async move {
+                PrepareBroker::ping(ping_board, prepare_connector, replica, ping_settings).await
+            });
+        }
+
+        Ok(Broker {
+            address,
+            identity,
+            _fuse: fuse,
+        })
+    }
+
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    pub fn identity(&self) -> Identity {
+        self.identity
+    }
+}
+
+mod broker;
+mod flush;
+mod frontend;
@@ -0,0 +1,35 @@
+use crate::brokers::{
+    commit::BrokerFailure as CommitBrokerFailure, prepare::BrokerFailure as PrepareBrokerFailure,
+};
+
+use serde::{Deserialize, Serialize};
+
+use std::time::Duration;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) enum BrokerFailure {
+    Throttle { retry_after: Option<Duration> },
+    Error,
+}
+
+impl From<PrepareBrokerFailure> for BrokerFailure {
+    fn from(failure: PrepareBrokerFailure) -> Self {
+        match failure {
+            PrepareBrokerFailure::Throttle { retry_after } => {
+                BrokerFailure::Throttle { retry_after }
+            }
+            PrepareBrokerFailure::Error => BrokerFailure::Error,
+        }
+    }
+}
+
+impl From<CommitBrokerFailure> for BrokerFailure {
+    fn from(failure: CommitBrokerFailure) -> Self {
+        match failure {
+            CommitBrokerFailure::Throttle { retry_after } => {
+                BrokerFailure::Throttle { retry_after }
+            }
+            CommitBrokerFailure::Error => BrokerFailure::Error,
+        }
+    }
+}
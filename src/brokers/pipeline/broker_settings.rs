@@ -0,0 +1,101 @@
+use crate::{
+    brokers::{
+        commit::BrokerTaskSettings as CommitBrokerTaskSettings,
+        prepare::{BrokerTaskSettings as PrepareBrokerTaskSettings, PingTaskSettings},
+    },
+    data::{AdmissionSettings, SpongeSettings},
+    transport::Transport,
+};
+
+use serde::Deserialize;
+
+use std::time::Duration;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub(crate) struct BrokerSettings {
+    pub transport: Transport,
+    pub authenticate_clients: bool,
+
+    pub admission_settings: AdmissionSettings,
+
+    pub brokerage_sponge_settings: SpongeSettings,
+
+    pub reduction_threshold: f64,
+    pub reduction_timeout: Duration,
+    pub optimistic_witness_timeout: Duration,
+
+    pub ping_interval: Duration,
+}
+
+pub(in crate::brokers::pipeline) struct BrokerSettingsComponents {
+    pub frontend: FrontendTaskSettings,
+    pub flush: FlushTaskSettings,
+    pub broker: BrokerTaskSettings,
+    pub ping: PingTaskSettings,
+}
+
+#[derive(Debug, Clone)]
+pub(in crate::brokers::pipeline) struct FrontendTaskSettings {
+    pub transport: Transport,
+    pub authenticate_clients: bool,
+    pub admission_settings: AdmissionSettings,
+}
+
+#[derive(Debug, Clone)]
+pub(in crate::brokers::pipeline) struct FlushTaskSettings {
+    pub brokerage_sponge_settings: SpongeSettings,
+}
+
+#[derive(Debug, Clone)]
+pub(in crate::brokers::pipeline) struct BrokerTaskSettings {
+    pub prepare: PrepareBrokerTaskSettings,
+    pub commit: CommitBrokerTaskSettings,
+}
+
+impl BrokerSettings {
+    pub(in crate::brokers::pipeline) fn into_components(self) -> BrokerSettingsComponents {
+        BrokerSettingsComponents {
+            frontend: FrontendTaskSettings {
+                transport: self.transport,
+                authenticate_clients: self.authenticate_clients,
+                admission_settings: self.admission_settings,
+            },
+            flush: FlushTaskSettings {
+                brokerage_sponge_settings: self.brokerage_sponge_settings,
+            },
+            broker: BrokerTaskSettings {
+                prepare: PrepareBrokerTaskSettings {
+                    reduction_threshold: self.reduction_threshold,
+                    reduction_timeout: self.reduction_timeout,
+                    optimistic_witness_timeout: self.optimistic_witness_timeout,
+                },
+                commit: CommitBrokerTaskSettings {
+                    optimistic_witness_timeout: self.optimistic_witness_timeout,
+                },
+            },
+            ping: PingTaskSettings {
+                ping_interval: self.ping_interval,
+            },
+        }
+    }
+}
+
+impl Default for BrokerSettings {
+    fn default() -> Self {
+        BrokerSettings {
+            transport: Transport::Secure,
+            authenticate_clients: false,
+
+            admission_settings: Default::default(),
+
+            brokerage_sponge_settings: Default::default(),
+
+            reduction_threshold: 1.,
+            reduction_timeout: Duration::from_secs(1),
+            optimistic_witness_timeout: Duration::from_secs(1),
+
+            ping_interval: Duration::from_secs(60),
+        }
+    }
+}
@@ -0,0 +1,19 @@
+use crate::{
+    brokers::{
+        commit::BrokerFailure as CommitBrokerFailure,
+        pipeline::Request,
+        prepare::{BrokerFailure as PrepareBrokerFailure, Reduction},
+    },
+    commit::CompletionProof,
+};
+
+use tokio::sync::oneshot::Sender;
+
+type ReductionInlet = Sender<Result<Reduction, PrepareBrokerFailure>>;
+type CompletionInlet = Sender<Result<CompletionProof, CommitBrokerFailure>>;
+
+pub(in crate::brokers::pipeline) struct Brokerage {
+    pub request: Request,
+    pub reduction_inlet: ReductionInlet,
+    pub completion_inlet: CompletionInlet,
+}
@@ -0,0 +1,14 @@
+mod broker;
+mod broker_failure;
+mod broker_settings;
+mod brokerage;
+mod request;
+
+use broker_settings::BrokerSettingsComponents;
+use brokerage::Brokerage;
+
+pub(crate) use broker::Broker;
+
+pub(crate) use broker_failure::BrokerFailure;
+pub(crate) use broker_settings::BrokerSettings;
+pub(crate) use request::Request;
@@ -0,0 +1,79 @@
+use crate::{
+    account::Id,
+    brokers::prepare::Request as PrepareRequest,
+    commit::{Completion, Payload},
+    discovery::Client,
+};
+
+use doomstack::{here, Doom, ResultExt, Top};
+
+use serde::{Deserialize, Serialize};
+
+/// A `Payload`, along with the (signed) `Request` to prepare it: a pipeline
+/// broker drives it through both prepare and commit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Request {
+    pub prepare: PrepareRequest,
+    pub payload: Payload,
+    pub dependency: Option<Completion>,
+}
+
+#[derive(Doom)]
+pub(crate) enum RequestError {
+    #[doom(description("Prepare `Request` invalid"))]
+    PrepareInvalid,
+    #[doom(description("`Payload` does not match the prepared one"))]
+    PayloadMismatch,
+    #[doom(description("Dependency mismatch"))]
+    DependencyMismatch,
+    #[doom(description("Dependency invalid"))]
+    DependencyInvalid,
+}
+
+impl Request {
+    pub fn new(prepare: PrepareRequest, payload: Payload, dependency: Option<Completion>) -> Self {
+        Request {
+            prepare,
+            payload,
+            dependency,
+        }
+    }
+
+    pub fn id(&self) -> Id {
+        self.payload.id()
+    }
+
+    pub fn validate(&self, discovery: &Client) -> Result<(), Top<RequestError>> {
+        self.prepare
+            .validate(discovery)
+            .pot(RequestError::PrepareInvalid, here!())?;
+
+        let prepare = self.payload.prepare();
+
+        if prepare.entry() != self.prepare.prepare().entry()
+            || prepare.commitment() != self.prepare.prepare().commitment()
+        {
+            return RequestError::PayloadMismatch.fail().spot(here!());
+        }
+
+        match (self.payload.dependency(), &self.dependency) {
+            (Some(dependency), Some(completion)) => {
+                if completion.entry() != dependency {
+                    return RequestError::DependencyMismatch.fail().spot(here!());
+                }
+
+                completion
+                    .validate(discovery)
+                    .pot(RequestError::DependencyInvalid, here!())?;
+            }
+
+            (None, None) => {}
+
+            _ => {
+                return RequestError::DependencyMismatch.fail().spot(here!());
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -14,7 +14,11 @@ use doomstack::{here, Doom, ResultExt, Top};
 
 use futures::stream::{FuturesUnordered, StreamExt};
 
-use std::{iter, sync::Arc};
+use std::{
+    iter,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use talk::{
     crypto::{primitives::multi::Signature as MultiSignature, Identity},
@@ -32,18 +36,21 @@ enum PublishError {
 }
 
 impl Broker {
-    pub(in crate::brokers::prepare::broker) async fn broker(
+    pub(in crate::brokers) async fn broker(
         discovery: Arc<Client>,
         view: View,
         ping_board: PingBoard,
         connector: Arc<SessionConnector>,
+        query_connector: Arc<SessionConnector>,
         brokerages: Vec<Brokerage>,
         settings: BrokerTaskSettings,
-    ) {
+    ) -> Duration {
         // Unzip `brokerages` into its components
 
         let UnzippedBrokerages {
             assignments,
+            rotations,
+            continuities,
             prepares,
             signatures,
             reduction_inlets,
@@ -71,6 +78,7 @@ impl Broker {
         let reduction_sponge = Arc::new(Sponge::new(SpongeSettings {
             capacity: ((inclusions.len() as f64) * settings.reduction_threshold) as usize,
             timeout: settings.reduction_timeout,
+            adaptive: false,
         }));
 
         // Build vector of `Reduction`s
@@ -111,24 +119,32 @@ impl Broker {
 
         let submission = Submission::new(
             assignments,
+            rotations,
+            continuities,
             prepares,
             reduction_signature,
             individual_signatures,
         );
 
-        // Orchestrate submission of `submission`
+        // Orchestrate submission of `submission` (timing replica orchestration alone,
+        // as opposed to the reduction round trips above, which depend on clients)
+
+        let start = Instant::now();
 
         let commit = Broker::orchestrate(
             discovery,
             view.clone(),
             ping_board,
             connector.clone(),
+            query_connector,
             submission,
             settings,
         )
         .await
         .map_err(|_| BrokerFailure::Error);
 
+        let latency = start.elapsed();
+
         // Send a copy of `commit` to each `serve` task (note that `commit` is
         // a `Result<BatchCommit, Failure>`)
 
@@ -149,6 +165,8 @@ impl Broker {
                 .collect::<Vec<_>>()
                 .await;
         }
+
+        latency
     }
 
     async fn publish(
@@ -18,6 +18,7 @@ impl Broker {
         brokerage_sponge: Arc<Sponge<Brokerage>>,
         ping_board: PingBoard,
         connector: Arc<SessionConnector>,
+        query_connector: Arc<SessionConnector>,
         settings: BrokerTaskSettings,
     ) {
         let fuse = Fuse::new();
@@ -32,10 +33,25 @@ impl Broker {
             let view = view.clone();
             let ping_board = ping_board.clone();
             let connector = connector.clone();
+            let query_connector = query_connector.clone();
+            let brokerage_sponge = brokerage_sponge.clone();
             let settings = settings.clone();
 
             fuse.spawn(async move {
-                Broker::broker(discovery, view, ping_board, connector, brokerages, settings).await;
+                let latency = Broker::broker(
+                    discovery,
+                    view,
+                    ping_board,
+                    connector,
+                    query_connector,
+                    brokerages,
+                    settings,
+                )
+                .await;
+
+                // Feed `brokerage_sponge` the batch's orchestration latency (relevant
+                // in adaptive mode)
+                brokerage_sponge.observe(latency);
             });
         }
     }
@@ -58,7 +74,9 @@ impl Broker {
             .into_iter()
             .filter_map(|brokerage| {
                 if Some(brokerage.request.id()) == previous {
-                    let _ = brokerage.reduction_inlet.send(Err(BrokerFailure::Throttle));
+                    let _ = brokerage
+                        .reduction_inlet
+                        .send(Err(BrokerFailure::Throttle { retry_after: None }));
                     None
                 } else {
                     previous = Some(brokerage.request.id());
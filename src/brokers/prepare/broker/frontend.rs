@@ -1,63 +1,129 @@
 use crate::{
+    account::{Entry, Id},
     brokers::prepare::{
         broker::{Brokerage, Reduction},
-        Broker, BrokerFailure, Inclusion, Request,
+        broker_settings::FrontendTaskSettings,
+        Broker, BrokerFailure, Inclusion, Outcome, OutcomeInlets, Request,
     },
-    data::Sponge,
+    data::{Admission, ResultCache, Sponge},
     discovery::Client,
-    prepare::ReductionStatement,
+    prepare::{BatchCommit, ReductionStatement},
+    transport::{Connection, Transport},
 };
 
 use doomstack::{here, Doom, ResultExt, Top};
 
-use std::sync::Arc;
+use futures::future;
+
+use std::{
+    sync::{atomic::Ordering, Arc},
+    time::Duration,
+};
 
 use talk::{
-    crypto::primitives::multi::Signature as MultiSignature, net::PlainConnection, sync::fuse::Fuse,
+    crypto::{primitives::multi::Signature as MultiSignature, KeyCard, KeyChain},
+    sync::fuse::Fuse,
 };
 
-use tokio::{net::TcpListener, sync::oneshot};
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::oneshot::{self, Receiver},
+    time,
+};
+
+type Results = ResultCache<Entry, Arc<Outcome>>;
 
 #[derive(Doom)]
 enum ServeError {
+    #[doom(description("Failed to accept the connection"))]
+    AcceptFailed,
     #[doom(description("Connection error"))]
     ConnectionError,
     #[doom(description("Request invalid"))]
     RequestInvalid,
+    #[doom(description("Client authenticated with a foreign `KeyCard`"))]
+    ForeignClient,
     #[doom(description("`Brokerage` forfeited (most likely, the `Broker` is shutting down)"))]
-    #[doom(wrap(request_forfeited))]
-    BrokerageForfeited { source: oneshot::error::RecvError },
+    BrokerageForfeited,
     #[doom(description("Reduction shard invalid"))]
     ReductionShardInvalid,
 }
 
 impl Broker {
     pub(in crate::brokers::prepare::broker) async fn listen(
+        keychain: KeyChain,
         discovery: Arc<Client>,
         brokerage_sponge: Arc<Sponge<Brokerage>>,
         listener: TcpListener,
+        settings: FrontendTaskSettings,
     ) {
+        let keychain = Arc::new(keychain);
+        let results = Arc::new(Results::new(settings.result_cache_capacity));
+        let admission = Arc::new(Admission::new(settings.admission_settings.clone()));
+
         let fuse = Fuse::new();
 
         loop {
             if let Ok((stream, _)) = listener.accept().await {
-                let connection: PlainConnection = stream.into();
+                // Connections in excess of `max_connections` are throttled, then closed
+                // unserved (a refusal outlasting `retry_after` is abandoned: by then,
+                // the client might as well retry)
+
+                let connection_permit = match admission.connect() {
+                    Ok(permit) => permit,
+                    Err(retry_after) => {
+                        let keychain = keychain.clone();
+                        let transport = settings.transport;
+
+                        fuse.spawn(async move {
+                            let refusal =
+                                Broker::refuse(keychain.as_ref(), stream, transport, retry_after);
+
+                            let _ = time::timeout(retry_after, refusal).await;
+                        });
+
+                        continue;
+                    }
+                };
 
+                let keychain = keychain.clone();
                 let discovery = discovery.clone();
                 let brokerage_sponge = brokerage_sponge.clone();
+                let results = results.clone();
+                let admission = admission.clone();
+                let settings = settings.clone();
 
                 fuse.spawn(async move {
-                    let _ = Broker::serve(discovery, brokerage_sponge, connection).await;
+                    let _ = Broker::serve(
+                        keychain.as_ref(),
+                        discovery,
+                        brokerage_sponge,
+                        results,
+                        admission,
+                        stream,
+                        settings,
+                    )
+                    .await;
+
+                    drop(connection_permit);
                 });
             }
         }
     }
 
     async fn serve(
+        keychain: &KeyChain,
         discovery: Arc<Client>,
         brokerage_sponge: Arc<Sponge<Brokerage>>,
-        mut connection: PlainConnection,
+        results: Arc<Results>,
+        admission: Arc<Admission<Id>>,
+        stream: TcpStream,
+        settings: FrontendTaskSettings,
     ) -> Result<(), Top<ServeError>> {
+        let (mut connection, client) = Connection::accept(stream, keychain, settings.transport)
+            .await
+            .pot(ServeError::AcceptFailed, here!())?;
+
         // Receive and validate `Request`
 
         let request = connection
@@ -69,10 +135,50 @@ impl Broker {
             .validate(discovery.as_ref())
             .pot(ServeError::RequestInvalid, here!())?;
 
-        // Build and submit `Brokerage` to `brokerage_sponge`
+        // If required, the client must authenticate as the account it prepares for
+
+        if settings.authenticate_clients
+            && client.map(|client| client.identity()) != Some(request.keycard().identity())
+        {
+            return ServeError::ForeignClient.fail().spot(here!());
+        }
+
+        // Throttle clients submitting `Request`s in excess of their rate
+
+        if let Err(retry_after) = admission.submit(request.prepare().id()) {
+            return Broker::throttle(connection, retry_after).await;
+        }
+
+        // If a `Request` for the same `Entry` and commitment was previously
+        // submitted (e.g., by a client whose connection dropped), attach to
+        // its `Outcome`. Otherwise, build and submit a new `Brokerage`.
+
+        let entry = request.prepare().entry();
+        let commitment = request.prepare().commitment();
 
         let keycard = request.keycard().clone(); // Needed to later verify the client's reduction shard
 
+        let (outcome, inlets) = Outcome::new(commitment);
+
+        let (outcome, fresh) = results.attach_or_insert(entry, Arc::new(outcome), |cached| {
+            cached.commitment == commitment
+        });
+
+        if !fresh {
+            return Broker::attend(connection, keycard, outcome).await;
+        }
+
+        // Throttle new `Brokerage`s in excess of `max_brokerages` (unbrokered, `outcome`
+        // is evicted from `results`, and any `serve` task attached to it forfeits)
+
+        let brokerage_permit = match admission.broker() {
+            Ok(permit) => permit,
+            Err(retry_after) => {
+                results.remove_if(&entry, |cached| Arc::ptr_eq(cached, &outcome));
+                return Broker::throttle(connection, retry_after).await;
+            }
+        };
+
         let (reduction_inlet, reduction_outlet) = oneshot::channel();
         let (commit_inlet, commit_outlet) = oneshot::channel();
 
@@ -84,13 +190,120 @@ impl Broker {
 
         brokerage_sponge.push(brokerage);
 
+        // The `Brokerage` is tracked to completion even if the client's connection drops
+
+        let tracked = outcome.clone();
+
+        let track = async {
+            Broker::track(
+                results.as_ref(),
+                entry,
+                tracked,
+                inlets,
+                reduction_outlet,
+                commit_outlet,
+            )
+            .await;
+
+            // The `Brokerage` is no longer outstanding
+            drop(brokerage_permit);
+        };
+
+        let attend = Broker::attend(connection, keycard, outcome);
+
+        let (_, result) = future::join(track, attend).await;
+        result
+    }
+
+    async fn refuse(
+        keychain: &KeyChain,
+        stream: TcpStream,
+        transport: Transport,
+        retry_after: Duration,
+    ) -> Result<(), Top<ServeError>> {
+        let (connection, _) = Connection::accept(stream, keychain, transport)
+            .await
+            .pot(ServeError::AcceptFailed, here!())?;
+
+        Broker::throttle(connection, retry_after).await
+    }
+
+    async fn throttle(
+        mut connection: Connection,
+        retry_after: Duration,
+    ) -> Result<(), Top<ServeError>> {
+        let failure = BrokerFailure::Throttle {
+            retry_after: Some(retry_after),
+        };
+
+        connection
+            .send::<Result<Inclusion, BrokerFailure>>(&Err(failure))
+            .await
+            .pot(ServeError::ConnectionError, here!())?;
+
+        // Throttling is not a shortcoming of `serve`, and should not result in an `Err`
+        Ok(())
+    }
+
+    async fn track(
+        results: &Results,
+        entry: Entry,
+        outcome: Arc<Outcome>,
+        inlets: OutcomeInlets,
+        reduction_outlet: Receiver<Result<Reduction, BrokerFailure>>,
+        commit_outlet: Receiver<Result<BatchCommit, BrokerFailure>>,
+    ) {
+        let OutcomeInlets {
+            reduction_inlet,
+            commit_inlet,
+        } = inlets;
+
+        // Forward `Reduction` and `BatchCommit` from the `broker` task to all
+        // attached `serve` tasks (`inlets` are dropped on forfeit)
+
+        let committed = async {
+            let reduction = match reduction_outlet.await {
+                Ok(reduction) => reduction,
+                Err(_) => return false,
+            };
+
+            let reduced = reduction.is_ok();
+            let _ = reduction_inlet.send(Some(reduction));
+
+            if !reduced {
+                return false;
+            }
+
+            let commit = match commit_outlet.await {
+                Ok(commit) => commit,
+                Err(_) => return false,
+            };
+
+            let committed = commit.is_ok();
+            let _ = commit_inlet.send(Some(commit));
+
+            committed
+        }
+        .await;
+
+        // Failed or forfeited `Brokerage`s are not cached: resubmissions are brokered anew
+
+        if !committed {
+            results.remove_if(&entry, |cached| Arc::ptr_eq(cached, &outcome));
+        }
+    }
+
+    async fn attend(
+        mut connection: Connection,
+        keycard: KeyCard,
+        outcome: Arc<Outcome>,
+    ) -> Result<(), Top<ServeError>> {
         // Wait for `Reduction` from `broker` task
 
-        let reduction = reduction_outlet
+        let reduction = outcome
+            .reduction()
             .await
-            .map_err(ServeError::request_forfeited)
-            .map_err(Doom::into_top)
-            .spot(here!())?;
+            .ok_or(ServeError::BrokerageForfeited.into_top().spot(here!()))?;
 
         // If `reduction` is `Err`, forward `BrokerFailure` to the served client,
         // otherwise explode `reduction`'s fields
@@ -131,17 +344,19 @@ impl Broker {
             .verify([&keycard], &ReductionStatement::new(root))
             .pot(ServeError::ReductionShardInvalid, here!())?;
 
-        // Submit `reduction_shard` to `reduction_sponge`
+        // Submit `reduction_shard` to `reduction_sponge` (at most once per `Brokerage`:
+        // `reduction_sponge` is aggregated without checking for duplicates)
 
-        let _ = reduction_sponge.push((index, reduction_shard));
+        if !outcome.reduced.swap(true, Ordering::Relaxed) {
+            let _ = reduction_sponge.push((index, reduction_shard));
+        }
 
         // Wait for `BatchCommit` from `broker` task
 
-        let commit = commit_outlet
+        let commit = outcome
+            .commit()
             .await
-            .map_err(ServeError::request_forfeited)
-            .map_err(Doom::into_top)
-            .spot(here!())?;
+            .ok_or(ServeError::BrokerageForfeited.into_top().spot(here!()))?;
 
         // Send `commit` to the served client (note that `commit` is a `Result<BatchCommit, Failure>`)
 
@@ -11,6 +11,7 @@ use doomstack::{here, Doom, ResultExt, Top};
 use std::{net::SocketAddr, sync::Arc};
 
 use talk::{
+    crypto::{Identity, KeyChain},
     link::context::ConnectDispatcher,
     net::{Connector, SessionConnector},
     sync::fuse::Fuse,
@@ -23,6 +24,7 @@ use tokio::{
 
 pub(crate) struct Broker {
     address: SocketAddr,
+    identity: Identity,
     _fuse: Fuse,
 }
 
@@ -35,6 +37,7 @@ pub(crate) enum BrokerError {
 
 impl Broker {
     pub async fn new<A, C>(
+        keychain: KeyChain,
         discovery: Arc<Client>,
         view: View,
         address: A,
@@ -46,6 +49,7 @@ impl Broker {
         C: Connector,
     {
         let BrokerSettingsComponents {
+            frontend: frontend_settings,
             flush: flush_settings,
             broker: broker_settings,
             ping: ping_settings,
@@ -67,9 +71,15 @@ impl Broker {
         let context = format!("{:?}::processor::prepare", view.identifier());
         let connector = Arc::new(SessionConnector::new(dispatcher.register(context)));
 
+        // Replicas are queried for `AccountProof`s when lagging behind
+        // the commits of some client (see `Broker::prove`)
+        let query_context = format!("{:?}::processor::query", view.identifier());
+        let query_connector = Arc::new(SessionConnector::new(dispatcher.register(query_context)));
+
         let brokerage_sponge = Arc::new(Sponge::new(flush_settings.brokerage_sponge_settings));
         let ping_board = PingBoard::new(&view);
 
+        let identity = keychain.keycard().identity();
         let fuse = Fuse::new();
 
         {
@@ -77,7 +87,14 @@ impl Broker {
             let brokerage_sponge = brokerage_sponge.clone();
 
             fuse.spawn(async move {
-                Broker::listen(discovery, brokerage_sponge, listener).await;
+                Broker::listen(
+                    keychain,
+                    discovery,
+                    brokerage_sponge,
+                    listener,
+                    frontend_settings,
+                )
+                .await;
             });
         }
 
@@ -94,6 +111,7 @@ impl Broker {
                     brokerage_sponge,
                     ping_board,
                     connector,
+                    query_connector,
                     broker_settings,
                 )
                 .await;
@@ -112,6 +130,7 @@ impl Broker {
 
         Ok(Broker {
             address,
+            identity,
             _fuse: fuse,
         })
     }
@@ -119,6 +138,10 @@ impl Broker {
     pub fn address(&self) -> SocketAddr {
         self.address
     }
+
+    pub fn identity(&self) -> Identity {
+        self.identity
+    }
 }
 
 mod broker;
@@ -126,6 +149,7 @@ mod flush;
 mod frontend;
 mod orchestrate;
 mod ping;
+mod prove;
 
 #[cfg(test)]
 mod tests {
@@ -186,7 +210,14 @@ mod tests {
         // Prepare
 
         let prepare_broker = prepare_brokers.remove(0);
-        let request = Request::new(&client_keychain, assignment, 0, hash::hash(&42u32).unwrap());
+        let request = Request::new(
+            &client_keychain,
+            assignment,
+            None,
+            None,
+            0,
+            hash::hash(&42u32).unwrap(),
+        );
 
         let stream = TcpStream::connect(prepare_broker.address()).await.unwrap();
         let mut connection: PlainConnection = stream.into();
@@ -216,4 +247,96 @@ mod tests {
 
         // tokio::time::sleep(std::time::Duration::from_secs(10)).await;
     }
+
+    #[tokio::test]
+    async fn resubmit() {
+        let System {
+            view,
+            discovery_server: _discovery_server,
+            discovery_client,
+            processors,
+            mut signup_brokers,
+            mut prepare_brokers,
+            ..
+        } = System::setup(4, 1, 1, 0).await;
+
+        let client_keychain = KeyChain::random();
+
+        // Signup
+
+        let signup_broker = signup_brokers.remove(0);
+        let allocator_identity = processors[0].0.keycard().identity();
+
+        let request = IdRequest::new(
+            &client_keychain,
+            &view,
+            allocator_identity,
+            SignupSettings::default().work_difficulty,
+        );
+
+        let stream = TcpStream::connect(signup_broker.address()).await.unwrap();
+        let mut connection: PlainConnection = stream.into();
+
+        connection.send(&request).await.unwrap();
+
+        let assignment = connection
+            .receive::<Result<IdAssignment, SignupBrokerFailure>>()
+            .await
+            .unwrap()
+            .unwrap();
+
+        // Submit, then drop the connection before any response is received
+
+        let prepare_broker = prepare_brokers.remove(0);
+        let request = Request::new(
+            &client_keychain,
+            assignment,
+            None,
+            None,
+            0,
+            hash::hash(&42u32).unwrap(),
+        );
+
+        {
+            let stream = TcpStream::connect(prepare_broker.address()).await.unwrap();
+            let mut connection: PlainConnection = stream.into();
+
+            connection.send(&request).await.unwrap();
+        }
+
+        // Both resubmissions are served the outcome of the original brokerage
+        // (no new brokerage is submitted for the same `Entry` and commitment)
+
+        let mut roots = Vec::new();
+
+        for _ in 0..2 {
+            let stream = TcpStream::connect(prepare_broker.address()).await.unwrap();
+            let mut connection: PlainConnection = stream.into();
+
+            connection.send(&request).await.unwrap();
+
+            let inclusion = connection
+                .receive::<Result<Inclusion, BrokerFailure>>()
+                .await
+                .unwrap()
+                .unwrap();
+
+            let reduction_shard = inclusion
+                .certify_reduction(&client_keychain, request.prepare())
+                .unwrap();
+
+            connection.send(&reduction_shard).await.unwrap();
+
+            let commit = connection
+                .receive::<Result<BatchCommit, BrokerFailure>>()
+                .await
+                .unwrap()
+                .unwrap();
+
+            commit.validate(discovery_client.as_ref()).unwrap();
+            roots.push(commit.root());
+        }
+
+        assert_eq!(roots[0], roots[1]);
+    }
 }
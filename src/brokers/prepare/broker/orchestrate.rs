@@ -1,4 +1,5 @@
 use crate::{
+    account::Id,
     brokers::prepare::{broker_settings::BrokerTaskSettings, Broker, Submission},
     crypto::{Aggregator, Certificate},
     data::PingBoard,
@@ -11,6 +12,8 @@ use crate::{
 
 use doomstack::{here, Doom, ResultExt, Top};
 
+use futures::future;
+
 use std::{collections::HashMap, sync::Arc};
 
 use talk::{
@@ -76,6 +79,8 @@ enum SubmitError {
     UnexpectedResponse,
     #[doom(description("Malformed response"))]
     MalformedResponse,
+    #[doom(description("Failed to prove rotation"))]
+    ProofFailed,
     #[doom(description("Invalid witness shard"))]
     InvalidWitnessShard,
     #[doom(description("Invalid `BatchCommitShard`"))]
@@ -96,6 +101,7 @@ impl Broker {
         view: View,
         ping_board: PingBoard,
         connector: Arc<SessionConnector>,
+        query_connector: Arc<SessionConnector>,
         submission: Submission,
         settings: BrokerTaskSettings,
     ) -> Result<BatchCommit, Top<OrchestrateError>> {
@@ -112,6 +118,7 @@ impl Broker {
             let discovery = discovery.clone();
             let view = view.clone();
             let connector = connector.clone();
+            let query_connector = query_connector.clone();
             let submission = submission.clone();
             let update_inlet = update_inlet.clone();
 
@@ -123,6 +130,7 @@ impl Broker {
                     discovery,
                     view,
                     connector,
+                    query_connector,
                     replica,
                     submission,
                     command_outlet,
@@ -210,6 +218,7 @@ impl Broker {
         discovery: Arc<Client>,
         view: View,
         connector: Arc<SessionConnector>,
+        query_connector: Arc<SessionConnector>,
         replica: KeyCard,
         submission: Arc<Submission>,
         mut command_outlet: CommandOutlet,
@@ -255,31 +264,24 @@ impl Broker {
                         .pot(SubmitError::ConnectionError, here!())?;
 
                     // Obtain a witness shard (if requested to do so, first provide `replica` with the
-                    // `IdAssignment`s it is missing)
+                    // `IdAssignment`s it is missing, then with proofs of the rotations it lags behind)
 
                     let response = session
                         .receive::<PrepareResponse>()
                         .await
                         .pot(SubmitError::ConnectionError, here!())?;
 
-                    let shard = match response {
-                        // If `response` is `UnknownIds`, then `replica` misses some `IdAssignment`s,
-                        // required to validate the submitted signatures
+                    // If `response` is `UnknownIds`, then `replica` misses some `IdAssignment`s,
+                    // required to validate the submitted signatures
+
+                    let response = match response {
                         PrepareResponse::UnknownIds(unknown_ids) => {
-                            // Gather the necessary `IdAssignments`. Assignments are requested
-                            // by `Id`, prompting a binary search on `submission.assignments()`
-                            // (which was sorted by `Id` by `Broker::prepare`)
+                            // Gather the necessary `IdAssignments`
+
                             let id_assignments = unknown_ids
                                 .into_iter()
                                 .map(|id| {
-                                    // If `id` is not present in `submission.assignments()`, then
-                                    // `replica` is Byzantine
-                                    let index = submission
-                                        .assignments()
-                                        .binary_search_by_key(&id, |assignment| assignment.id())
-                                        .map_err(|_| SubmitError::MalformedResponse.into_top())
-                                        .spot(here!())?;
-
+                                    let index = Broker::locate(&submission, id)?;
                                     Ok(submission.assignments()[index].clone())
                                 })
                                 .collect::<Result<Vec<IdAssignment>, Top<SubmitError>>>()?;
@@ -291,23 +293,63 @@ impl Broker {
                                 .await
                                 .pot(SubmitError::ConnectionError, here!())?;
 
-                            // Receive witness shard (a correct `replica` cannot provide any
-                            // response other than `WitnessShard`)
-
-                            let response = session
+                            session
                                 .receive::<PrepareResponse>()
                                 .await
+                                .pot(SubmitError::ConnectionError, here!())?
+                        }
+                        response => response,
+                    };
+
+                    // If `response` is `LaggingIds`, then `replica` lags behind the commits of
+                    // some `Id`s, and requires a proof of their rotations to validate the submitted
+                    // signatures
+
+                    let response = match response {
+                        PrepareResponse::LaggingIds(lagging_ids) => {
+                            // Gather an `AccountProof` for each element of `lagging_ids`, along
+                            // with the `KeyRotation` (if any) provided by the relevant client
+
+                            let rotations = lagging_ids.into_iter().map(|id| {
+                                let submission = submission.clone();
+                                let view = &view;
+                                let query_connector = query_connector.as_ref();
+
+                                async move {
+                                    let index = Broker::locate(&submission, id)?;
+
+                                    let proof = Broker::prove(view, query_connector, id)
+                                        .await
+                                        .pot(SubmitError::ProofFailed, here!())?;
+
+                                    Ok::<_, Top<SubmitError>>((
+                                        proof,
+                                        submission.rotations()[index].clone(),
+                                    ))
+                                }
+                            });
+
+                            let rotations = future::try_join_all(rotations).await?;
+
+                            // Send `AccountProof`s (and `KeyRotation`s)
+
+                            session
+                                .send(&PrepareRequest::Rotations(rotations))
+                                .await
                                 .pot(SubmitError::ConnectionError, here!())?;
 
-                            match response {
-                                PrepareResponse::WitnessShard(shard) => Ok(shard),
-                                _ => SubmitError::UnexpectedResponse.fail().spot(here!()),
-                            }
+                            session
+                                .receive::<PrepareResponse>()
+                                .await
+                                .pot(SubmitError::ConnectionError, here!())?
                         }
+                        response => response,
+                    };
 
-                        // If `command` is `WitnessShard`, return witness shard
-                        PrepareResponse::WitnessShard(shard) => Ok(shard),
+                    // A correct `replica` cannot provide any response other than `WitnessShard`
 
+                    let shard = match response {
+                        PrepareResponse::WitnessShard(shard) => Ok(shard),
                         _ => SubmitError::UnexpectedResponse.fail().spot(here!()),
                     }?;
 
@@ -350,16 +392,63 @@ impl Broker {
                 .await
                 .pot(SubmitError::ConnectionError, here!())?;
 
-            // Receive `BatchCommitShard` (a correct `replica` cannot provide
-            // any response other than `CommitShard`)
+            // Receive `BatchCommitShard` (if requested to do so, first provide `replica`
+            // with the `Continuity`s it is missing)
 
             let response = session
                 .receive::<PrepareResponse>()
                 .await
                 .pot(SubmitError::ConnectionError, here!())?;
 
+            // `Id`s whose `Continuity` was queried by `replica` (only their `Prepare`s
+            // can be excepted as discontinuous, see `BatchCommitShard::validate`)
+            let mut queried = Vec::new();
+
             let shard = match response {
+                // If `response` is `MissingContinuities`, then `replica` cannot tell whether
+                // or not some clients reached the height below their `Prepare`s
+                PrepareResponse::MissingContinuities(missing_ids) => {
+                    // Gather the requested `Continuity`s (`None` if unavailable). `Continuity`s
+                    // are requested by `Id`, prompting a binary search on `submission.prepares()`
+                    let continuities = missing_ids
+                        .iter()
+                        .copied()
+                        .map(|id| {
+                            // If `id` is not present in `submission.prepares()`, then
+                            // `replica` is Byzantine
+                            let index = submission
+                                .prepares()
+                                .binary_search_by_key(&id, |prepare| prepare.id())
+                                .map_err(|_| SubmitError::MalformedResponse.into_top())
+                                .spot(here!())?;
+
+                            Ok(submission.continuities()[index].clone())
+                        })
+                        .collect::<Result<Vec<_>, Top<SubmitError>>>()?;
+
+                    queried = missing_ids;
+
+                    session
+                        .send(&PrepareRequest::Continuities(continuities))
+                        .await
+                        .pot(SubmitError::ConnectionError, here!())?;
+
+                    // Receive commit shard (a correct `replica` cannot provide any
+                    // response other than `CommitShard`)
+
+                    let response = session
+                        .receive::<PrepareResponse>()
+                        .await
+                        .pot(SubmitError::ConnectionError, here!())?;
+
+                    match response {
+                        PrepareResponse::CommitShard(shard) => Ok(shard),
+                        _ => SubmitError::UnexpectedResponse.fail().spot(here!()),
+                    }
+                }
+
                 PrepareResponse::CommitShard(shard) => Ok(shard),
+
                 _ => SubmitError::UnexpectedResponse.fail().spot(here!()),
             }?;
 
@@ -371,6 +460,8 @@ impl Broker {
                     &view,
                     submission.root(),
                     submission.prepares(),
+                    submission.continuities(),
+                    queried.as_slice(),
                     &replica,
                 )
                 .pot(SubmitError::InvalidCommitShard, here!())?;
@@ -386,6 +477,19 @@ impl Broker {
             Err(_) => update_inlet.send((replica.identity(), Update::Error)),
         };
     }
+
+    // Returns the index of `id` in `submission.assignments()`. Assignments are requested
+    // by `Id`, prompting a binary search on `submission.assignments()` (which was sorted
+    // by `Id` by `Broker::prepare`)
+    fn locate(submission: &Submission, id: Id) -> Result<usize, Top<SubmitError>> {
+        // If `id` is not present in `submission.assignments()`, then
+        // `replica` is Byzantine
+        submission
+            .assignments()
+            .binary_search_by_key(&id, |assignment| assignment.id())
+            .map_err(|_| SubmitError::MalformedResponse.into_top())
+            .spot(here!())
+    }
 }
 
 impl WitnessCollector {
@@ -0,0 +1,105 @@
+use crate::{
+    account::Id,
+    brokers::prepare::Broker,
+    processing::messages::{QueryRequest, QueryResponse},
+    query::{AccountProof, AccountProofAggregator, AccountProofShard},
+    view::View,
+};
+
+use doomstack::{here, Doom, ResultExt, Top};
+
+use futures::stream::{FuturesUnordered, StreamExt};
+
+use talk::{crypto::KeyCard, net::SessionConnector};
+
+#[derive(Doom)]
+pub(in crate::brokers::prepare::broker) enum ProveError {
+    #[doom(description("Failed to collect a plurality of matching `AccountProofShard`s"))]
+    ProofCollectionFailed,
+}
+
+#[derive(Doom)]
+enum QueryError {
+    #[doom(description("Connection failed"))]
+    ConnectionFailed,
+    #[doom(description("Connection error"))]
+    ConnectionError,
+    #[doom(description("Unexpected response"))]
+    UnexpectedResponse,
+    #[doom(description("Invalid `AccountProofShard`"))]
+    InvalidShard,
+}
+
+impl Broker {
+    /// Collects an `AccountProof` of `id` from the members of `view`.
+    pub(in crate::brokers::prepare::broker) async fn prove(
+        view: &View,
+        connector: &SessionConnector,
+        id: Id,
+    ) -> Result<AccountProof, Top<ProveError>> {
+        // Query every member of `view` until a plurality of members attests the same
+        // root (members lagging behind each other attest different roots: if no root
+        // is attested by a plurality of members, collection fails)
+
+        let mut shards = view
+            .members()
+            .values()
+            .map(|replica| async move {
+                let shard = Broker::proof_shard(view, connector, replica, id).await;
+                (replica, shard)
+            })
+            .collect::<FuturesUnordered<_>>();
+
+        let mut aggregator = AccountProofAggregator::new(view.clone());
+
+        while let Some((replica, shard)) = shards.next().await {
+            if let Ok(shard) = shard {
+                aggregator.add(replica, shard);
+
+                if aggregator.complete() {
+                    return Ok(aggregator.finalize());
+                }
+            }
+        }
+
+        ProveError::ProofCollectionFailed.fail().spot(here!())
+    }
+
+    async fn proof_shard(
+        view: &View,
+        connector: &SessionConnector,
+        replica: &KeyCard,
+        id: Id,
+    ) -> Result<AccountProofShard, Top<QueryError>> {
+        let mut session = connector
+            .connect(replica.identity())
+            .await
+            .pot(QueryError::ConnectionFailed, here!())?;
+
+        session
+            .send(&QueryRequest::Proof(id))
+            .await
+            .pot(QueryError::ConnectionError, here!())?;
+
+        let response = session
+            .receive::<QueryResponse>()
+            .await
+            .pot(QueryError::ConnectionError, here!())?;
+
+        session.end();
+
+        let shard = match response {
+            QueryResponse::Proof(shard) => shard,
+            _ => {
+                return QueryError::UnexpectedResponse.fail().spot(here!());
+            }
+        };
+
+        // `AccountProofAggregator` relies on `shard` being valid
+        shard
+            .validate(view, id, replica)
+            .pot(QueryError::InvalidShard, here!())?;
+
+        Ok(shard)
+    }
+}
@@ -1,7 +1,9 @@
 use serde::{Deserialize, Serialize};
 
+use std::time::Duration;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) enum BrokerFailure {
-    Throttle,
+    Throttle { retry_after: Option<Duration> },
     Error,
 }
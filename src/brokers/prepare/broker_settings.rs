@@ -1,9 +1,21 @@
-use crate::data::SpongeSettings;
+use crate::{
+    data::{AdmissionSettings, SpongeSettings},
+    transport::Transport,
+};
+
+use serde::Deserialize;
 
 use std::time::Duration;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
 pub(crate) struct BrokerSettings {
+    pub transport: Transport,
+    pub authenticate_clients: bool,
+    pub result_cache_capacity: usize,
+
+    pub admission_settings: AdmissionSettings,
+
     pub brokerage_sponge_settings: SpongeSettings,
 
     pub reduction_threshold: f64,
@@ -14,30 +26,45 @@ pub(crate) struct BrokerSettings {
 }
 
 pub(in crate::brokers::prepare) struct BrokerSettingsComponents {
+    pub frontend: FrontendTaskSettings,
     pub flush: FlushTaskSettings,
     pub broker: BrokerTaskSettings,
     pub ping: PingTaskSettings,
 }
+#[derive(Debug, Clone)]
+pub(in crate::brokers::prepare) struct FrontendTaskSettings {
+    pub transport: Transport,
+    pub authenticate_clients: bool,
+    pub result_cache_capacity: usize,
+    pub admission_settings: AdmissionSettings,
+}
+
 #[derive(Debug, Clone)]
 pub(in crate::brokers::prepare) struct FlushTaskSettings {
     pub brokerage_sponge_settings: SpongeSettings,
 }
 
 #[derive(Debug, Clone)]
-pub(in crate::brokers::prepare) struct BrokerTaskSettings {
+pub(in crate::brokers) struct BrokerTaskSettings {
     pub reduction_threshold: f64,
     pub reduction_timeout: Duration,
     pub optimistic_witness_timeout: Duration,
 }
 
 #[derive(Debug, Clone)]
-pub(in crate::brokers::prepare) struct PingTaskSettings {
+pub(in crate::brokers) struct PingTaskSettings {
     pub ping_interval: Duration,
 }
 
 impl BrokerSettings {
     pub(in crate::brokers::prepare) fn into_components(self) -> BrokerSettingsComponents {
         BrokerSettingsComponents {
+            frontend: FrontendTaskSettings {
+                transport: self.transport,
+                authenticate_clients: self.authenticate_clients,
+                result_cache_capacity: self.result_cache_capacity,
+                admission_settings: self.admission_settings,
+            },
             flush: FlushTaskSettings {
                 brokerage_sponge_settings: self.brokerage_sponge_settings,
             },
@@ -56,6 +83,12 @@ impl BrokerSettings {
 impl Default for BrokerSettings {
     fn default() -> Self {
         BrokerSettings {
+            transport: Transport::Secure,
+            authenticate_clients: false,
+            result_cache_capacity: 65536,
+
+            admission_settings: Default::default(),
+
             brokerage_sponge_settings: Default::default(),
 
             reduction_threshold: 1.,
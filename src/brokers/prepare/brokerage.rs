@@ -1,7 +1,7 @@
 use crate::{
     brokers::prepare::{BrokerFailure, Reduction, Request},
-    prepare::{BatchCommit, Prepare},
-    signup::IdAssignment,
+    prepare::{BatchCommit, Continuity, Prepare},
+    signup::{IdAssignment, KeyRotation},
 };
 
 use talk::crypto::primitives::sign::Signature;
@@ -10,7 +10,7 @@ use tokio::sync::oneshot::Sender;
 type ReductionInlet = Sender<Result<Reduction, BrokerFailure>>;
 type CommitInlet = Sender<Result<BatchCommit, BrokerFailure>>;
 
-pub(in crate::brokers::prepare) struct Brokerage {
+pub(in crate::brokers) struct Brokerage {
     pub request: Request,
     pub reduction_inlet: ReductionInlet,
     pub commit_inlet: CommitInlet,
@@ -18,6 +18,8 @@ pub(in crate::brokers::prepare) struct Brokerage {
 
 pub(in crate::brokers::prepare) struct UnzippedBrokerages {
     pub assignments: Vec<IdAssignment>,
+    pub rotations: Vec<Option<KeyRotation>>,
+    pub continuities: Vec<Option<Continuity>>,
     pub prepares: Vec<Prepare>,
     pub signatures: Vec<Signature>,
 
@@ -28,6 +30,8 @@ pub(in crate::brokers::prepare) struct UnzippedBrokerages {
 impl Brokerage {
     pub fn unzip(brokerages: Vec<Brokerage>) -> UnzippedBrokerages {
         let mut assignments = Vec::new();
+        let mut rotations = Vec::new();
+        let mut continuities = Vec::new();
         let mut prepares = Vec::new();
         let mut signatures = Vec::new();
 
@@ -39,6 +43,8 @@ impl Brokerage {
                 request:
                     Request {
                         assignment,
+                        rotation,
+                        continuity,
                         prepare,
                         signature,
                     },
@@ -47,6 +53,8 @@ impl Brokerage {
             } = brokerage;
 
             assignments.push(assignment);
+            rotations.push(rotation);
+            continuities.push(continuity);
             prepares.push(prepare);
             signatures.push(signature);
 
@@ -56,6 +64,8 @@ impl Brokerage {
 
         UnzippedBrokerages {
             assignments,
+            rotations,
+            continuities,
             prepares,
             signatures,
             reduction_inlets,
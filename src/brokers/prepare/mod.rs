@@ -3,15 +3,20 @@ mod broker_failure;
 mod broker_settings;
 mod brokerage;
 mod inclusion;
+mod outcome;
 mod reduction;
 mod request;
 mod submission;
 
 use broker_settings::BrokerSettingsComponents;
-use brokerage::{Brokerage, UnzippedBrokerages};
-use reduction::Reduction;
+use brokerage::UnzippedBrokerages;
+use outcome::{Outcome, OutcomeInlets};
 use submission::Submission;
 
+pub(in crate::brokers) use broker_settings::{BrokerTaskSettings, PingTaskSettings};
+pub(in crate::brokers) use brokerage::Brokerage;
+pub(in crate::brokers) use reduction::Reduction;
+
 pub(crate) use broker::Broker;
 pub(crate) use broker_failure::BrokerFailure;
 pub(crate) use broker_settings::BrokerSettings;
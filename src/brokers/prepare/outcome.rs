@@ -0,0 +1,75 @@
+use crate::{
+    brokers::prepare::{BrokerFailure, Reduction},
+    prepare::BatchCommit,
+};
+
+use std::sync::atomic::AtomicBool;
+
+use talk::crypto::primitives::hash::Hash;
+
+use tokio::sync::watch::{self, Receiver, Sender};
+
+type ReductionOutlet = Receiver<Option<Result<Reduction, BrokerFailure>>>;
+type CommitOutlet = Receiver<Option<Result<BatchCommit, BrokerFailure>>>;
+
+/// The (possibly pending) outcome of a `Brokerage`, shared by all `serve`
+/// tasks attached to it (i.e., the original submission and its resubmissions).
+pub(in crate::brokers::prepare) struct Outcome {
+    pub commitment: Hash,
+    pub reduced: AtomicBool,
+    reduction_outlet: ReductionOutlet,
+    commit_outlet: CommitOutlet,
+}
+
+pub(in crate::brokers::prepare) struct OutcomeInlets {
+    pub reduction_inlet: Sender<Option<Result<Reduction, BrokerFailure>>>,
+    pub commit_inlet: Sender<Option<Result<BatchCommit, BrokerFailure>>>,
+}
+
+impl Outcome {
+    pub fn new(commitment: Hash) -> (Self, OutcomeInlets) {
+        let (reduction_inlet, reduction_outlet) = watch::channel(None);
+        let (commit_inlet, commit_outlet) = watch::channel(None);
+
+        let outcome = Outcome {
+            commitment,
+            reduced: AtomicBool::new(false),
+            reduction_outlet,
+            commit_outlet,
+        };
+
+        let inlets = OutcomeInlets {
+            reduction_inlet,
+            commit_inlet,
+        };
+
+        (outcome, inlets)
+    }
+
+    /// Waits for the `Brokerage`'s `Reduction` (`None` if the `Brokerage` was forfeited).
+    pub async fn reduction(&self) -> Option<Result<Reduction, BrokerFailure>> {
+        wait(self.reduction_outlet.clone()).await
+    }
+
+    /// Waits for the `Brokerage`'s `BatchCommit` (`None` if the `Brokerage` was forfeited).
+    pub async fn commit(&self) -> Option<Result<BatchCommit, BrokerFailure>> {
+        wait(self.commit_outlet.clone()).await
+    }
+}
+
+async fn wait<T>(mut outlet: Receiver<Option<T>>) -> Option<T>
+where
+    T: Clone,
+{
+    loop {
+        let value = outlet.borrow().clone();
+
+        if value.is_some() {
+            return value;
+        }
+
+        // If `outlet.changed()` fails, the corresponding inlet was dropped
+        // without ever being fed a value
+        outlet.changed().await.ok()?;
+    }
+}
@@ -4,7 +4,8 @@ use std::sync::Arc;
 
 use talk::crypto::primitives::multi::Signature as MultiSignature;
 
-pub(in crate::brokers::prepare) struct Reduction {
+#[derive(Clone)]
+pub(in crate::brokers) struct Reduction {
     pub index: usize,
     pub inclusion: Inclusion,
     pub reduction_sponge: Arc<Sponge<(usize, MultiSignature)>>,
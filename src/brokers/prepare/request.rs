@@ -1,8 +1,8 @@
 use crate::{
     account::{Entry, Id},
     discovery::Client,
-    prepare::Prepare,
-    signup::IdAssignment,
+    prepare::{Continuity, Prepare},
+    signup::{IdAssignment, KeyRotation},
 };
 
 use doomstack::{here, Doom, ResultExt, Top};
@@ -17,6 +17,8 @@ use talk::crypto::{
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct Request {
     pub assignment: IdAssignment,
+    pub rotation: Option<KeyRotation>,
+    pub continuity: Option<Continuity>,
     pub prepare: Prepare,
     pub signature: Signature,
 }
@@ -27,6 +29,16 @@ pub(crate) enum RequestError {
     IdsMismatched,
     #[doom(description("`IdAssignment` invalid"))]
     AssignmentInvalid,
+    #[doom(description("`KeyRotation`'s `Id` does not match `Prepare`'s `Id`"))]
+    RotationMismatched,
+    #[doom(description("`KeyRotation` is not in effect at `Prepare`'s height"))]
+    RotationPremature,
+    #[doom(description("`KeyRotation` invalid"))]
+    RotationInvalid,
+    #[doom(description("`Continuity` is not for the height below `Prepare`'s"))]
+    ContinuityMismatched,
+    #[doom(description("`Continuity` invalid"))]
+    ContinuityInvalid,
     #[doom(description("`Signature` invalid"))]
     SignatureInvalid,
 }
@@ -35,6 +47,8 @@ impl Request {
     pub fn new(
         keychain: &KeyChain,
         assignment: IdAssignment,
+        rotation: Option<KeyRotation>,
+        continuity: Option<Continuity>,
         height: u64,
         commitment: Hash,
     ) -> Self {
@@ -49,6 +63,8 @@ impl Request {
 
         Request {
             assignment,
+            rotation,
+            continuity,
             prepare,
             signature,
         }
@@ -58,8 +74,13 @@ impl Request {
         self.assignment.id()
     }
 
+    /// Returns the `KeyCard` that signs `self.prepare()`: the rotated `KeyCard`,
+    /// if `self` carries a `KeyRotation`, the assigned `KeyCard` otherwise.
     pub fn keycard(&self) -> &KeyCard {
-        self.assignment.keycard()
+        match &self.rotation {
+            Some(rotation) => rotation.keycard(),
+            None => self.assignment.keycard(),
+        }
     }
 
     pub fn prepare(&self) -> &Prepare {
@@ -75,8 +96,37 @@ impl Request {
             .validate(&discovery)
             .pot(RequestError::AssignmentInvalid, here!())?;
 
+        // Replicas ultimately check `self.prepare` against the `KeyCard` they know to
+        // be current: a stale `KeyRotation` can only make `self.prepare` fail later
+
+        if let Some(rotation) = &self.rotation {
+            if rotation.id() != self.prepare.id() {
+                return RequestError::RotationMismatched.fail().spot(here!());
+            }
+
+            if rotation.height() > self.prepare.height() {
+                return RequestError::RotationPremature.fail().spot(here!());
+            }
+
+            rotation
+                .validate(&discovery)
+                .pot(RequestError::RotationInvalid, here!())?;
+        }
+
+        if let Some(continuity) = &self.continuity {
+            let entry = continuity.entry();
+
+            if entry.id != self.prepare.id() || entry.height + 1 != self.prepare.height() {
+                return RequestError::ContinuityMismatched.fail().spot(here!());
+            }
+
+            continuity
+                .validate(&discovery)
+                .pot(RequestError::ContinuityInvalid, here!())?;
+        }
+
         self.signature
-            .verify(&self.assignment.keycard(), &self.prepare)
+            .verify(self.keycard(), &self.prepare)
             .pot(RequestError::SignatureInvalid, here!())?;
 
         Ok(())
@@ -1,4 +1,8 @@
-use crate::{prepare::Prepare, processing::messages::PrepareRequest, signup::IdAssignment};
+use crate::{
+    prepare::{Continuity, Prepare},
+    processing::messages::PrepareRequest,
+    signup::{IdAssignment, KeyRotation},
+};
 
 use talk::crypto::primitives::{hash::Hash, multi::Signature as MultiSignature, sign::Signature};
 
@@ -6,6 +10,8 @@ use zebra::vector::Vector;
 
 pub(in crate::brokers::prepare) struct Submission {
     assignments: Vec<IdAssignment>,
+    rotations: Vec<Option<KeyRotation>>,
+    continuities: Vec<Option<Continuity>>,
     pub requests: Requests,
 }
 
@@ -25,12 +31,16 @@ pub(in crate::brokers::prepare) struct Requests {
 impl Submission {
     pub fn new(
         assignments: Vec<IdAssignment>,
+        rotations: Vec<Option<KeyRotation>>,
+        continuities: Vec<Option<Continuity>>,
         prepares: Vector<Prepare>,
         reduction_signature: MultiSignature,
         individual_signatures: Vec<Option<Signature>>,
     ) -> Self {
         Submission {
             assignments,
+            rotations,
+            continuities,
             requests: Requests {
                 batch: PrepareRequest::Batch(prepares),
                 signatures: PrepareRequest::Signatures(reduction_signature, individual_signatures),
@@ -46,6 +56,16 @@ impl Submission {
         self.assignments.as_slice()
     }
 
+    /// Returns the `KeyRotation` (if any) of each element of `self.assignments()`.
+    pub fn rotations(&self) -> &[Option<KeyRotation>] {
+        self.rotations.as_slice()
+    }
+
+    /// Returns the `Continuity` (if any) of each element of `self.prepares()`.
+    pub fn continuities(&self) -> &[Option<Continuity>] {
+        self.continuities.as_slice()
+    }
+
     pub fn prepares(&self) -> &[Prepare] {
         self.requests.prepares().items()
     }
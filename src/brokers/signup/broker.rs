@@ -1,9 +1,10 @@
 use crate::{
     brokers::signup::{BrokerFailure, BrokerSettings},
     crypto::Identify,
-    data::Sponge,
+    data::{Admission, Sponge},
     processing::messages::{SignupRequest, SignupResponse},
     signup::{IdAssignment, IdAssignmentAggregator, IdClaim, IdRequest, SignupSettings},
+    transport::{Connection, Transport},
     view::View,
 };
 
@@ -11,19 +12,25 @@ use doomstack::{here, Doom, ResultExt, Top};
 
 use futures::stream::{FuturesUnordered, StreamExt};
 
-use std::{collections::HashMap, net::SocketAddr, sync::Arc};
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use talk::{
-    crypto::Identity,
+    crypto::{Identity, KeyChain},
     link::context::ConnectDispatcher,
-    net::{Connector, PlainConnection, SessionConnector},
+    net::{Connector, SessionConnector},
     sync::fuse::Fuse,
 };
 
 use tokio::{
     io,
-    net::{TcpListener, ToSocketAddrs},
+    net::{TcpListener, TcpStream, ToSocketAddrs},
     sync::oneshot::{self, Receiver, Sender},
+    time,
 };
 
 type OutcomeInlet = Sender<Result<IdAssignment, BrokerFailure>>;
@@ -31,6 +38,7 @@ type OutcomeOutlet = Receiver<Result<IdAssignment, BrokerFailure>>;
 
 pub(crate) struct Broker {
     address: SocketAddr,
+    identity: Identity,
     _fuse: Fuse,
 }
 
@@ -55,8 +63,12 @@ pub(crate) enum BrokerError {
 
 #[derive(Doom)]
 enum ServeError {
+    #[doom(description("Failed to accept the connection"))]
+    AcceptFailed,
     #[doom(description("Connection error"))]
     ConnectionError,
+    #[doom(description("Client authenticated with a foreign `KeyCard`"))]
+    ForeignClient,
     #[doom(description("Request invalid"))]
     RequestInvalid,
     #[doom(description("Request pertains to a foreign view"))]
@@ -92,6 +104,7 @@ enum SubmitError {
 
 impl Broker {
     pub async fn new<A, C>(
+        keychain: KeyChain,
         view: View,
         address: A,
         connector: C,
@@ -124,7 +137,13 @@ impl Broker {
                 .collect::<HashMap<_, _>>(),
         );
 
+        let identity = keychain.keycard().identity();
+
         let signup_settings = settings.signup_settings;
+        let transport = settings.transport;
+        let authenticate_clients = settings.authenticate_clients;
+        let admission = Admission::new(settings.admission_settings);
+
         let fuse = Fuse::new();
 
         {
@@ -133,7 +152,17 @@ impl Broker {
             let signup_settings = signup_settings.clone();
 
             fuse.spawn(async move {
-                Broker::listen(view, sponges, listener, signup_settings).await;
+                Broker::listen(
+                    keychain,
+                    view,
+                    sponges,
+                    listener,
+                    transport,
+                    authenticate_clients,
+                    admission,
+                    signup_settings,
+                )
+                .await;
             });
         }
 
@@ -150,6 +179,7 @@ impl Broker {
 
         Ok(Broker {
             address,
+            identity,
             _fuse: fuse,
         })
     }
@@ -158,40 +188,98 @@ impl Broker {
         self.address
     }
 
+    pub fn identity(&self) -> Identity {
+        self.identity
+    }
+
     async fn listen(
+        keychain: KeyChain,
         view: View,
         sponges: Arc<HashMap<Identity, Sponge<Brokerage>>>,
         listener: TcpListener,
+        transport: Transport,
+        authenticate_clients: bool,
+        admission: Admission<Identity>,
         signup_settings: SignupSettings,
     ) {
+        let keychain = Arc::new(keychain);
+        let admission = Arc::new(admission);
+
         let fuse = Fuse::new();
 
         loop {
             if let Ok((stream, _)) = listener.accept().await {
-                let connection: PlainConnection = stream.into();
+                // Connections in excess of `max_connections` are throttled, then closed
+                // unserved (a refusal outlasting `retry_after` is abandoned: by then,
+                // the client might as well retry)
+
+                let connection_permit = match admission.connect() {
+                    Ok(permit) => permit,
+                    Err(retry_after) => {
+                        let keychain = keychain.clone();
+
+                        fuse.spawn(async move {
+                            let refusal =
+                                Broker::refuse(keychain.as_ref(), stream, transport, retry_after);
 
+                            let _ = time::timeout(retry_after, refusal).await;
+                        });
+
+                        continue;
+                    }
+                };
+
+                let keychain = keychain.clone();
                 let view = view.clone();
                 let sponges = sponges.clone();
+                let admission = admission.clone();
                 let signup_settings = signup_settings.clone();
 
                 fuse.spawn(async move {
-                    let _ = Broker::serve(connection, view, sponges, signup_settings).await;
+                    let _ = Broker::serve(
+                        stream,
+                        keychain.as_ref(),
+                        view,
+                        sponges,
+                        transport,
+                        authenticate_clients,
+                        admission.as_ref(),
+                        signup_settings,
+                    )
+                    .await;
+
+                    drop(connection_permit);
                 });
             }
         }
     }
 
     async fn serve(
-        mut connection: PlainConnection,
+        stream: TcpStream,
+        keychain: &KeyChain,
         view: View,
         sponges: Arc<HashMap<Identity, Sponge<Brokerage>>>,
+        transport: Transport,
+        authenticate_clients: bool,
+        admission: &Admission<Identity>,
         signup_settings: SignupSettings,
     ) -> Result<(), Top<ServeError>> {
+        let (mut connection, client) = Connection::accept(stream, keychain, transport)
+            .await
+            .pot(ServeError::AcceptFailed, here!())?;
+
         let request = connection
             .receive::<IdRequest>()
             .await
             .pot(ServeError::ConnectionError, here!())?;
 
+        // If required, the client must authenticate as the account it signs up
+        if authenticate_clients
+            && client.map(|client| client.identity()) != Some(request.client().identity())
+        {
+            return ServeError::ForeignClient.fail().spot(here!());
+        }
+
         request
             .validate(signup_settings.work_difficulty)
             .pot(ServeError::RequestInvalid, here!())?;
@@ -204,6 +292,19 @@ impl Broker {
             .get(&request.allocator())
             .ok_or(ServeError::ForeignAllocator.into_top().spot(here!()))?;
 
+        // Throttle clients submitting requests in excess of their rate, and new
+        // `Brokerage`s in excess of `max_brokerages` (the permit is held until
+        // the `Brokerage` is resolved, or the served client goes away)
+
+        if let Err(retry_after) = admission.submit(request.client().identity()) {
+            return Broker::throttle(connection, retry_after).await;
+        }
+
+        let _brokerage_permit = match admission.broker() {
+            Ok(permit) => permit,
+            Err(retry_after) => return Broker::throttle(connection, retry_after).await,
+        };
+
         let (outcome_inlet, outcome_outlet) = oneshot::channel();
 
         let brokerage = Brokerage {
@@ -227,6 +328,33 @@ impl Broker {
         Ok(())
     }
 
+    async fn refuse(
+        keychain: &KeyChain,
+        stream: TcpStream,
+        transport: Transport,
+        retry_after: Duration,
+    ) -> Result<(), Top<ServeError>> {
+        let (connection, _) = Connection::accept(stream, keychain, transport)
+            .await
+            .pot(ServeError::AcceptFailed, here!())?;
+
+        Broker::throttle(connection, retry_after).await
+    }
+
+    async fn throttle(
+        mut connection: Connection,
+        retry_after: Duration,
+    ) -> Result<(), Top<ServeError>> {
+        let failure = BrokerFailure::Throttle {
+            retry_after: Some(retry_after),
+        };
+
+        connection
+            .send::<Result<IdAssignment, BrokerFailure>>(&Err(failure))
+            .await
+            .pot(ServeError::ConnectionError, here!())
+    }
+
     async fn flush(
         view: View,
         allocator: Identity,
@@ -245,11 +373,14 @@ impl Broker {
             }
 
             let view = view.clone();
+            let sponges = sponges.clone();
             let connector = connector.clone();
             let signup_settings = signup_settings.clone();
 
             fuse.spawn(async move {
+                let start = Instant::now();
                 Broker::broker(view, allocator, connector, brokerages, signup_settings).await;
+                sponges.get(&allocator).unwrap().observe(start.elapsed());
             });
         }
     }
@@ -270,7 +401,9 @@ impl Broker {
             .into_iter()
             .filter_map(|brokerage| {
                 if Some(brokerage.request.client()) == previous {
-                    let _ = brokerage.outcome_inlet.send(Err(BrokerFailure::Throttle));
+                    let _ = brokerage
+                        .outcome_inlet
+                        .send(Err(BrokerFailure::Throttle { retry_after: None }));
                     None
                 } else {
                     previous = Some(brokerage.request.client());
@@ -602,9 +735,7 @@ mod tests {
 
     use crate::brokers::test::System;
 
-    use talk::crypto::KeyChain;
-
-    use tokio::net::TcpStream;
+    use talk::net::PlainConnection;
 
     #[tokio::test]
     async fn stress() {
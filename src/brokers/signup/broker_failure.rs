@@ -2,9 +2,13 @@ use crate::signup::IdClaim;
 
 use serde::{Deserialize, Serialize};
 
+use std::time::Duration;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) enum BrokerFailure {
-    Throttle,
+    Throttle {
+        retry_after: Option<Duration>,
+    },
     Error,
     Collision {
         brokered: IdClaim,
@@ -1,7 +1,19 @@
-use crate::{data::SpongeSettings, signup::SignupSettings};
+use crate::{
+    data::{AdmissionSettings, SpongeSettings},
+    signup::SignupSettings,
+    transport::Transport,
+};
 
-#[derive(Debug, Clone, Default)]
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
 pub(crate) struct BrokerSettings {
     pub signup_settings: SignupSettings,
     pub sponge_settings: SpongeSettings,
+
+    pub transport: Transport,
+    pub authenticate_clients: bool,
+
+    pub admission_settings: AdmissionSettings,
 }
@@ -1,11 +1,14 @@
 use crate::{
     brokers::{
-        commit::Broker as CommitBroker, prepare::Broker as PrepareBroker,
-        signup::Broker as SignupBroker,
+        commit::{Broker as CommitBroker, BrokerSettings as CommitBrokerSettings},
+        pipeline::{Broker as PipelineBroker, BrokerSettings as PipelineBrokerSettings},
+        prepare::{Broker as PrepareBroker, BrokerSettings as PrepareBrokerSettings},
+        signup::{Broker as SignupBroker, BrokerSettings as SignupBrokerSettings},
     },
     database::Database,
     discovery::{self, Client, Mode, Server},
-    processing::Processor,
+    processing::{test::TestBroker, Processor, ProcessorSettings},
+    transport::Transport,
     view::View,
 };
 
@@ -21,6 +24,9 @@ pub(crate) struct System {
     pub signup_brokers: Vec<SignupBroker>,
     pub prepare_brokers: Vec<PrepareBroker>,
     pub commit_brokers: Vec<CommitBroker>,
+    pub pipeline_brokers: Vec<PipelineBroker>,
+    // Queries processors directly (e.g., to inspect their state)
+    pub test_broker: TestBroker,
 }
 
 impl System {
@@ -29,6 +35,45 @@ impl System {
         signup_brokers: usize,
         prepare_brokers: usize,
         commit_brokers: usize,
+    ) -> Self {
+        System::setup_with_settings(
+            processors,
+            signup_brokers,
+            prepare_brokers,
+            commit_brokers,
+            Default::default(),
+        )
+        .await
+    }
+
+    pub async fn setup_with_settings(
+        processors: usize,
+        signup_brokers: usize,
+        prepare_brokers: usize,
+        commit_brokers: usize,
+        processor_settings: ProcessorSettings,
+    ) -> Self {
+        // Broker tests talk to brokers over raw `PlainConnection`s
+        System::setup_with_transport(
+            processors,
+            signup_brokers,
+            prepare_brokers,
+            commit_brokers,
+            0,
+            processor_settings,
+            Transport::Plain,
+        )
+        .await
+    }
+
+    pub async fn setup_with_transport(
+        processors: usize,
+        signup_brokers: usize,
+        prepare_brokers: usize,
+        commit_brokers: usize,
+        pipeline_brokers: usize,
+        processor_settings: ProcessorSettings,
+        transport: Transport,
     ) -> Self {
         let (install_generator, discovery_server, _, mut discovery_clients, _) =
             discovery::test::setup(processors, processors, Mode::Full).await;
@@ -57,6 +102,14 @@ impl System {
 
         commit_broker_keychains.sort_by_key(|keychain| keychain.keycard().identity());
 
+        let mut pipeline_broker_keychains = (0..pipeline_brokers)
+            .map(|_| KeyChain::random())
+            .collect::<Vec<_>>();
+
+        pipeline_broker_keychains.sort_by_key(|keychain| keychain.keycard().identity());
+
+        let test_broker_keychain = KeyChain::random();
+
         let NetSystem {
             mut connectors,
             mut listeners,
@@ -67,7 +120,9 @@ impl System {
                 .cloned()
                 .chain(signup_broker_keychains.iter().cloned())
                 .chain(prepare_broker_keychains.iter().cloned())
-                .chain(commit_broker_keychains.iter().cloned()),
+                .chain(commit_broker_keychains.iter().cloned())
+                .chain(pipeline_broker_keychains.iter().cloned())
+                .chain(vec![test_broker_keychain.clone()]),
         )
         .await;
 
@@ -83,7 +138,7 @@ impl System {
                         Database::new(),
                         connectors.remove(0),
                         listeners.remove(0),
-                        Default::default(),
+                        processor_settings.clone(),
                     ),
                 )
             })
@@ -91,13 +146,17 @@ impl System {
 
         let mut signup_brokers = Vec::new();
 
-        for _ in signup_broker_keychains {
+        for keychain in signup_broker_keychains {
             signup_brokers.push(
                 SignupBroker::new(
+                    keychain,
                     view.clone(),
                     (Ipv4Addr::LOCALHOST, 0),
                     connectors.remove(0),
-                    Default::default(),
+                    SignupBrokerSettings {
+                        transport,
+                        ..Default::default()
+                    },
                 )
                 .await
                 .unwrap(),
@@ -106,14 +165,18 @@ impl System {
 
         let mut prepare_brokers = Vec::new();
 
-        for _ in prepare_broker_keychains {
+        for keychain in prepare_broker_keychains {
             prepare_brokers.push(
                 PrepareBroker::new(
+                    keychain,
                     discovery_client.clone(),
                     view.clone(),
                     (Ipv4Addr::LOCALHOST, 0),
                     connectors.remove(0),
-                    Default::default(),
+                    PrepareBrokerSettings {
+                        transport,
+                        ..Default::default()
+                    },
                 )
                 .await
                 .unwrap(),
@@ -122,19 +185,46 @@ impl System {
 
         let mut commit_brokers = Vec::new();
 
-        for _ in commit_broker_keychains {
+        for keychain in commit_broker_keychains {
             commit_brokers.push(
                 CommitBroker::new(
+                    keychain,
+                    discovery_client.clone(),
+                    view.clone(),
+                    (Ipv4Addr::LOCALHOST, 0),
+                    connectors.remove(0),
+                    CommitBrokerSettings {
+                        transport,
+                        ..Default::default()
+                    },
+                )
+                .await
+                .unwrap(),
+            );
+        }
+
+        let mut pipeline_brokers = Vec::new();
+
+        for keychain in pipeline_broker_keychains {
+            pipeline_brokers.push(
+                PipelineBroker::new(
+                    keychain,
                     discovery_client.clone(),
                     view.clone(),
                     (Ipv4Addr::LOCALHOST, 0),
                     connectors.remove(0),
+                    PipelineBrokerSettings {
+                        transport,
+                        ..Default::default()
+                    },
                 )
                 .await
                 .unwrap(),
             );
         }
 
+        let test_broker = TestBroker::new(test_broker_keychain, view.clone(), connectors.remove(0));
+
         System {
             view,
             discovery_server,
@@ -143,6 +233,8 @@ impl System {
             signup_brokers,
             prepare_brokers,
             commit_brokers,
+            pipeline_brokers,
+            test_broker,
         }
     }
 }
@@ -11,4 +11,6 @@ pub(crate) use resignation::Resignation;
 pub(crate) use resignation::ResignationClaim;
 #[allow(unused_imports)]
 pub(crate) use resolution::Resolution;
+#[allow(unused_imports)]
+pub(crate) use resolution::ResolutionAggregator;
 pub(crate) use resolution::ResolutionClaim;
@@ -1,5 +1,5 @@
 use crate::{
-    crypto::{Certificate, Header, Identify},
+    crypto::{Aggregator, Certificate, Header, Identify},
     discovery::Client,
     view::{Change, View},
 };
@@ -8,7 +8,13 @@ use doomstack::{here, Doom, ResultExt, Top};
 
 use serde::{Deserialize, Serialize};
 
-use talk::crypto::{primitives::hash::Hash, Statement as CryptoStatement};
+use talk::crypto::{
+    primitives::{
+        hash::Hash,
+        multi::{MultiError, Signature as MultiSignature},
+    },
+    KeyCard, KeyChain, Statement as CryptoStatement,
+};
 
 #[derive(Clone, Serialize)]
 #[serde(into = "ResolutionClaim")]
@@ -26,6 +32,11 @@ struct Statement {
     change: Change,
 }
 
+pub(crate) struct ResolutionAggregator {
+    view: Hash,
+    aggregator: Aggregator<Statement>,
+}
+
 #[derive(Doom)]
 pub(crate) enum ResolutionError {
     #[doom(description("The `Resolution` pertains to an unknown `View`"))]
@@ -39,6 +50,12 @@ pub(crate) enum ResolutionError {
 }
 
 impl Resolution {
+    pub fn certify(keychain: &KeyChain, change: Change) -> MultiSignature {
+        keychain
+            .multisign(&Statement { change })
+            .expect("Panic at `Resolution::certify`: unexpected error from `keychain.multisign`")
+    }
+
     pub fn change(&self) -> Change {
         self.0.change()
     }
@@ -84,6 +101,41 @@ impl ResolutionClaim {
     }
 }
 
+impl ResolutionAggregator {
+    pub fn new(view: View, change: Change) -> Self {
+        ResolutionAggregator {
+            view: view.identifier(),
+            aggregator: Aggregator::new(view, Statement { change }),
+        }
+    }
+
+    pub fn add(
+        &mut self,
+        keycard: &KeyCard,
+        signature: MultiSignature,
+    ) -> Result<(), Top<MultiError>> {
+        self.aggregator.add(keycard, signature)
+    }
+
+    pub fn multiplicity(&self) -> usize {
+        self.aggregator.multiplicity()
+    }
+
+    pub fn complete(&self) -> bool {
+        self.aggregator.multiplicity() >= self.aggregator.view().quorum()
+    }
+
+    pub fn finalize(self) -> Resolution {
+        let (statement, certificate) = self.aggregator.finalize_quorum();
+
+        Resolution(ResolutionClaim {
+            view: self.view,
+            statement,
+            certificate,
+        })
+    }
+}
+
 impl Identify for Resolution {
     fn identifier(&self) -> Hash {
         self.0.identifier()
@@ -106,3 +158,42 @@ impl CryptoStatement for Statement {
     type Header = Header;
     const HEADER: Header = Header::Resolution;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{
+        churn::Churn,
+        discovery::{self, Mode},
+    };
+
+    use talk::crypto::KeyChain;
+
+    #[tokio::test]
+    async fn quorum() {
+        let (install_generator, _discovery_server, _, mut discovery_clients, _) =
+            discovery::test::setup(4, 4, Mode::Full).await;
+
+        let client = discovery_clients.next().unwrap();
+        let view = install_generator.view(4);
+
+        let change = Change::Join(KeyChain::random().keycard());
+        let mut aggregator = ResolutionAggregator::new(view.clone(), change.clone());
+
+        for keychain in install_generator.keychains.iter().take(view.quorum()) {
+            assert!(!aggregator.complete());
+
+            let signature = Resolution::certify(keychain, change.clone());
+            aggregator.add(&keychain.keycard(), signature).unwrap();
+        }
+
+        assert!(aggregator.complete());
+
+        let resolution = aggregator.finalize();
+        assert_eq!(resolution.change(), change);
+
+        let churn = Churn::Resolution(resolution.into());
+        assert_eq!(churn.to_change(&client, &view).unwrap(), change);
+    }
+}
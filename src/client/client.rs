@@ -0,0 +1,1001 @@
+use crate::{
+    account::{Corruption, Entry, Id, Operation},
+    brokers::{
+        commit::{BrokerFailure as CommitBrokerFailure, Request as CommitRequest},
+        pipeline::{BrokerFailure as PipelineBrokerFailure, Request as PipelineRequest},
+        prepare::{BrokerFailure as PrepareBrokerFailure, Inclusion, Request as PrepareRequest},
+        signup::BrokerFailure as SignupBrokerFailure,
+    },
+    client::{ClientSettings, DepositTracker},
+    commit::{Commit, CommitProof, Completion, CompletionProof, Payload},
+    discovery::Client as DiscoveryClient,
+    prepare::{BatchCommit, Continuity},
+    signup::{IdAssignment, IdRequest, KeyRotation},
+    transport::Connection,
+    view::View,
+};
+
+use doomstack::{here, Doom, ResultExt, Top};
+
+use std::{net::SocketAddr, sync::Arc};
+
+use talk::crypto::{primitives::hash::Hash, Identity, KeyChain};
+
+use tokio::time;
+
+/// Drives `Operation`s on a single account through the prepare and commit brokers,
+/// keeping track of the account's `IdAssignment` (and latest `KeyRotation`),
+/// height (along with the `Completion` that reached it) and deposits.
+///
+/// Remark: an `Operation` that fails after being submitted is kept pending, and is
+/// resumed (see `Client::resume`) before any other `Operation` is submitted: issuing
+/// another `Operation` at the same height would equivocate the account.
+pub struct Client {
+    keychain: KeyChain,
+    discovery: Arc<DiscoveryClient>,
+    assignment: IdAssignment,
+    rotation: Option<KeyRotation>,
+    height: u64,
+    continuity: Option<Continuity>,
+    corruption: Option<Corruption>,
+    deposits: DepositTracker,
+    pending: Option<Pending>,
+    prepare_brokers: Vec<(SocketAddr, Identity)>,
+    commit_brokers: Vec<(SocketAddr, Identity)>,
+    pipeline_brokers: Vec<(SocketAddr, Identity)>,
+    settings: ClientSettings,
+}
+
+// An `Operation` submitted at the height following `Client::height`, but not (yet)
+// completed. Once prepared, its `Commit` is retained: retries skip preparation.
+struct Pending {
+    payload: Payload,
+    dependency: Option<Completion>,
+    commit: Option<Commit>,
+    settlement: Settlement,
+}
+
+// Updates to a `Client`'s bookkeeping, applied once its pending `Operation` completes
+enum Settlement {
+    None,
+    Rotate(KeyChain),
+    Deposit { withdrawal: Entry, collect: bool },
+}
+
+#[derive(Doom)]
+pub enum ClientError {
+    #[doom(description("No broker available"))]
+    NoBrokers,
+    #[doom(description("Failed to sign up (all attempts exhausted)"))]
+    SignupFailed,
+    #[doom(description("Failed to prepare (all attempts exhausted)"))]
+    PrepareFailed,
+    #[doom(description("Failed to commit (all attempts exhausted)"))]
+    CommitFailed,
+    #[doom(description(
+        "Failed to prepare and commit through a pipeline broker (all attempts exhausted)"
+    ))]
+    PipelineFailed,
+    #[doom(description(
+        "`Operation` excepted (the account is now corrupted, see `Client::corruption`)"
+    ))]
+    OperationExcepted,
+    #[doom(description("Withdrawal invalid"))]
+    WithdrawalInvalid,
+}
+
+#[derive(Doom)]
+enum AttemptError {
+    #[doom(description("Failed to establish a connection"))]
+    ConnectionFailed,
+    #[doom(description("Connection error"))]
+    ConnectionError,
+    #[doom(description("`IdAssignment` invalid"))]
+    AssignmentInvalid,
+    #[doom(description("`Inclusion` invalid"))]
+    InclusionInvalid,
+    #[doom(description("`Commit` invalid"))]
+    CommitInvalid,
+    #[doom(description("`CompletionProof` invalid"))]
+    CompletionInvalid,
+}
+
+impl Client {
+    /// Signs up a new account for `keychain`, then returns a `Client` to operate it.
+    /// Each broker is identified by its address and the `Identity` it authenticates
+    /// with (see `ClientSettings::transport`).
+    pub async fn signup(
+        keychain: KeyChain,
+        view: View,
+        discovery: Arc<DiscoveryClient>,
+        signup_brokers: Vec<(SocketAddr, Identity)>,
+        prepare_brokers: Vec<(SocketAddr, Identity)>,
+        commit_brokers: Vec<(SocketAddr, Identity)>,
+        settings: ClientSettings,
+    ) -> Result<Self, Top<ClientError>> {
+        if signup_brokers.is_empty() || prepare_brokers.is_empty() || commit_brokers.is_empty() {
+            return ClientError::NoBrokers.fail().spot(here!());
+        }
+
+        // Cycle through both allocators and signup brokers (an `IdRequest`
+        // is directed to a specific allocator)
+        let allocators = view.members().keys().copied().collect::<Vec<_>>();
+
+        for attempt in 0..settings.max_attempts {
+            let allocator = allocators[attempt % allocators.len()];
+            let broker = signup_brokers[attempt % signup_brokers.len()];
+
+            let request = IdRequest::new(
+                &keychain,
+                &view,
+                allocator,
+                settings.signup_settings.work_difficulty,
+            );
+
+            match Client::signup_attempt(&keychain, discovery.as_ref(), broker, &request, &settings)
+                .await
+            {
+                Ok(Ok(assignment)) => {
+                    let deposits = DepositTracker::new(assignment.id());
+
+                    return Ok(Client {
+                        keychain,
+                        discovery,
+                        assignment,
+                        rotation: None,
+                        height: 0,
+                        continuity: None,
+                        corruption: None,
+                        deposits,
+                        pending: None,
+                        prepare_brokers,
+                        commit_brokers,
+                        pipeline_brokers: Vec::new(),
+                        settings,
+                    });
+                }
+                Ok(Err(SignupBrokerFailure::Throttle { retry_after })) => {
+                    time::sleep(retry_after.unwrap_or(settings.throttle_backoff)).await;
+                }
+                Ok(Err(_)) | Err(_) => {}
+            }
+        }
+
+        ClientError::SignupFailed.fail().spot(here!())
+    }
+
+    pub fn id(&self) -> Id {
+        self.assignment.id()
+    }
+
+    pub fn assignment(&self) -> &IdAssignment {
+        &self.assignment
+    }
+
+    pub fn rotation(&self) -> Option<&KeyRotation> {
+        self.rotation.as_ref()
+    }
+
+    pub fn height(&self) -> u64 {
+        self.height
+    }
+
+    /// Routes all subsequent `Operation`s through `brokers` (if non-empty): each pipeline
+    /// broker prepares and commits an `Operation` in a single brokerage, sparing the
+    /// round trip to a separate commit broker.
+    pub fn set_pipeline_brokers(&mut self, brokers: Vec<(SocketAddr, Identity)>) {
+        self.pipeline_brokers = brokers;
+    }
+
+    /// Returns the (certified) evidence of why `self`'s account was corrupted,
+    /// if one of `self`'s `Operation`s was excepted.
+    pub fn corruption(&self) -> Option<&Corruption> {
+        self.corruption.as_ref()
+    }
+
+    /// The slot a withdrawal to `self` must target to be deposited.
+    pub fn slot(&self) -> u64 {
+        self.deposits.slot()
+    }
+
+    pub async fn withdraw(
+        &mut self,
+        beneficiary: Id,
+        slot: u64,
+        amount: u64,
+    ) -> Result<Completion, Top<ClientError>> {
+        self.perform(
+            Operation::withdraw(beneficiary, slot, amount),
+            None,
+            Settlement::None,
+        )
+        .await
+    }
+
+    /// Debits `self`'s account once to pay every `(beneficiary, slot, amount)`
+    /// leg in `legs`. Each beneficiary deposits its own leg.
+    pub async fn transfer<L>(&mut self, legs: L) -> Result<Completion, Top<ClientError>>
+    where
+        L: IntoIterator<Item = (Id, u64, u64)>,
+    {
+        self.perform(Operation::transfer(legs), None, Settlement::None)
+            .await
+    }
+
+    /// Rotates `self`'s key: all subsequent `Operation`s are signed by `keychain`.
+    pub async fn rotate(&mut self, keychain: KeyChain) -> Result<Completion, Top<ClientError>> {
+        let operation = Operation::rotate(keychain.keycard());
+
+        self.perform(operation, None, Settlement::Rotate(keychain))
+            .await
+    }
+
+    /// Deposits `withdrawal` onto `self`'s account. If `collect` is `true`,
+    /// `self`'s deposit slot is advanced.
+    pub async fn deposit(
+        &mut self,
+        withdrawal: Completion,
+        collect: bool,
+    ) -> Result<Completion, Top<ClientError>> {
+        // A pending deposit affects which deposits are applicable
+        self.resume().await?;
+
+        // Inapplicable deposits are rejected before being submitted
+        let operation = self
+            .deposits
+            .deposit(&withdrawal, collect)
+            .pot(ClientError::WithdrawalInvalid, here!())?;
+
+        withdrawal
+            .validate(self.discovery.as_ref())
+            .pot(ClientError::WithdrawalInvalid, here!())?;
+
+        let settlement = Settlement::Deposit {
+            withdrawal: withdrawal.entry(),
+            collect,
+        };
+
+        self.perform(operation, Some(withdrawal), settlement).await
+    }
+
+    /// Queues `withdrawal` (addressed to `self`) for deposit (see `deposit_next`).
+    pub fn receive(&mut self, withdrawal: Completion) -> Result<(), Top<ClientError>> {
+        withdrawal
+            .validate(self.discovery.as_ref())
+            .pot(ClientError::WithdrawalInvalid, here!())?;
+
+        self.deposits
+            .scan(withdrawal)
+            .pot(ClientError::WithdrawalInvalid, here!())
+    }
+
+    /// Collects the current deposit slot once all received withdrawals to it are deposited.
+    pub fn close_slot(&mut self) {
+        self.deposits.close();
+    }
+
+    /// Deposits the next received withdrawal (if any), collecting the
+    /// current slot when appropriate (see `DepositTracker`).
+    pub async fn deposit_next(&mut self) -> Result<Option<Completion>, Top<ClientError>> {
+        match self.deposits.next() {
+            Some((withdrawal, collect)) => self.deposit(withdrawal, collect).await.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    pub async fn support(&mut self, motion: Hash) -> Result<Completion, Top<ClientError>> {
+        self.perform(Operation::support(motion), None, Settlement::None)
+            .await
+    }
+
+    pub async fn abandon(&mut self, motion: Hash) -> Result<Completion, Top<ClientError>> {
+        self.perform(Operation::abandon(motion), None, Settlement::None)
+            .await
+    }
+
+    /// Completes the `Operation` left pending by a previous (failed) call, if any,
+    /// returning its `Completion`. Any further `Operation` resumes it first.
+    pub async fn resume(&mut self) -> Result<Option<Completion>, Top<ClientError>> {
+        if self.pending.is_none() {
+            return Ok(None);
+        }
+
+        self.complete().await.map(Some)
+    }
+
+    async fn perform(
+        &mut self,
+        operation: Operation,
+        dependency: Option<Completion>,
+        settlement: Settlement,
+    ) -> Result<Completion, Top<ClientError>> {
+        self.resume().await?;
+
+        let payload = Payload::new(
+            Entry {
+                id: self.id(),
+                height: self.height + 1,
+            },
+            operation,
+        );
+
+        self.pending = Some(Pending {
+            payload,
+            dependency,
+            commit: None,
+            settlement,
+        });
+
+        self.complete().await
+    }
+
+    async fn complete(&mut self) -> Result<Completion, Top<ClientError>> {
+        let (payload, dependency, commit) = match &self.pending {
+            Some(pending) => (
+                pending.payload.clone(),
+                pending.dependency.clone(),
+                pending.commit.clone(),
+            ),
+            None => panic!("Called `Client::complete` without a pending `Operation`"),
+        };
+
+        // Once prepared, `payload` is committed through the commit brokers (a
+        // pipeline broker would prepare it again, to no avail)
+        let (proof, corruption) = if self.pipeline_brokers.is_empty() || commit.is_some() {
+            let commit = match commit {
+                Some(commit) => commit,
+                None => {
+                    let commit = self.prepare(&payload).await?;
+                    self.pending.as_mut().unwrap().commit = Some(commit.clone());
+                    commit
+                }
+            };
+
+            self.commit(CommitRequest::new(commit, dependency)).await?
+        } else {
+            self.pipeline(&payload, dependency).await?
+        };
+
+        let completion = Completion::new(proof, payload);
+
+        let settlement = self.pending.take().unwrap().settlement;
+
+        // Whether or not `payload` was excepted, the account moved to the next height
+        // (`completion` proves it to replicas lagging behind; unlike a `Commit`, it
+        // also proves that the next height is within their pipeline window)
+        self.height += 1;
+        self.continuity = Some(Continuity::Completion(completion.clone()));
+
+        if corruption.is_some() {
+            self.corruption = corruption;
+            return ClientError::OperationExcepted.fail().spot(here!());
+        }
+
+        match settlement {
+            Settlement::None => {}
+            Settlement::Rotate(keychain) => {
+                // `completion` is for a `Rotate`: the following cannot fail
+                self.rotation = Some(KeyRotation::new(completion.clone()).unwrap());
+                self.keychain = keychain;
+            }
+            Settlement::Deposit {
+                withdrawal,
+                collect,
+            } => {
+                self.deposits.apply(withdrawal, collect);
+            }
+        }
+
+        Ok(completion)
+    }
+
+    fn prepare_request(&self, payload: &Payload) -> PrepareRequest {
+        let prepare = payload.prepare();
+
+        PrepareRequest::new(
+            &self.keychain,
+            self.assignment.clone(),
+            self.rotation.clone(),
+            self.continuity.clone(),
+            prepare.height(),
+            prepare.commitment(),
+        )
+    }
+
+    async fn prepare(&self, payload: &Payload) -> Result<Commit, Top<ClientError>> {
+        let request = self.prepare_request(payload);
+
+        // `request` is resubmitted unchanged across attempts: if a previous attempt
+        // reached a broker before failing, that broker serves the outcome of the
+        // original submission (instead of brokering `request` again)
+        for attempt in 0..self.settings.max_attempts {
+            let broker = self.prepare_brokers[attempt % self.prepare_brokers.len()];
+
+            match self.prepare_attempt(broker, &request, payload).await {
+                Ok(Ok(commit)) => return Ok(commit),
+                Ok(Err(PrepareBrokerFailure::Throttle { retry_after })) => {
+                    time::sleep(retry_after.unwrap_or(self.settings.throttle_backoff)).await;
+                }
+                Ok(Err(PrepareBrokerFailure::Error)) | Err(_) => {}
+            }
+        }
+
+        ClientError::PrepareFailed.fail().spot(here!())
+    }
+
+    async fn commit(
+        &self,
+        request: CommitRequest,
+    ) -> Result<(CompletionProof, Option<Corruption>), Top<ClientError>> {
+        for attempt in 0..self.settings.max_attempts {
+            let broker = self.commit_brokers[attempt % self.commit_brokers.len()];
+
+            match self.commit_attempt(broker, &request).await {
+                Ok(Ok(outcome)) => return Ok(outcome),
+                Ok(Err(CommitBrokerFailure::Throttle { retry_after })) => {
+                    time::sleep(retry_after.unwrap_or(self.settings.throttle_backoff)).await;
+                }
+                Ok(Err(CommitBrokerFailure::Error)) | Err(_) => {}
+            }
+        }
+
+        ClientError::CommitFailed.fail().spot(here!())
+    }
+
+    async fn pipeline(
+        &self,
+        payload: &Payload,
+        dependency: Option<Completion>,
+    ) -> Result<(CompletionProof, Option<Corruption>), Top<ClientError>> {
+        let request =
+            PipelineRequest::new(self.prepare_request(payload), payload.clone(), dependency);
+
+        for attempt in 0..self.settings.max_attempts {
+            let broker = self.pipeline_brokers[attempt % self.pipeline_brokers.len()];
+
+            match self.pipeline_attempt(broker, &request).await {
+                Ok(Ok(outcome)) => return Ok(outcome),
+                Ok(Err(PipelineBrokerFailure::Throttle { retry_after })) => {
+                    time::sleep(retry_after.unwrap_or(self.settings.throttle_backoff)).await;
+                }
+                Ok(Err(PipelineBrokerFailure::Error)) | Err(_) => {}
+            }
+        }
+
+        ClientError::PipelineFailed.fail().spot(here!())
+    }
+
+    async fn signup_attempt(
+        keychain: &KeyChain,
+        discovery: &DiscoveryClient,
+        broker: (SocketAddr, Identity),
+        request: &IdRequest,
+        settings: &ClientSettings,
+    ) -> Result<Result<IdAssignment, SignupBrokerFailure>, Top<AttemptError>> {
+        let mut connection = Client::connect(keychain, broker, settings).await?;
+
+        connection
+            .send(request)
+            .await
+            .pot(AttemptError::ConnectionError, here!())?;
+
+        let assignment = match connection
+            .receive::<Result<IdAssignment, SignupBrokerFailure>>()
+            .await
+            .pot(AttemptError::ConnectionError, here!())?
+        {
+            Ok(assignment) => assignment,
+            Err(failure) => return Ok(Err(failure)),
+        };
+
+        assignment
+            .validate(discovery)
+            .pot(AttemptError::AssignmentInvalid, here!())?;
+
+        if assignment.keycard().identity() != keychain.keycard().identity() {
+            return AttemptError::AssignmentInvalid.fail().spot(here!());
+        }
+
+        Ok(Ok(assignment))
+    }
+
+    async fn prepare_attempt(
+        &self,
+        broker: (SocketAddr, Identity),
+        request: &PrepareRequest,
+        payload: &Payload,
+    ) -> Result<Result<Commit, PrepareBrokerFailure>, Top<AttemptError>> {
+        let mut connection = Client::connect(&self.keychain, broker, &self.settings).await?;
+
+        connection
+            .send(request)
+            .await
+            .pot(AttemptError::ConnectionError, here!())?;
+
+        let inclusion = match connection
+            .receive::<Result<Inclusion, PrepareBrokerFailure>>()
+            .await
+            .pot(AttemptError::ConnectionError, here!())?
+        {
+            Ok(inclusion) => inclusion,
+            Err(failure) => return Ok(Err(failure)),
+        };
+
+        let reduction_shard = inclusion
+            .certify_reduction(&self.keychain, request.prepare())
+            .pot(AttemptError::InclusionInvalid, here!())?;
+
+        connection
+            .send(&reduction_shard)
+            .await
+            .pot(AttemptError::ConnectionError, here!())?;
+
+        let batch_commit = match connection
+            .receive::<Result<BatchCommit, PrepareBrokerFailure>>()
+            .await
+            .pot(AttemptError::ConnectionError, here!())?
+        {
+            Ok(batch_commit) => batch_commit,
+            Err(failure) => return Ok(Err(failure)),
+        };
+
+        let commit = Commit::new(
+            CommitProof::new(batch_commit, inclusion.proof),
+            payload.clone(),
+        );
+
+        commit
+            .validate(self.discovery.as_ref())
+            .pot(AttemptError::CommitInvalid, here!())?;
+
+        Ok(Ok(commit))
+    }
+
+    async fn commit_attempt(
+        &self,
+        broker: (SocketAddr, Identity),
+        request: &CommitRequest,
+    ) -> Result<Result<(CompletionProof, Option<Corruption>), CommitBrokerFailure>, Top<AttemptError>>
+    {
+        let mut connection = Client::connect(&self.keychain, broker, &self.settings).await?;
+
+        connection
+            .send(request)
+            .await
+            .pot(AttemptError::ConnectionError, here!())?;
+
+        let proof = match connection
+            .receive::<Result<CompletionProof, CommitBrokerFailure>>()
+            .await
+            .pot(AttemptError::ConnectionError, here!())?
+        {
+            Ok(proof) => proof,
+            Err(failure) => return Ok(Err(failure)),
+        };
+
+        let corruption = proof
+            .exception(self.discovery.as_ref(), request.commit.payload())
+            .pot(AttemptError::CompletionInvalid, here!())?;
+
+        Ok(Ok((proof, corruption)))
+    }
+
+    async fn pipeline_attempt(
+        &self,
+        broker: (SocketAddr, Identity),
+        request: &PipelineRequest,
+    ) -> Result<
+        Result<(CompletionProof, Option<Corruption>), PipelineBrokerFailure>,
+        Top<AttemptError>,
+    > {
+        let mut connection = Client::connect(&self.keychain, broker, &self.settings).await?;
+
+        connection
+            .send(request)
+            .await
+            .pot(AttemptError::ConnectionError, here!())?;
+
+        let inclusion = match connection
+            .receive::<Result<Inclusion, PipelineBrokerFailure>>()
+            .await
+            .pot(AttemptError::ConnectionError, here!())?
+        {
+            Ok(inclusion) => inclusion,
+            Err(failure) => return Ok(Err(failure)),
+        };
+
+        let reduction_shard = inclusion
+            .certify_reduction(&self.keychain, request.prepare.prepare())
+            .pot(AttemptError::InclusionInvalid, here!())?;
+
+        connection
+            .send(&reduction_shard)
+            .await
+            .pot(AttemptError::ConnectionError, here!())?;
+
+        let proof = match connection
+            .receive::<Result<CompletionProof, PipelineBrokerFailure>>()
+            .await
+            .pot(AttemptError::ConnectionError, here!())?
+        {
+            Ok(proof) => proof,
+            Err(failure) => return Ok(Err(failure)),
+        };
+
+        let corruption = proof
+            .exception(self.discovery.as_ref(), &request.payload)
+            .pot(AttemptError::CompletionInvalid, here!())?;
+
+        Ok(Ok((proof, corruption)))
+    }
+
+    async fn connect(
+        keychain: &KeyChain,
+        broker: (SocketAddr, Identity),
+        settings: &ClientSettings,
+    ) -> Result<Connection, Top<AttemptError>> {
+        let (address, identity) = broker;
+
+        // If `settings.authenticate` is unset, authenticate with a throwaway
+        // `KeyChain`, so that the connection is not linkable to the account
+        let ephemeral;
+
+        let keychain = if settings.authenticate {
+            keychain
+        } else {
+            ephemeral = KeyChain::random();
+            &ephemeral
+        };
+
+        Connection::connect(address, identity, keychain, settings.transport)
+            .await
+            .pot(AttemptError::ConnectionFailed, here!())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{
+        account::{AccountSettings, OperationError},
+        brokers::test::System,
+        motion::PassAggregator,
+        processing::{processor_settings::Commit as CommitSettings, ProcessorSettings},
+        transport::Transport,
+    };
+
+    use std::time::Duration;
+
+    use talk::crypto::primitives::hash;
+
+    // Accounts are funded with an initial balance of 100
+    fn funded() -> ProcessorSettings {
+        ProcessorSettings {
+            commit: CommitSettings {
+                account_settings: AccountSettings {
+                    initial_balance: 100,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    // Sets up a `System` with one broker of each kind (pipeline brokers only if
+    // `pipeline`), then signs up `clients` `Client`s through its brokers
+    async fn setup(
+        clients: usize,
+        pipeline: bool,
+        settings: ProcessorSettings,
+    ) -> (System, Vec<Client>) {
+        let system = System::setup_with_transport(
+            4,
+            1,
+            1,
+            1,
+            if pipeline { 1 } else { 0 },
+            settings,
+            Transport::Secure,
+        )
+        .await;
+
+        let signup_brokers = system
+            .signup_brokers
+            .iter()
+            .map(|broker| (broker.address(), broker.identity()))
+            .collect::<Vec<_>>();
+
+        let prepare_brokers = system
+            .prepare_brokers
+            .iter()
+            .map(|broker| (broker.address(), broker.identity()))
+            .collect::<Vec<_>>();
+
+        let commit_brokers = system
+            .commit_brokers
+            .iter()
+            .map(|broker| (broker.address(), broker.identity()))
+            .collect::<Vec<_>>();
+
+        let pipeline_brokers = system
+            .pipeline_brokers
+            .iter()
+            .map(|broker| (broker.address(), broker.identity()))
+            .collect::<Vec<_>>();
+
+        let mut signed_up = Vec::new();
+
+        for _ in 0..clients {
+            let mut client = Client::signup(
+                KeyChain::random(),
+                system.view.clone(),
+                system.discovery_client.clone(),
+                signup_brokers.clone(),
+                prepare_brokers.clone(),
+                commit_brokers.clone(),
+                Default::default(),
+            )
+            .await
+            .unwrap();
+
+            client.set_pipeline_brokers(pipeline_brokers.clone());
+            signed_up.push(client);
+        }
+
+        (system, signed_up)
+    }
+
+    #[tokio::test]
+    async fn withdraw_deposit() {
+        let (_system, mut clients) = setup(2, false, funded()).await;
+
+        let mut bob = clients.pop().unwrap();
+        let mut alice = clients.pop().unwrap();
+
+        let withdrawal = alice.withdraw(bob.id(), bob.slot(), 60).await.unwrap();
+        assert_eq!(alice.height(), 1);
+
+        bob.deposit(withdrawal.clone(), false).await.unwrap();
+        assert_eq!(bob.height(), 1);
+        assert_eq!(bob.slot(), 0);
+
+        // `withdrawal` was already deposited in the current slot: the
+        // deposit is rejected without being submitted
+        assert!(bob.deposit(withdrawal, true).await.is_err());
+        assert_eq!(bob.height(), 1);
+
+        // `alice` is left with 40
+        assert!(alice.corruption().is_none());
+        assert!(alice.withdraw(bob.id(), bob.slot(), 60).await.is_err());
+        assert_eq!(alice.height(), 2);
+
+        // The commit broker returns the reason of `alice`'s corruption
+        let corruption = alice.corruption().unwrap();
+
+        assert_eq!(corruption.entry().height, 2);
+        assert_eq!(*corruption.error(), OperationError::Overdraft);
+    }
+
+    #[tokio::test]
+    async fn corrupted() {
+        let (_system, mut clients) = setup(2, false, funded()).await;
+
+        let bob = clients.pop().unwrap();
+        let mut alice = clients.pop().unwrap();
+
+        assert!(alice.withdraw(bob.id(), bob.slot(), 160).await.is_err());
+        assert_eq!(alice.height(), 1);
+
+        // Further `Operation`s are excepted on the grounds of the original corruption
+        let motion = hash::hash(&"motion").unwrap();
+
+        assert!(alice.support(motion).await.is_err());
+        assert_eq!(alice.height(), 2);
+
+        let corruption = alice.corruption().unwrap();
+
+        assert_eq!(corruption.entry().height, 1);
+        assert!(matches!(corruption.operation(), Operation::Withdraw(_)));
+        assert_eq!(*corruption.error(), OperationError::Overdraft);
+    }
+
+    #[tokio::test]
+    async fn pipeline() {
+        let (_system, mut clients) = setup(2, true, funded()).await;
+
+        let mut bob = clients.pop().unwrap();
+        let mut alice = clients.pop().unwrap();
+
+        let withdrawal = alice.withdraw(bob.id(), bob.slot(), 60).await.unwrap();
+        assert_eq!(alice.height(), 1);
+
+        bob.deposit(withdrawal, false).await.unwrap();
+        assert_eq!(bob.height(), 1);
+
+        // Operations keep chaining on `Completion`s returned by the pipeline broker
+        assert!(alice.withdraw(bob.id(), bob.slot(), 60).await.is_err());
+        assert_eq!(alice.height(), 2);
+
+        let corruption = alice.corruption().unwrap();
+
+        assert_eq!(corruption.entry().height, 2);
+        assert_eq!(*corruption.error(), OperationError::Overdraft);
+    }
+
+    #[tokio::test]
+    async fn tracked_deposits() {
+        let (_system, mut clients) = setup(2, false, funded()).await;
+
+        let mut bob = clients.pop().unwrap();
+        let mut alice = clients.pop().unwrap();
+
+        let first = alice.withdraw(bob.id(), bob.slot(), 10).await.unwrap();
+        let second = alice.withdraw(bob.id(), bob.slot(), 20).await.unwrap();
+
+        // Withdrawals to other accounts are rejected, duplicates are ignored
+        assert!(alice.receive(first.clone()).is_err());
+
+        bob.receive(first.clone()).unwrap();
+        bob.receive(second).unwrap();
+        bob.receive(first.clone()).unwrap();
+
+        bob.close_slot();
+
+        // The slot is collected by the deposit of its last withdrawal
+        assert!(bob.deposit_next().await.unwrap().is_some());
+        assert_eq!(bob.slot(), 0);
+
+        assert!(bob.deposit_next().await.unwrap().is_some());
+        assert_eq!(bob.slot(), 1);
+
+        assert!(bob.deposit_next().await.unwrap().is_none());
+        assert_eq!(bob.height(), 2);
+
+        // `first` targets a collected slot
+        assert!(bob.receive(first).is_err());
+    }
+
+    #[tokio::test]
+    async fn transfer() {
+        let (_system, mut clients) = setup(3, false, funded()).await;
+
+        let mut carol = clients.pop().unwrap();
+        let mut bob = clients.pop().unwrap();
+        let mut alice = clients.pop().unwrap();
+
+        let transfer = alice
+            .transfer(vec![
+                (bob.id(), bob.slot(), 10),
+                (carol.id(), carol.slot(), 20),
+            ])
+            .await
+            .unwrap();
+
+        // Each beneficiary deposits its own leg
+        bob.deposit(transfer.clone(), true).await.unwrap();
+        carol.deposit(transfer.clone(), true).await.unwrap();
+
+        assert_eq!(bob.height(), 1);
+        assert_eq!(carol.height(), 1);
+
+        // Legs cannot be deposited twice
+        assert!(bob.deposit(transfer, true).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn rotate() {
+        let (_system, mut clients) = setup(2, false, funded()).await;
+
+        let mut bob = clients.pop().unwrap();
+        let mut alice = clients.pop().unwrap();
+
+        let keychain = KeyChain::random();
+        let keycard = keychain.keycard();
+
+        alice.rotate(keychain).await.unwrap();
+
+        let rotation = alice.rotation().unwrap();
+
+        assert_eq!(rotation.id(), alice.id());
+        assert_eq!(rotation.height(), 2);
+        assert_eq!(*rotation.keycard(), keycard);
+
+        // Subsequent `Operation`s are signed by the rotated key
+        let withdrawal = alice.withdraw(bob.id(), bob.slot(), 10).await.unwrap();
+        bob.deposit(withdrawal, true).await.unwrap();
+
+        assert_eq!(alice.height(), 2);
+    }
+
+    #[tokio::test]
+    async fn resume() {
+        let (_system, mut clients) = setup(2, false, funded()).await;
+
+        let mut bob = clients.pop().unwrap();
+        let mut alice = clients.pop().unwrap();
+
+        let commit_brokers = alice.commit_brokers.clone();
+
+        // No commit broker authenticates as the identity `alice` expects
+        alice.commit_brokers = vec![(commit_brokers[0].0, KeyChain::random().keycard().identity())];
+        alice.settings.max_attempts = 2;
+
+        // `alice`'s withdrawal is prepared, but cannot be committed
+        assert!(alice.withdraw(bob.id(), bob.slot(), 60).await.is_err());
+        assert_eq!(alice.height(), 0);
+        assert!(alice.pending.as_ref().unwrap().commit.is_some());
+
+        alice.commit_brokers = commit_brokers;
+
+        // The pending withdrawal is committed before the next one is submitted
+        // (preparing another `Operation` at height 1 would equivocate `alice`)
+        let second = alice.withdraw(bob.id(), bob.slot(), 30).await.unwrap();
+
+        assert_eq!(second.entry().height, 2);
+        assert_eq!(alice.height(), 2);
+        assert!(alice.pending.is_none());
+        assert!(alice.resume().await.unwrap().is_none());
+
+        bob.deposit(second, false).await.unwrap();
+        assert_eq!(bob.height(), 1);
+
+        // `alice` is left with 10
+        assert!(alice.withdraw(bob.id(), bob.slot(), 20).await.is_err());
+        assert!(alice.corruption().is_some());
+    }
+
+    #[tokio::test]
+    async fn motion() {
+        let mut settings = ProcessorSettings::default();
+        settings.commit.pass_threshold = 3;
+
+        let (system, mut clients) = setup(4, false, settings).await;
+
+        let motion = hash::hash(&"motion").unwrap();
+
+        // Three supports, one of which is then withdrawn: `motion` falls short of the threshold
+        clients[0].support(motion).await.unwrap();
+        clients[1].support(motion).await.unwrap();
+        clients[1].abandon(motion).await.unwrap();
+        clients[2].support(motion).await.unwrap();
+
+        for (keychain, _) in system.processors.iter() {
+            let (_, shard) = system
+                .test_broker
+                .motion(keychain.keycard().identity(), motion)
+                .await;
+
+            assert!(shard.is_none());
+        }
+
+        clients[3].support(motion).await.unwrap();
+
+        // A quorum of processors applied the last support: a plurality
+        // of them eventually attests that `motion` passed
+        let mut aggregator = PassAggregator::new(system.view.clone(), motion);
+
+        while !aggregator.complete() {
+            aggregator = PassAggregator::new(system.view.clone(), motion);
+
+            for (keychain, _) in system.processors.iter() {
+                let keycard = keychain.keycard();
+
+                let (tally, shard) = system.test_broker.motion(keycard.identity(), motion).await;
+
+                if let Some(shard) = shard {
+                    assert_eq!(tally, 3);
+
+                    shard.validate(&system.view, motion, &keycard).unwrap();
+                    aggregator.add(&keycard, shard);
+                }
+            }
+
+            time::sleep(Duration::from_millis(100)).await;
+        }
+
+        let pass = aggregator.finalize();
+        pass.validate(system.discovery_client.as_ref()).unwrap();
+    }
+}
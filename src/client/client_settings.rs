@@ -0,0 +1,28 @@
+use crate::{signup::SignupSettings, transport::Transport};
+
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+pub struct ClientSettings {
+    pub signup_settings: SignupSettings,
+
+    pub max_attempts: usize,
+    pub throttle_backoff: Duration,
+
+    pub transport: Transport,
+    pub authenticate: bool,
+}
+
+impl Default for ClientSettings {
+    fn default() -> Self {
+        ClientSettings {
+            signup_settings: Default::default(),
+
+            max_attempts: 16,
+            throttle_backoff: Duration::from_millis(500),
+
+            transport: Transport::Secure,
+            authenticate: true,
+        }
+    }
+}
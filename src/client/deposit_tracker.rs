@@ -0,0 +1,169 @@
+use crate::{
+    account::{Entry, Id, Operation},
+    commit::Completion,
+};
+
+use doomstack::{here, Doom, ResultExt, Top};
+
+use std::collections::BTreeMap;
+
+use talk::crypto::primitives::hash::Hash;
+
+use zebra::map::Set;
+
+/// Mirrors an account's deposit slot and set of deposited withdrawals
+/// (see `CorrectState`), so that only applicable `Operation::Deposit`s
+/// are built (an inapplicable deposit corrupts the account).
+///
+/// Withdrawals addressed to the account are queued by `scan`, then deposited
+/// in order through `next`. A slot is collected (i.e., `collect` is set on a
+/// deposit) only once its queued withdrawals are drained, and only if either
+/// `close` was called or withdrawals to a later slot are queued: any later
+/// withdrawal to a collected slot can no longer be deposited.
+pub struct DepositTracker {
+    id: Id,
+    slot: u64,
+    deposits: Option<Set<Entry>>,
+    pending: BTreeMap<u64, Vec<Completion>>,
+    closing: bool,
+}
+
+#[derive(Doom)]
+pub enum DepositTrackerError {
+    #[doom(description("Not a withdrawal to the tracked account"))]
+    ForeignWithdrawal,
+    #[doom(description("Withdrawal to an already collected slot"))]
+    SlotCollected,
+    #[doom(description("Withdrawal to a future slot"))]
+    FutureSlot,
+    #[doom(description("Withdrawal already deposited"))]
+    DoubleDeposit,
+}
+
+impl DepositTracker {
+    pub fn new(id: Id) -> Self {
+        DepositTracker {
+            id,
+            slot: 0,
+            deposits: None,
+            pending: BTreeMap::new(),
+            closing: false,
+        }
+    }
+
+    /// The slot a withdrawal to the tracked account must target to be deposited.
+    pub fn slot(&self) -> u64 {
+        self.slot
+    }
+
+    /// The root of the current slot's deposits (`None` if no deposit was
+    /// made in the current slot), as stored in the account's `CorrectState`.
+    pub fn root(&self) -> Option<Hash> {
+        self.deposits.as_ref().map(Set::commit)
+    }
+
+    /// Queues `withdrawal` for deposit. `withdrawal` is assumed to be valid.
+    pub fn scan(&mut self, withdrawal: Completion) -> Result<(), Top<DepositTrackerError>> {
+        let slot = self.target(&withdrawal)?;
+
+        if slot < self.slot {
+            return DepositTrackerError::SlotCollected.fail().spot(here!());
+        }
+
+        if slot == self.slot && self.deposited(withdrawal.entry()) {
+            return DepositTrackerError::DoubleDeposit.fail().spot(here!());
+        }
+
+        let queue = self.pending.entry(slot).or_insert_with(Vec::new);
+
+        if queue
+            .iter()
+            .all(|queued| queued.entry() != withdrawal.entry())
+        {
+            queue.push(withdrawal);
+        }
+
+        Ok(())
+    }
+
+    /// Marks the current slot for collection, once its queued withdrawals are drained.
+    pub fn close(&mut self) {
+        self.closing = true;
+    }
+
+    /// Returns the next queued withdrawal to deposit, and whether
+    /// or not its deposit should collect the current slot.
+    pub fn next(&self) -> Option<(Completion, bool)> {
+        let queue = self.pending.get(&self.slot)?;
+        let withdrawal = queue.first()?.clone();
+
+        let later = self.pending.range((self.slot + 1)..).next().is_some();
+        let collect = queue.len() == 1 && (self.closing || later);
+
+        Some((withdrawal, collect))
+    }
+
+    /// Builds the `Operation::Deposit` of `withdrawal` to the current slot.
+    pub fn deposit(
+        &self,
+        withdrawal: &Completion,
+        collect: bool,
+    ) -> Result<Operation, Top<DepositTrackerError>> {
+        let slot = self.target(withdrawal)?;
+
+        if slot < self.slot {
+            return DepositTrackerError::SlotCollected.fail().spot(here!());
+        }
+
+        if slot > self.slot {
+            return DepositTrackerError::FutureSlot.fail().spot(here!());
+        }
+
+        if self.deposited(withdrawal.entry()) {
+            return DepositTrackerError::DoubleDeposit.fail().spot(here!());
+        }
+
+        Ok(Operation::deposit(
+            withdrawal.entry(),
+            self.deposits.as_ref(),
+            collect,
+        ))
+    }
+
+    /// Records the (successful) deposit of `withdrawal`, as built by `deposit`.
+    pub fn apply(&mut self, withdrawal: Entry, collect: bool) {
+        if collect {
+            // Withdrawals still queued to the collected slot can no longer be deposited
+            self.pending.remove(&self.slot);
+
+            self.slot += 1;
+            self.deposits = None;
+            self.closing = false;
+        } else {
+            if let Some(queue) = self.pending.get_mut(&self.slot) {
+                queue.retain(|queued| queued.entry() != withdrawal);
+            }
+
+            self.deposits
+                .get_or_insert_with(Set::new)
+                .insert(withdrawal)
+                .unwrap();
+        }
+    }
+
+    // Returns the slot `withdrawal` targets (if `withdrawal` is addressed to `self.id`)
+    fn target(&self, withdrawal: &Completion) -> Result<u64, Top<DepositTrackerError>> {
+        // Either a `Withdraw` to `self.id`, or the leg of a `Transfer` paying `self.id`
+        match withdrawal.operation().leg(self.id) {
+            Some(leg) => Ok(leg.slot()),
+            None => DepositTrackerError::ForeignWithdrawal.fail().spot(here!()),
+        }
+    }
+
+    fn deposited(&self, withdrawal: Entry) -> bool {
+        // `self.deposits` is never a stub: `contains` cannot fail
+        self.deposits
+            .as_ref()
+            .map_or(false, |deposits| deposits.contains(&withdrawal).unwrap())
+    }
+}
@@ -0,0 +1,7 @@
+mod client;
+mod client_settings;
+mod deposit_tracker;
+
+pub use client::{Client, ClientError};
+pub use client_settings::ClientSettings;
+pub use deposit_tracker::{DepositTracker, DepositTrackerError};
@@ -1,5 +1,5 @@
 use crate::{
-    account::Id,
+    account::{Corruption, Id},
     commit::{BatchCompletionShard, BatchCompletionStatement},
     crypto::{Aggregator, Certificate, Identify},
     discovery::Client,
@@ -10,22 +10,34 @@ use doomstack::{here, Doom, ResultExt, Top};
 
 use serde::{Deserialize, Serialize};
 
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
-use talk::crypto::{primitives::hash::Hash, KeyCard};
+use talk::crypto::{
+    primitives::{hash, hash::Hash},
+    KeyCard,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct BatchCompletion {
     view: Hash,
     root: Hash,
-    exceptions: BTreeSet<Id>,
+    exceptions: BTreeMap<Id, Corruption>,
+    deferred: BTreeSet<Id>,
     certificate: Certificate,
 }
 
 pub(crate) struct BatchCompletionAggregator {
     view: View,
     root: Hash,
-    aggregators: HashMap<BTreeSet<Id>, Aggregator<BatchCompletionStatement>>,
+    // `Corruption`s are not `Hash`: shards are grouped by the hash
+    // of their exceptions and deferred `Id`s
+    aggregators: HashMap<
+        Hash,
+        (
+            (BTreeMap<Id, Corruption>, BTreeSet<Id>),
+            Aggregator<BatchCompletionStatement>,
+        ),
+    >,
 }
 
 #[derive(Doom)]
@@ -42,7 +54,18 @@ impl BatchCompletion {
     }
 
     pub fn excepts(&self, id: Id) -> bool {
-        self.exceptions.contains(&id)
+        self.exceptions.contains_key(&id)
+    }
+
+    /// Returns the `Corruption` of `id`'s account (if `id` is excepted).
+    pub fn exception(&self, id: Id) -> Option<&Corruption> {
+        self.exceptions.get(&id)
+    }
+
+    /// Returns whether `id`'s `Payload` was deferred, i.e., not applied by
+    /// the certifying quorum (and left for the client to submit again).
+    pub fn defers(&self, id: Id) -> bool {
+        self.deferred.contains(&id)
     }
 
     pub fn validate(&self, discovery: &Client) -> Result<(), Top<BatchCompletionError>> {
@@ -51,8 +74,12 @@ impl BatchCompletion {
             .ok_or(BatchCompletionError::ViewUnknown.into_top())
             .spot(here!())?;
 
-        let statement =
-            BatchCompletionStatement::new(self.view, self.root, self.exceptions.clone());
+        let statement = BatchCompletionStatement::new(
+            self.view,
+            self.root,
+            self.exceptions.clone(),
+            self.deferred.clone(),
+        );
 
         self.certificate
             .verify_quorum(&view, &statement)
@@ -72,15 +99,18 @@ impl BatchCompletionAggregator {
     }
 
     pub fn add(&mut self, completer: &KeyCard, shard: BatchCompletionShard) {
-        let statement =
-            BatchCompletionStatement::new(self.view.identifier(), self.root, shard.exceptions());
+        let outcome = (shard.exceptions(), shard.deferred());
+        let key = hash::hash(&outcome).unwrap();
 
-        let aggregator = Aggregator::new(self.view.clone(), statement);
+        let view = &self.view;
+        let root = self.root;
 
-        let aggregator = self
-            .aggregators
-            .entry(shard.exceptions())
-            .or_insert(aggregator);
+        let (_, aggregator) = self.aggregators.entry(key).or_insert_with(|| {
+            let (exceptions, deferred) = outcome.clone();
+            let statement =
+                BatchCompletionStatement::new(view.identifier(), root, exceptions, deferred);
+            (outcome, Aggregator::new(view.clone(), statement))
+        });
 
         // Assuming that `shard` is valid, `shard.signature()` is valid
         aggregator.add(completer, shard.signature()).unwrap();
@@ -88,7 +118,7 @@ impl BatchCompletionAggregator {
 
     pub fn complete(&self) -> bool {
         self.aggregators
-            .iter()
+            .values()
             .find(|(_, aggregator)| aggregator.multiplicity() >= self.view.quorum())
             .is_some()
     }
@@ -101,8 +131,9 @@ impl BatchCompletionAggregator {
         } = self;
 
         // Assuming that `self.complete()`, exactly one `Aggregator` in `aggregators` has reached a quorum multiplicity
-        let (exceptions, aggregator) = aggregators
+        let ((exceptions, deferred), aggregator) = aggregators
             .into_iter()
+            .map(|(_, entry)| entry)
             .find(|(_, aggregator)| aggregator.multiplicity() >= view.quorum())
             .unwrap();
 
@@ -112,6 +143,7 @@ impl BatchCompletionAggregator {
             view: view.identifier(),
             root,
             exceptions,
+            deferred,
             certificate,
         }
     }
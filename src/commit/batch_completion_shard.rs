@@ -1,5 +1,5 @@
 use crate::{
-    account::Id,
+    account::{Corruption, Id},
     commit::{BatchCompletionStatement, Payload},
     crypto::Identify,
     view::View,
@@ -9,7 +9,7 @@ use doomstack::{here, Doom, ResultExt, Top};
 
 use serde::{Deserialize, Serialize};
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 
 use talk::crypto::{
     primitives::{hash::Hash, multi::Signature as MultiSignature},
@@ -18,7 +18,10 @@ use talk::crypto::{
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct BatchCompletionShard {
-    exceptions: BTreeSet<Id>,
+    // Each excepted `Id` is paired with the `Corruption` of its account
+    exceptions: BTreeMap<Id, Corruption>,
+    // `Id`s whose `Payload` could not be applied in time (see `Commit::pipeline_timeout`)
+    deferred: BTreeSet<Id>,
     signature: MultiSignature,
 }
 
@@ -31,25 +34,40 @@ pub(crate) enum BatchCompletionShardError {
 }
 
 impl BatchCompletionShard {
-    pub fn new<I>(keychain: &KeyChain, view: Hash, root: Hash, exceptions: I) -> Self
+    pub fn new<I, D>(
+        keychain: &KeyChain,
+        view: Hash,
+        root: Hash,
+        exceptions: I,
+        deferred: D,
+    ) -> Self
     where
-        I: IntoIterator<Item = Id>,
+        I: IntoIterator<Item = (Id, Corruption)>,
+        D: IntoIterator<Item = Id>,
     {
-        let exceptions = exceptions.into_iter().collect::<BTreeSet<_>>();
+        let exceptions = exceptions.into_iter().collect::<BTreeMap<_, _>>();
+        let deferred = deferred.into_iter().collect::<BTreeSet<_>>();
+
+        let statement =
+            BatchCompletionStatement::new(view, root, exceptions.clone(), deferred.clone());
 
-        let statement = BatchCompletionStatement::new(view, root, exceptions.clone());
         let signature = keychain.multisign(&statement).unwrap();
 
         BatchCompletionShard {
             exceptions,
+            deferred,
             signature,
         }
     }
 
-    pub fn exceptions(&self) -> BTreeSet<Id> {
+    pub fn exceptions(&self) -> BTreeMap<Id, Corruption> {
         self.exceptions.clone()
     }
 
+    pub fn deferred(&self) -> BTreeSet<Id> {
+        self.deferred.clone()
+    }
+
     pub fn signature(&self) -> MultiSignature {
         self.signature.clone()
     }
@@ -63,15 +81,39 @@ impl BatchCompletionShard {
     ) -> Result<(), Top<BatchCompletionShardError>> {
         // Assuming that `payloads` was generated locally, it is is sorted by `Id`,
         // and can therefore be searched using `binary_search*`
-        for id in self.exceptions.iter() {
+        for (id, corruption) in self.exceptions.iter() {
+            if corruption.id() != *id {
+                return BatchCompletionShardError::ForeignException
+                    .fail()
+                    .spot(here!());
+            }
+
+            payloads
+                .binary_search_by_key(id, Payload::id)
+                .map_err(|_| BatchCompletionShardError::ForeignException.into_top())
+                .spot(here!())?;
+        }
+
+        // A deferred `Payload` was not applied, hence it cannot be excepted
+        for id in self.deferred.iter() {
+            if self.exceptions.contains_key(id) {
+                return BatchCompletionShardError::ForeignException
+                    .fail()
+                    .spot(here!());
+            }
+
             payloads
                 .binary_search_by_key(id, Payload::id)
                 .map_err(|_| BatchCompletionShardError::ForeignException.into_top())
                 .spot(here!())?;
         }
 
-        let exceptions = self.exceptions.clone();
-        let statement = BatchCompletionStatement::new(view.identifier(), root, exceptions);
+        let statement = BatchCompletionStatement::new(
+            view.identifier(),
+            root,
+            self.exceptions.clone(),
+            self.deferred.clone(),
+        );
 
         self.signature
             .verify([completer], &statement)
@@ -1,8 +1,11 @@
-use crate::{account::Id, crypto::Header};
+use crate::{
+    account::{Corruption, Id},
+    crypto::Header,
+};
 
 use serde::Serialize;
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 
 use talk::crypto::{primitives::hash::Hash, Statement};
 
@@ -10,15 +13,22 @@ use talk::crypto::{primitives::hash::Hash, Statement};
 pub(crate) struct BatchCompletionStatement {
     view: Hash,
     root: Hash,
-    exceptions: BTreeSet<Id>,
+    exceptions: BTreeMap<Id, Corruption>,
+    deferred: BTreeSet<Id>,
 }
 
 impl BatchCompletionStatement {
-    pub fn new(view: Hash, root: Hash, exceptions: BTreeSet<Id>) -> Self {
+    pub fn new(
+        view: Hash,
+        root: Hash,
+        exceptions: BTreeMap<Id, Corruption>,
+        deferred: BTreeSet<Id>,
+    ) -> Self {
         BatchCompletionStatement {
             view,
             root,
             exceptions,
+            deferred,
         }
     }
 }
@@ -9,13 +9,13 @@ use doomstack::Top;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub(crate) struct Completion {
+pub struct Completion {
     proof: CompletionProof,
     payload: Payload,
 }
 
 impl Completion {
-    pub fn new(proof: CompletionProof, payload: Payload) -> Self {
+    pub(crate) fn new(proof: CompletionProof, payload: Payload) -> Self {
         Completion { proof, payload }
     }
 
@@ -1,4 +1,5 @@
 use crate::{
+    account::Corruption,
     commit::{BatchCompletion, Payload},
     discovery::Client,
 };
@@ -16,13 +17,15 @@ pub(crate) struct CompletionProof {
 }
 
 #[derive(Doom)]
-pub(crate) enum CompletionProofError {
+pub enum CompletionProofError {
     #[doom(description("`BatchCompletion` invalid"))]
     BatchCompletionInvalid,
     #[doom(description("Inclusion `Proof` invalid"))]
     InclusionInvalid,
     #[doom(description("`Payload` is in `BatchCompletion`'s exceptions"))]
     PayloadException,
+    #[doom(description("`Payload` was deferred by `BatchCompletion`"))]
+    PayloadDeferred,
 }
 
 impl CompletionProof {
@@ -35,6 +38,22 @@ impl CompletionProof {
         discovery: &Client,
         payload: &Payload,
     ) -> Result<(), Top<CompletionProofError>> {
+        if self.exception(discovery, payload)?.is_some() {
+            return CompletionProofError::PayloadException.fail().spot(here!());
+        }
+
+        Ok(())
+    }
+
+    /// Validates `self` as a proof of `payload` being processed (either
+    /// successfully or not), then returns the `Corruption` of `payload`'s
+    /// account if `payload` is in `self.batch`'s exceptions. A deferred
+    /// `payload` was not processed, and is not proven by `self`.
+    pub fn exception(
+        &self,
+        discovery: &Client,
+        payload: &Payload,
+    ) -> Result<Option<Corruption>, Top<CompletionProofError>> {
         self.batch
             .validate(discovery)
             .pot(CompletionProofError::BatchCompletionInvalid, here!())?;
@@ -43,10 +62,13 @@ impl CompletionProof {
             .verify(self.batch.root(), payload)
             .pot(CompletionProofError::InclusionInvalid, here!())?;
 
-        if self.batch.excepts(payload.id()) {
-            return CompletionProofError::PayloadException.fail().spot(here!());
+        if self.batch.defers(payload.id()) {
+            return CompletionProofError::PayloadDeferred.fail().spot(here!());
         }
 
-        Ok(())
+        // `self.batch` certifies the `Corruption` of every exception (i.e., the
+        // `Operation` that originally corrupted the account, which is not
+        // necessarily `payload`'s)
+        Ok(self.batch.exception(payload.id()).cloned())
     }
 }
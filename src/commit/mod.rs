@@ -18,9 +18,10 @@ pub(crate) use batch_completion_statement::BatchCompletionStatement;
 pub(crate) use commit::Commit;
 
 pub(crate) use commit_proof::{CommitProof, CommitProofError};
-pub(crate) use completion::Completion;
-pub(crate) use completion_proof::{CompletionProof, CompletionProofError};
+pub use completion::Completion;
+pub(crate) use completion_proof::CompletionProof;
+pub use completion_proof::CompletionProofError;
 pub(crate) use extract::Extract;
-pub(crate) use payload::Payload;
+pub use payload::Payload;
 pub(crate) use witness_statement::WitnessStatement;
 pub(crate) use witnessed_batch::WitnessedBatch;
@@ -7,7 +7,7 @@ use crate::{
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub(crate) struct Payload {
+pub struct Payload {
     entry: Entry,
     operation: Operation,
 }
@@ -37,7 +37,7 @@ impl Payload {
         self.operation.dependency()
     }
 
-    pub fn prepare(&self) -> Prepare {
+    pub(crate) fn prepare(&self) -> Prepare {
         Prepare::new(self.entry, self.operation.identifier())
     }
 }
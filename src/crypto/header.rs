@@ -24,4 +24,8 @@ pub(crate) enum Header {
     CommitWitness = 12,
 
     Completion = 13,
+
+    Imminent = 14,
+
+    Pass = 15,
 }
@@ -0,0 +1,155 @@
+use crate::data::AdmissionSettings;
+
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Bounds the load a broker accepts: concurrent connections, outstanding
+/// brokerages and per-client request rate (see `AdmissionSettings`).
+pub(crate) struct Admission<Client> {
+    connections: Arc<Semaphore>,
+    brokerages: Arc<Semaphore>,
+    clients: Mutex<Clients<Client>>,
+    settings: AdmissionSettings,
+}
+
+// Per-client rate limiting follows the generic cell rate algorithm: each
+// client is assigned the theoretical arrival time of its next request,
+// which advances by `client_interval` on every admitted request.
+struct Clients<Client> {
+    arrivals: HashMap<Client, Instant>,
+    prune_at: usize,
+}
+
+const MIN_PRUNE_AT: usize = 1024;
+
+impl<Client> Admission<Client>
+where
+    Client: Eq + Hash,
+{
+    pub fn new(settings: AdmissionSettings) -> Self {
+        let connections = Arc::new(Semaphore::new(settings.max_connections.max(1)));
+        let brokerages = Arc::new(Semaphore::new(settings.max_brokerages.max(1)));
+
+        let clients = Mutex::new(Clients {
+            arrivals: HashMap::new(),
+            prune_at: MIN_PRUNE_AT,
+        });
+
+        Admission {
+            connections,
+            brokerages,
+            clients,
+            settings,
+        }
+    }
+
+    /// Returns a permit to serve a connection (released on drop), or how long
+    /// to wait before retrying if `max_connections` connections are being served.
+    pub fn connect(&self) -> Result<OwnedSemaphorePermit, Duration> {
+        self.connections
+            .clone()
+            .try_acquire_owned()
+            .map_err(|_| self.settings.connection_retry_after)
+    }
+
+    /// Admits a request by `client`. If `client` exceeded its rate,
+    /// returns how long it should wait before retrying.
+    pub fn submit(&self, client: Client) -> Result<(), Duration> {
+        let now = Instant::now();
+
+        let interval = self.settings.client_interval;
+        let tolerance = interval * self.settings.client_burst.saturating_sub(1);
+
+        let mut clients = self.clients.lock().unwrap();
+
+        // Clients whose theoretical arrival time is past are as good as new:
+        // they are periodically pruned to keep `arrivals` bounded
+        if clients.arrivals.len() >= clients.prune_at {
+            clients.arrivals.retain(|_, arrival| *arrival > now);
+            clients.prune_at = (2 * clients.arrivals.len()).max(MIN_PRUNE_AT);
+        }
+
+        let arrival = clients.arrivals.entry(client).or_insert(now);
+
+        if *arrival > now + tolerance {
+            return Err(*arrival - tolerance - now);
+        }
+
+        *arrival = (*arrival).max(now) + interval;
+        Ok(())
+    }
+
+    /// Returns a permit to submit a brokerage (released on drop), or how long
+    /// to wait before retrying if `max_brokerages` brokerages are outstanding.
+    pub fn broker(&self) -> Result<OwnedSemaphorePermit, Duration> {
+        self.brokerages
+            .clone()
+            .try_acquire_owned()
+            .map_err(|_| self.settings.brokerage_retry_after)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn connections() {
+        let admission = Admission::<u32>::new(AdmissionSettings {
+            max_connections: 2,
+            connection_retry_after: Duration::from_secs(1),
+            ..Default::default()
+        });
+
+        let first = admission.connect().unwrap();
+        let _second = admission.connect().unwrap();
+
+        assert_eq!(admission.connect().unwrap_err(), Duration::from_secs(1));
+
+        drop(first);
+        assert!(admission.connect().is_ok());
+    }
+
+    #[test]
+    fn brokerages() {
+        let admission = Admission::<u32>::new(AdmissionSettings {
+            max_brokerages: 1,
+            brokerage_retry_after: Duration::from_secs(1),
+            ..Default::default()
+        });
+
+        let permit = admission.broker().unwrap();
+        assert_eq!(admission.broker().unwrap_err(), Duration::from_secs(1));
+
+        drop(permit);
+        assert!(admission.broker().is_ok());
+    }
+
+    #[test]
+    fn rate() {
+        let admission = Admission::new(AdmissionSettings {
+            client_burst: 3,
+            client_interval: Duration::from_secs(3600),
+            ..Default::default()
+        });
+
+        for _ in 0..3 {
+            admission.submit(0u32).unwrap();
+        }
+
+        // `0` exhausted its burst: it is told to wait for (about) one interval
+        let retry_after = admission.submit(0).unwrap_err();
+
+        assert!(retry_after > Duration::from_secs(3599));
+        assert!(retry_after <= Duration::from_secs(3600));
+
+        // Clients are rate-limited independently
+        admission.submit(1).unwrap();
+    }
+}
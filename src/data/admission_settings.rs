@@ -0,0 +1,37 @@
+use serde::Deserialize;
+
+use std::time::Duration;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub(crate) struct AdmissionSettings {
+    // Connections beyond `max_connections` are throttled with a
+    // `connection_retry_after` hint, then closed without being served
+    pub max_connections: usize,
+    pub connection_retry_after: Duration,
+
+    // Brokerages beyond `max_brokerages` are throttled with a
+    // `brokerage_retry_after` hint until an outstanding one resolves
+    pub max_brokerages: usize,
+    pub brokerage_retry_after: Duration,
+
+    // Each client can submit up to `client_burst` requests at once,
+    // and one more every `client_interval` thereafter
+    pub client_burst: u32,
+    pub client_interval: Duration,
+}
+
+impl Default for AdmissionSettings {
+    fn default() -> Self {
+        AdmissionSettings {
+            max_connections: 16384,
+            connection_retry_after: Duration::from_secs(1),
+
+            max_brokerages: 65536,
+            brokerage_retry_after: Duration::from_millis(100),
+
+            client_burst: 16,
+            client_interval: Duration::from_millis(10),
+        }
+    }
+}
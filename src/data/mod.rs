@@ -1,9 +1,15 @@
+mod admission;
+mod admission_settings;
 mod ping_board;
+mod result_cache;
 mod shift_vec;
 mod sponge;
 mod sponge_settings;
 
+pub(crate) use admission::Admission;
+pub(crate) use admission_settings::AdmissionSettings;
 pub(crate) use ping_board::PingBoard;
+pub(crate) use result_cache::ResultCache;
 pub(crate) use shift_vec::ShiftVec;
 pub(crate) use sponge::Sponge;
 pub(crate) use sponge_settings::SpongeSettings;
@@ -0,0 +1,158 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    hash::Hash,
+    sync::Mutex,
+};
+
+/// A bounded map from keys (e.g., `Entry`s) to handles on the (possibly
+/// pending) result of the request relative to each key. When `capacity`
+/// is exceeded, the least recently inserted key is evicted.
+pub(crate) struct ResultCache<Key, Value> {
+    database: Mutex<Database<Key, Value>>,
+    capacity: usize,
+}
+
+struct Database<Key, Value> {
+    sequence: u64,
+    values: HashMap<Key, (u64, Value)>,
+    order: VecDeque<(u64, Key)>,
+}
+
+impl<Key, Value> ResultCache<Key, Value>
+where
+    Key: Clone + Eq + Hash,
+    Value: Clone,
+{
+    pub fn new(capacity: usize) -> Self {
+        let database = Mutex::new(Database {
+            sequence: 0,
+            values: HashMap::new(),
+            order: VecDeque::new(),
+        });
+
+        ResultCache {
+            database,
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Returns a copy of the value cached for `key`, if `reuse` accepts it.
+    /// Otherwise, caches `value` (replacing the previous, if any) and returns it.
+    /// The returned flag is `true` if and only if `value` was cached.
+    pub fn attach_or_insert<F>(&self, key: Key, value: Value, reuse: F) -> (Value, bool)
+    where
+        F: FnOnce(&Value) -> bool,
+    {
+        let mut database = self.database.lock().unwrap();
+
+        if let Some((_, cached)) = database.values.get(&key) {
+            if reuse(cached) {
+                return (cached.clone(), false);
+            }
+        }
+
+        database.sequence += 1;
+        let sequence = database.sequence;
+
+        database
+            .values
+            .insert(key.clone(), (sequence, value.clone()));
+
+        database.order.push_back((sequence, key));
+
+        // Evict the oldest keys (skipping records of replaced or removed keys)
+
+        while database.values.len() > self.capacity {
+            let (sequence, key) = database.order.pop_front().unwrap();
+
+            if database.values.get(&key).map(|(current, _)| *current) == Some(sequence) {
+                database.values.remove(&key);
+            }
+        }
+
+        // Records of replaced or removed keys are garbage-collected
+        // once they outnumber the live ones
+
+        if database.order.len() > 2 * self.capacity {
+            let Database { values, order, .. } = &mut *database;
+
+            order.retain(|(sequence, key)| {
+                values.get(key).map(|(current, _)| current) == Some(sequence)
+            });
+        }
+
+        (value, true)
+    }
+
+    pub fn get(&self, key: &Key) -> Option<Value> {
+        let database = self.database.lock().unwrap();
+        database.values.get(key).map(|(_, value)| value.clone())
+    }
+
+    /// Removes the value cached for `key`, if `remove` accepts it.
+    pub fn remove_if<F>(&self, key: &Key, remove: F)
+    where
+        F: FnOnce(&Value) -> bool,
+    {
+        let mut database = self.database.lock().unwrap();
+
+        if let Some((_, cached)) = database.values.get(key) {
+            if remove(cached) {
+                database.values.remove(key);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attach() {
+        let cache = ResultCache::new(4);
+
+        assert_eq!(cache.attach_or_insert(0, 'a', |_| true), ('a', true));
+        assert_eq!(cache.attach_or_insert(0, 'b', |_| true), ('a', false));
+        assert_eq!(cache.attach_or_insert(0, 'b', |_| false), ('b', true));
+
+        cache.remove_if(&0, |value| *value == 'a');
+        assert_eq!(cache.attach_or_insert(0, 'c', |_| true), ('b', false));
+
+        cache.remove_if(&0, |value| *value == 'b');
+        assert_eq!(cache.attach_or_insert(0, 'c', |_| true), ('c', true));
+    }
+
+    #[test]
+    fn evict() {
+        let cache = ResultCache::new(4);
+
+        for key in 0..8 {
+            cache.attach_or_insert(key, key, |_| true);
+        }
+
+        // Replacing a value refreshes its key
+        cache.attach_or_insert(4, 40, |_| false);
+        cache.attach_or_insert(8, 8, |_| true);
+
+        cache.remove_if(&6, |_| true);
+        cache.attach_or_insert(9, 9, |_| true);
+
+        let expected = [
+            None,
+            None,
+            None,
+            None,
+            Some(40),
+            None,
+            None,
+            Some(7),
+            Some(8),
+            Some(9),
+        ];
+
+        for (key, expected) in expected.iter().enumerate() {
+            assert_eq!(cache.get(&key), *expected);
+        }
+    }
+}
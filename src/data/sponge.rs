@@ -3,7 +3,7 @@ use crate::data::SpongeSettings;
 use std::{
     mem,
     sync::{Arc, Mutex},
-    time::Instant,
+    time::{Duration, Instant},
 };
 
 use talk::sync::fuse::Fuse;
@@ -19,14 +19,32 @@ pub(crate) struct Sponge<Item> {
 
 struct Database<Item> {
     start: Instant,
+    timeout: Duration,
     items: Vec<Item>,
+    estimates: Estimates,
 }
 
+// Smoothed estimates (in seconds) driving adaptive flushes (see `SpongeSettings`)
+struct Estimates {
+    last_arrival: Option<Instant>,
+    interarrival: Option<f64>,
+    latency: Option<f64>,
+}
+
+// Weight of each new sample in `Estimates`'s exponential moving averages
+const SMOOTHING: f64 = 0.125;
+
 impl<Item> Sponge<Item> {
     pub fn new(settings: SpongeSettings) -> Self {
         let database = Mutex::new(Database {
             start: Instant::now(),
+            timeout: settings.timeout,
             items: Vec::new(),
+            estimates: Estimates {
+                last_arrival: None,
+                interarrival: None,
+                latency: None,
+            },
         });
 
         let notify = Arc::new(Notify::new());
@@ -44,13 +62,18 @@ impl<Item> Sponge<Item> {
     pub fn push(&self, item: Item) {
         let mut database = self.database.lock().unwrap();
 
+        if self.settings.adaptive {
+            database.estimates.arrive(Instant::now());
+        }
+
         database.items.push(item);
 
         if database.items.len() == 1 {
             database.start = Instant::now();
+            database.timeout = self.timeout(&database.estimates);
 
             let notify = self.notify.clone();
-            let timeout = self.settings.timeout;
+            let timeout = database.timeout;
 
             self.fuse.spawn(async move {
                 time::sleep(timeout).await;
@@ -58,11 +81,27 @@ impl<Item> Sponge<Item> {
             });
         }
 
-        if database.items.len() >= self.settings.capacity {
+        if database.items.len() >= self.capacity(&database.estimates) {
             self.notify.notify_one();
         }
     }
 
+    /// Reports how long it took to process a flushed batch. In adaptive
+    /// mode, this steers the size and deadline of subsequent flushes.
+    pub fn observe(&self, latency: Duration) {
+        if self.settings.adaptive {
+            let mut database = self.database.lock().unwrap();
+            database.estimates.observe(latency);
+
+            // A shrunk capacity might already be reached
+            if !database.items.is_empty()
+                && database.items.len() >= self.capacity(&database.estimates)
+            {
+                self.notify.notify_one();
+            }
+        }
+    }
+
     pub async fn flush(&self) -> Vec<Item> {
         loop {
             self.notify.notified().await;
@@ -73,8 +112,8 @@ impl<Item> Sponge<Item> {
                 continue;
             }
 
-            if database.items.len() >= self.settings.capacity
-                || database.start.elapsed() > self.settings.timeout
+            if database.items.len() >= self.capacity(&database.estimates)
+                || database.start.elapsed() > database.timeout
             {
                 let mut flush = Vec::new();
                 mem::swap(&mut flush, &mut database.items);
@@ -83,6 +122,63 @@ impl<Item> Sponge<Item> {
             }
         }
     }
+
+    fn capacity(&self, estimates: &Estimates) -> usize {
+        if !self.settings.adaptive {
+            return self.settings.capacity;
+        }
+
+        // Flush as many items as are expected to arrive while a batch is processed:
+        // under low load (or before any estimate is available) this flushes items
+        // one by one, under high load it grows batches up to `settings.capacity`
+        let expected = match (estimates.interarrival, estimates.latency) {
+            (Some(interarrival), Some(latency)) => (latency / interarrival).ceil() as usize,
+            _ => 1,
+        };
+
+        expected.min(self.settings.capacity).max(1)
+    }
+
+    fn timeout(&self, estimates: &Estimates) -> Duration {
+        if !self.settings.adaptive {
+            return self.settings.timeout;
+        }
+
+        // Waiting for longer than a batch takes to process would delay items
+        // more than processing them in a separate batch
+        match estimates.latency {
+            Some(latency) => Duration::from_secs_f64(latency).min(self.settings.timeout),
+            None => self.settings.timeout,
+        }
+    }
+}
+
+impl Estimates {
+    fn arrive(&mut self, now: Instant) {
+        if let Some(last_arrival) = self.last_arrival {
+            let sample = (now - last_arrival).as_secs_f64();
+
+            self.interarrival = Some(match self.interarrival {
+                Some(interarrival) => average(interarrival, sample),
+                None => sample,
+            });
+        }
+
+        self.last_arrival = Some(now);
+    }
+
+    fn observe(&mut self, latency: Duration) {
+        let sample = latency.as_secs_f64();
+
+        self.latency = Some(match self.latency {
+            Some(latency) => average(latency, sample),
+            None => sample,
+        });
+    }
+}
+
+fn average(estimate: f64, sample: f64) -> f64 {
+    (1. - SMOOTHING) * estimate + SMOOTHING * sample
 }
 
 #[cfg(test)]
@@ -97,6 +193,7 @@ mod tests {
         let sponge = Arc::new(Sponge::<u32>::new(SpongeSettings {
             capacity: 10,
             timeout: Duration::from_secs_f64(0.1),
+            adaptive: false,
         }));
 
         {
@@ -116,6 +213,7 @@ mod tests {
         let sponge = Arc::new(Sponge::new(SpongeSettings {
             capacity: 10,
             timeout: Duration::from_secs_f64(0.1),
+            adaptive: false,
         }));
 
         let handle = {
@@ -136,6 +234,7 @@ mod tests {
         let sponge = Arc::new(Sponge::new(SpongeSettings {
             capacity: 10,
             timeout: Duration::from_secs_f64(0.1),
+            adaptive: false,
         }));
 
         for size in 1..5 {
@@ -161,6 +260,7 @@ mod tests {
         let sponge = Arc::new(Sponge::new(SpongeSettings {
             capacity: 10,
             timeout: Duration::from_secs_f64(0.5),
+            adaptive: false,
         }));
 
         let handle = {
@@ -186,6 +286,7 @@ mod tests {
         let sponge = Arc::new(Sponge::new(SpongeSettings {
             capacity: 10,
             timeout: Duration::from_secs_f64(0.5),
+            adaptive: false,
         }));
 
         {
@@ -204,4 +305,50 @@ mod tests {
             time::sleep(Duration::from_millis(1)).await;
         }
     }
+
+    #[tokio::test]
+    async fn adaptive_low_load() {
+        let sponge = Sponge::new(SpongeSettings {
+            capacity: 10,
+            timeout: Duration::from_secs(10),
+            adaptive: true,
+        });
+
+        // With no estimate of the arrival rate, items are flushed one by one
+
+        sponge.push(42u32);
+
+        let flush = time::timeout(Duration::from_secs(1), sponge.flush())
+            .await
+            .unwrap();
+
+        assert_eq!(flush.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn adaptive_high_load() {
+        let sponge = Sponge::new(SpongeSettings {
+            capacity: 10,
+            timeout: Duration::from_secs(10),
+            adaptive: true,
+        });
+
+        sponge.observe(Duration::from_secs(1));
+
+        sponge.push(42u32);
+        assert_eq!(sponge.flush().await.len(), 1);
+
+        // Items arrive much faster than batches are processed: flushes
+        // grow up to `capacity`, within (at most) the observed latency
+
+        for _ in 0..15 {
+            sponge.push(42u32);
+        }
+
+        let flush = time::timeout(Duration::from_secs(2), sponge.flush())
+            .await
+            .unwrap();
+
+        assert!(flush.len() >= 10);
+    }
 }
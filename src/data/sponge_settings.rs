@@ -1,9 +1,16 @@
+use serde::Deserialize;
+
 use std::time::Duration;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
 pub(crate) struct SpongeSettings {
     pub capacity: usize,
     pub timeout: Duration,
+
+    // If `adaptive`, flush size and deadline are tuned from the observed arrival
+    // rate and processing latency, with `capacity` and `timeout` as upper bounds
+    pub adaptive: bool,
 }
 
 impl Default for SpongeSettings {
@@ -11,6 +18,7 @@ impl Default for SpongeSettings {
         SpongeSettings {
             capacity: 100,
             timeout: Duration::from_secs(1),
+            adaptive: false,
         }
     }
 }
@@ -1,14 +1,24 @@
+use bit_vec::BitVec;
+
 use crate::commit::{BatchCompletion, WitnessedBatch};
 
+use std::iter;
+
 pub(crate) struct BatchHolder {
     batch: WitnessedBatch,
+    references: BitVec,
     completion: Option<BatchCompletion>,
 }
 
 impl BatchHolder {
     pub fn new(batch: WitnessedBatch) -> Self {
+        let references = iter::repeat(true)
+            .take(batch.payloads().len())
+            .collect::<BitVec>();
+
         BatchHolder {
             batch,
+            references,
             completion: None,
         }
     }
@@ -24,4 +34,18 @@ impl BatchHolder {
     pub fn attach(&mut self, completion: BatchCompletion) {
         self.completion = Some(completion);
     }
+
+    pub fn reference(&mut self, index: usize) {
+        self.references.set(index, true);
+    }
+
+    pub fn unref(&mut self, index: usize) {
+        self.references.set(index, false);
+    }
+
+    /// A `BatchHolder` is unreferenced once no `PayloadHandle` points to
+    /// any of its `Payload`s: it can then be garbage collected.
+    pub fn is_unreferenced(&self) -> bool {
+        self.references.none()
+    }
 }
@@ -12,6 +12,7 @@ use talk::crypto::primitives::hash::Hash;
 pub(crate) struct Commit {
     pub batches: HashMap<Hash, BatchHolder>,
     pub payloads: Buckets<HashMap<Entry, PayloadHandle>>,
+    pub superseded: Vec<Entry>,
 }
 
 impl Commit {
@@ -19,6 +20,7 @@ impl Commit {
         Commit {
             batches: HashMap::new(),
             payloads: Buckets::new(),
+            superseded: Vec::new(),
         }
     }
 }
@@ -1,5 +1,8 @@
+use serde::{Deserialize, Serialize};
+
 use talk::crypto::primitives::hash::Hash;
 
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub(crate) struct PayloadHandle {
     pub batch: Hash,
     pub index: usize,
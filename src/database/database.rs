@@ -1,25 +1,37 @@
-use buckets::Buckets;
+use buckets::{Buckets, Split};
 
 use crate::{
     account::{Account, AccountSummary, Id},
-    database::{Commit, Prepare, Signup, Zebras},
+    database::{
+        commit::BatchHolder as CommitBatchHolder,
+        prepare::{BatchHolder as PrepareBatchHolder, State as PrepareState},
+        storage::{Journal, Record, Storage, StorageError},
+        Commit, Motions, Prepare, Receipt, Signup, Zebras,
+    },
     signup::IdAssignment,
 };
 
-use std::collections::HashMap;
+use doomstack::{here, ResultExt, Top};
 
-use zebra::database::Table;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use zebra::database::{CollectionTransaction, Table, TableTransaction};
 
 pub(crate) struct Database {
     pub assignments: Buckets<HashMap<Id, IdAssignment>>,
     pub accounts: Buckets<HashMap<Id, Account>>,
+    pub history: Buckets<HashMap<Id, BTreeMap<u64, Receipt>>>,
     pub imminent: Table<Id, AccountSummary>,
 
     pub signup: Signup,
     pub prepare: Prepare,
     pub commit: Commit,
 
+    pub motions: Motions,
+
     pub families: Zebras,
+
+    pub journal: Journal,
 }
 
 impl Database {
@@ -29,13 +41,376 @@ impl Database {
         Database {
             assignments: Buckets::new(),
             accounts: Buckets::new(),
+            history: Buckets::new(),
             imminent: zebras.ids_to_account_summaries.empty_table(),
 
             signup: Signup::new(&zebras),
             prepare: Prepare::new(&zebras),
             commit: Commit::new(),
 
+            motions: Motions::new(),
+
             families: zebras,
+
+            journal: Journal::volatile(),
+        }
+    }
+
+    /// Opens a `Database` backed by `storage`, restoring all `Record`s previously
+    /// journaled to `storage`. All subsequent updates are journaled to `storage`.
+    pub fn open<S>(mut storage: S) -> Result<Self, Top<StorageError>>
+    where
+        S: 'static + Storage,
+    {
+        let records = storage.load().spot(here!())?;
+
+        let mut database = Database::new();
+        database.restore(records);
+
+        database.journal = Journal::persistent(Box::new(storage));
+
+        Ok(database)
+    }
+
+    fn restore(&mut self, records: Vec<Record>) {
+        // Later `Record`s supersede earlier `Record`s for the same element
+
+        let mut assignments = HashMap::new();
+        let mut accounts = HashMap::new();
+        let mut receipts = Vec::new();
+        let mut states = HashMap::new();
+        let mut payloads = HashMap::new();
+        let mut prepare_commits = Vec::new();
+        let mut completions = Vec::new();
+
+        let mut claimed = CollectionTransaction::new();
+
+        for record in records {
+            match record {
+                Record::Allocation { identity, id } => {
+                    self.signup.allocated.insert(id);
+                    self.signup.allocations.insert(identity, id);
+                }
+                Record::Claim(claim) => {
+                    let _ = claimed.insert(claim.id());
+                    self.signup.claims.insert(claim.id(), claim);
+                }
+                Record::Assignment(assignment) => {
+                    assignments.insert(assignment.id(), assignment);
+                }
+                Record::PrepareState { id, state } => {
+                    states.insert(id, state);
+                }
+                Record::PrepareBatch(batch) => {
+                    self.prepare
+                        .batches
+                        .insert(batch.root(), PrepareBatchHolder::new(batch));
+                }
+                Record::PrepareCommit(commit) => {
+                    prepare_commits.push(commit);
+                }
+                Record::PrepareCollect(root) => {
+                    self.prepare.batches.remove(&root);
+                }
+                Record::CommitBatch(batch) => {
+                    self.commit
+                        .batches
+                        .insert(batch.root(), CommitBatchHolder::new(batch));
+                }
+                Record::CommitCollect(root) => {
+                    self.commit.batches.remove(&root);
+                }
+                Record::Account { id, account } => {
+                    accounts.insert(id, account);
+                }
+                Record::Receipt(receipt) => {
+                    receipts.push(receipt);
+                }
+                Record::Payload { entry, handle } => {
+                    payloads.insert(entry, handle);
+                }
+                Record::PayloadCollect(entry) => {
+                    payloads.remove(&entry);
+                }
+                Record::Completion(completion) => {
+                    completions.push(completion);
+                }
+            }
+        }
+
+        self.signup.claimed.execute(claimed);
+
+        // `BatchCommit`s and `BatchCompletion`s can only be attached once
+        // all batches are restored
+
+        for commit in prepare_commits {
+            if let Some(holder) = self.prepare.batches.get_mut(&commit.root()) {
+                holder.attach(commit);
+            }
+        }
+
+        for completion in completions {
+            if let Some(holder) = self.commit.batches.get_mut(&completion.root()) {
+                holder.attach(completion);
+            }
+        }
+
+        // References are not journaled, and are recomputed from restored
+        // elements: a `Prepare` is referenced if its state's pipeline is still
+        // `Batched` in its batch at its height, which was not committed yet, ...
+
+        for (root, holder) in self.prepare.batches.iter_mut() {
+            let ids = holder
+                .batch()
+                .prepares()
+                .iter()
+                .map(|prepare| prepare.id())
+                .collect::<Vec<_>>();
+
+            for (index, id) in ids.into_iter().enumerate() {
+                let referenced = match states.get(&id) {
+                    Some(PrepareState::Consistent(pipeline)) => {
+                        pipeline.iter().any(|(height, _, handle)| {
+                            handle.batched() == Some((*root, index))
+                                && accounts
+                                    .get(&id)
+                                    .map_or(true, |account| account.height() < height)
+                        })
+                    }
+                    _ => false,
+                };
+
+                if !referenced {
+                    holder.unref(index);
+                }
+            }
+        }
+
+        // ... and a `Payload` is referenced if a `PayloadHandle` points to it.
+        // `Payload`s at a lower height than their `Account` are superseded.
+
+        let handles = payloads
+            .values()
+            .map(|handle| (handle.batch, handle.index))
+            .collect::<HashSet<_>>();
+
+        for (root, holder) in self.commit.batches.iter_mut() {
+            for index in 0..holder.batch().payloads().len() {
+                if !handles.contains(&(*root, index)) {
+                    holder.unref(index);
+                }
+            }
+        }
+
+        self.commit.superseded = payloads
+            .keys()
+            .filter(|entry| {
+                accounts
+                    .get(&entry.id)
+                    .map_or(false, |account| account.height() > entry.height)
+            })
+            .copied()
+            .collect();
+
+        // The root of `imminent` is a function of its content only: re-setting
+        // the summary of every `Account` restores the same root
+
+        let mut imminent = TableTransaction::new();
+
+        for (id, account) in accounts.iter() {
+            imminent.set(*id, account.summarize()).unwrap();
+        }
+
+        self.imminent.execute(imminent);
+
+        // `motions` is not journaled
+
+        let none = BTreeSet::new();
+
+        for account in accounts.values() {
+            self.motions.update(&none, &account.motions());
         }
+
+        self.assignments
+            .apply(
+                Split::with_key(assignments, |(id, _)| *id),
+                |assignments, (id, assignment)| {
+                    assignments.insert(id, assignment);
+                },
+            )
+            .join();
+
+        self.accounts
+            .apply(
+                Split::with_key(accounts, |(id, _)| *id),
+                |accounts, (id, account)| {
+                    accounts.insert(id, account);
+                },
+            )
+            .join();
+
+        self.history
+            .apply(
+                Split::with_key(receipts, |receipt| receipt.entry().id),
+                |history, receipt| {
+                    let entry = receipt.entry();
+
+                    history
+                        .entry(entry.id)
+                        .or_insert_with(BTreeMap::new)
+                        .insert(entry.height, receipt);
+                },
+            )
+            .join();
+
+        // Restored states are flagged as `stale`, as `prepare.advertisements`
+        // is not journaled
+        self.prepare.stale = states.keys().copied().collect();
+
+        self.prepare
+            .states
+            .apply(
+                Split::with_key(states, |(id, _)| *id),
+                |states, (id, state)| {
+                    states.insert(id, state);
+                },
+            )
+            .join();
+
+        self.commit
+            .payloads
+            .apply(
+                Split::with_key(payloads, |(entry, _)| entry.id),
+                |payloads, (entry, handle)| {
+                    payloads.insert(entry, handle);
+                },
+            )
+            .join();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{
+        account::AccountSettings,
+        database::{
+            prepare::{Pipeline, PrepareHandle},
+            storage::LogStorage,
+        },
+    };
+
+    use std::{env, fs, path::Path};
+
+    use talk::crypto::{primitives::hash, KeyChain};
+
+    fn open(path: &Path) -> Database {
+        Database::open(LogStorage::open(path, Default::default()).unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn reopen() {
+        let path = env::temp_dir().join(format!("carbon-database-{}", rand::random::<u64>()));
+
+        let identity = KeyChain::random().keycard().identity();
+
+        let account = Account::new(
+            0,
+            &AccountSettings {
+                initial_balance: 10,
+                ..Default::default()
+            },
+        );
+
+        let summary = account.summarize();
+
+        let commitment = hash::hash(&0u32).unwrap();
+
+        let state = PrepareState::Consistent(Pipeline::new(
+            1,
+            commitment,
+            PrepareHandle::Batched {
+                batch: hash::hash(&1u32).unwrap(),
+                index: 0,
+            },
+            None,
+        ));
+
+        {
+            let mut database = open(&path);
+
+            database.signup.allocated.insert(7);
+            database.signup.allocations.insert(identity, 7);
+            database
+                .journal
+                .record(Record::Allocation { identity, id: 7 });
+
+            database
+                .accounts
+                .apply(
+                    Split::with_key(vec![(0, account.clone())], |(id, _)| *id),
+                    |accounts, (id, account)| {
+                        accounts.insert(id, account);
+                    },
+                )
+                .join();
+
+            database.journal.record(Record::Account { id: 0, account });
+
+            database
+                .prepare
+                .states
+                .apply(
+                    Split::with_key(vec![(0, state.clone())], |(id, _)| *id),
+                    |states, (id, state)| {
+                        states.insert(id, state);
+                    },
+                )
+                .join();
+
+            database
+                .journal
+                .record(Record::PrepareState { id: 0, state });
+
+            database.journal.flush().await.unwrap();
+        }
+
+        {
+            let database = open(&path);
+
+            assert!(database.signup.allocated.contains(&7));
+            assert_eq!(database.signup.allocations.get(&identity), Some(&7));
+
+            let summaries = database
+                .accounts
+                .apply(Split::with_key(vec![0], |id| *id), |accounts, id| {
+                    accounts.get(&id).map(Account::summarize)
+                })
+                .join();
+
+            assert_eq!(summaries, vec![Some(summary)]);
+
+            // The commitment prepared at height 1 survives: the replica
+            // cannot sign a conflicting one upon restart
+            let commitments = database
+                .prepare
+                .states
+                .apply(
+                    Split::with_key(vec![0], |id| *id),
+                    |states, id| match states.get(&id) {
+                        Some(PrepareState::Consistent(pipeline)) => {
+                            pipeline.get(1).map(|(commitment, _)| commitment)
+                        }
+                        _ => None,
+                    },
+                )
+                .join();
+
+            assert_eq!(commitments, vec![Some(commitment)]);
+            assert!(database.prepare.stale.contains(&0));
+        }
+
+        fs::remove_dir_all(&path).unwrap();
     }
 }
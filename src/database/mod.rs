@@ -1,12 +1,17 @@
 mod database;
+mod motions;
+mod receipt;
 mod signup;
 mod zebras;
 
 pub(crate) mod commit;
 pub(crate) mod prepare;
+pub(crate) mod storage;
 
 pub(crate) use commit::Commit;
 pub(crate) use database::Database;
+pub(crate) use motions::Motions;
 pub(crate) use prepare::Prepare;
+pub(crate) use receipt::Receipt;
 pub(crate) use signup::Signup;
 pub(crate) use zebras::Zebras;
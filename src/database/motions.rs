@@ -0,0 +1,72 @@
+use std::collections::{BTreeSet, HashMap};
+
+use talk::crypto::primitives::hash::Hash;
+
+/// Tallies, for each motion, the number of correct accounts supporting it.
+/// Tallies are not journaled: they are recomputed from `Account`s on restore.
+pub(crate) struct Motions {
+    tallies: HashMap<Hash, u64>,
+}
+
+impl Motions {
+    pub fn new() -> Self {
+        Motions {
+            tallies: HashMap::new(),
+        }
+    }
+
+    pub fn tally(&self, motion: &Hash) -> u64 {
+        self.tallies.get(motion).copied().unwrap_or(0)
+    }
+
+    /// Updates tallies to reflect an account whose supported motions
+    /// changed from `before` to `after`.
+    pub fn update(&mut self, before: &BTreeSet<Hash>, after: &BTreeSet<Hash>) {
+        for motion in after.difference(before) {
+            *self.tallies.entry(*motion).or_insert(0) += 1;
+        }
+
+        for motion in before.difference(after) {
+            if let Some(tally) = self.tallies.get_mut(motion) {
+                *tally -= 1;
+
+                if *tally == 0 {
+                    self.tallies.remove(motion);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use talk::crypto::primitives::hash;
+
+    #[test]
+    fn update() {
+        let alpha = hash::hash(&0u64).unwrap();
+        let beta = hash::hash(&1u64).unwrap();
+
+        let none = BTreeSet::new();
+        let both = [alpha, beta].iter().copied().collect::<BTreeSet<_>>();
+        let beta_only = [beta].iter().copied().collect::<BTreeSet<_>>();
+
+        let mut motions = Motions::new();
+
+        motions.update(&none, &both);
+        motions.update(&none, &beta_only);
+
+        assert_eq!(motions.tally(&alpha), 1);
+        assert_eq!(motions.tally(&beta), 2);
+
+        // Abandon `alpha`, then corrupt (i.e., drop all supports)
+        motions.update(&both, &beta_only);
+        motions.update(&beta_only, &none);
+
+        assert_eq!(motions.tally(&alpha), 0);
+        assert_eq!(motions.tally(&beta), 1);
+        assert!(!motions.tallies.contains_key(&alpha));
+    }
+}
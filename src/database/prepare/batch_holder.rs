@@ -1,6 +1,9 @@
 use bit_vec::BitVec;
 
-use crate::prepare::{BatchCommit, Extract, WitnessedBatch};
+use crate::{
+    commit::CommitProof,
+    prepare::{BatchCommit, Extract, WitnessedBatch},
+};
 
 use std::iter;
 
@@ -23,6 +26,10 @@ impl BatchHolder {
         }
     }
 
+    pub fn batch(&self) -> &WitnessedBatch {
+        &self.batch
+    }
+
     pub fn extract(&self, index: usize) -> Extract {
         self.batch.extract(index)
     }
@@ -31,6 +38,27 @@ impl BatchHolder {
         self.commit.as_ref()
     }
 
+    /// Determines whether or not the `Prepare` at `index` is known to be committed,
+    /// i.e., `self.commit()` is attached and does not except it.
+    pub fn committed(&self, index: usize) -> bool {
+        let id = self.batch.prepares()[index].id();
+
+        self.commit
+            .as_ref()
+            .map_or(false, |commit| !commit.excepts(id))
+    }
+
+    /// Returns a `CommitProof` for the `Prepare` at `index`, if `self.commit()`
+    /// is attached and does not except it.
+    pub fn commit_proof(&self, index: usize) -> Option<CommitProof> {
+        let id = self.batch.prepares()[index].id();
+
+        self.commit
+            .as_ref()
+            .filter(|commit| !commit.excepts(id))
+            .map(|commit| CommitProof::new(commit.clone(), self.batch.prove(index)))
+    }
+
     pub fn attach(&mut self, commit: BatchCommit) {
         self.commit = Some(commit);
     }
@@ -38,4 +66,10 @@ impl BatchHolder {
     pub fn unref(&mut self, index: usize) {
         self.references.set(index, false);
     }
+
+    /// A `BatchHolder` is unreferenced once each of its `Prepare`s is either
+    /// superseded or committed: it can then be garbage collected.
+    pub fn is_unreferenced(&self) -> bool {
+        self.references.none()
+    }
 }
@@ -1,11 +1,13 @@
 mod advertisement;
 mod batch_holder;
+mod pipeline;
 mod prepare;
 mod prepare_handle;
 mod state;
 
 pub(crate) use advertisement::Advertisement;
 pub(crate) use batch_holder::BatchHolder;
+pub(crate) use pipeline::Pipeline;
 pub(crate) use prepare::Prepare;
 pub(crate) use prepare_handle::PrepareHandle;
 pub(crate) use state::State;
@@ -0,0 +1,186 @@
+use crate::{database::prepare::PrepareHandle, prepare::Continuity};
+
+use serde::{Deserialize, Serialize};
+
+use std::collections::BTreeMap;
+
+use talk::crypto::primitives::hash::Hash;
+
+/// The commitments (and `PrepareHandle`s) of the `Prepare`s observed for an
+/// `Id` at its highest heights (at most `pipeline_width`, see `AccountSettings`,
+/// unless the lowest ones are not yet superseded by a committed `Prepare`).
+/// A `Pipeline` is never empty.
+///
+/// Every height below a `Pipeline` is provably stale: either a `Prepare` in the
+/// `Pipeline` is committed (heights are evicted only once superseded by a
+/// committed `Prepare`, see `Pipeline::push`), or `floor` proves that the client
+/// reached the height below `lowest` (a `Pipeline` above height 1 is initialized
+/// only along with a `Continuity`).
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct Pipeline {
+    prepares: BTreeMap<u64, (Hash, PrepareHandle)>,
+    floor: Option<Continuity>,
+}
+
+impl Pipeline {
+    /// Initializes a `Pipeline` at `height`. Unless `height` is 1, `floor`
+    /// must prove that the client reached `height - 1`.
+    pub fn new(
+        height: u64,
+        commitment: Hash,
+        handle: PrepareHandle,
+        floor: Option<Continuity>,
+    ) -> Self {
+        let mut prepares = BTreeMap::new();
+        prepares.insert(height, (commitment, handle));
+
+        Pipeline { prepares, floor }
+    }
+
+    pub fn get(&self, height: u64) -> Option<(Hash, &PrepareHandle)> {
+        self.prepares
+            .get(&height)
+            .map(|(commitment, handle)| (*commitment, handle))
+    }
+
+    /// Returns the lowest height in `self`, along with its `PrepareHandle`.
+    pub fn lowest(&self) -> (u64, &PrepareHandle) {
+        // `self` is never empty
+        let (height, (_, handle)) = self.prepares.iter().next().unwrap();
+        (*height, handle)
+    }
+
+    /// Returns the highest height in `self`, along with its commitment.
+    pub fn highest(&self) -> (u64, Hash) {
+        // `self` is never empty
+        let (height, (commitment, _)) = self.prepares.iter().next_back().unwrap();
+        (*height, *commitment)
+    }
+
+    /// Returns the `Continuity` (if any) with which `self` was initialized.
+    pub fn floor(&self) -> Option<&Continuity> {
+        self.floor.as_ref()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (u64, Hash, &PrepareHandle)> {
+        self.prepares
+            .iter()
+            .map(|(height, (commitment, handle))| (*height, *commitment, handle))
+    }
+
+    pub fn handles(&self) -> impl Iterator<Item = &PrepareHandle> {
+        self.prepares.values().map(|(_, handle)| handle)
+    }
+
+    pub fn handles_mut(&mut self) -> impl Iterator<Item = &mut PrepareHandle> {
+        self.prepares.values_mut().map(|(_, handle)| handle)
+    }
+
+    /// Adds a `Prepare` at `height` (not in `self`, and higher than `self.lowest()`)
+    /// to `self`. The lowest heights in excess of `width` are then evicted, as long
+    /// as a higher `Prepare` in `self` is `committed` (a client might still need any
+    /// height above its highest committed `Prepare`): their `PrepareHandle`s are
+    /// returned.
+    pub fn push<C>(
+        &mut self,
+        height: u64,
+        commitment: Hash,
+        handle: PrepareHandle,
+        width: u64,
+        committed: C,
+    ) -> Vec<PrepareHandle>
+    where
+        C: Fn(&PrepareHandle) -> bool,
+    {
+        self.prepares.insert(height, (commitment, handle));
+
+        let mut evicted = Vec::new();
+
+        while self.prepares.len() as u64 > width.max(1) {
+            let lowest = *self.prepares.keys().next().unwrap();
+
+            let superseded = self
+                .prepares
+                .range((lowest + 1)..)
+                .any(|(_, (_, handle))| committed(handle));
+
+            if !superseded {
+                break;
+            }
+
+            let (_, handle) = self.prepares.remove(&lowest).unwrap();
+            evicted.push(handle);
+        }
+
+        evicted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use talk::crypto::primitives::hash;
+
+    fn handle(index: usize) -> PrepareHandle {
+        PrepareHandle::Batched {
+            batch: hash::hash(&0u32).unwrap(),
+            index,
+        }
+    }
+
+    fn indices(handles: &[PrepareHandle]) -> Vec<usize> {
+        handles
+            .iter()
+            .filter_map(PrepareHandle::batched)
+            .map(|(_, index)| index)
+            .collect()
+    }
+
+    #[test]
+    fn push() {
+        let commitment = hash::hash(&0u64).unwrap();
+
+        let mut pipeline = Pipeline::new(1, commitment, handle(1), None);
+
+        // No `Prepare` is committed
+        let uncommitted = |_: &PrepareHandle| false;
+
+        assert!(pipeline
+            .push(2, commitment, handle(2), 3, uncommitted)
+            .is_empty());
+        assert!(pipeline
+            .push(4, commitment, handle(4), 3, uncommitted)
+            .is_empty());
+
+        assert_eq!(pipeline.lowest().0, 1);
+        assert_eq!(pipeline.highest().0, 4);
+
+        // A height between `lowest` and `highest` is still accepted. Heights in
+        // excess of `width` are retained until a higher `Prepare` is committed
+        assert!(pipeline
+            .push(3, commitment, handle(3), 3, uncommitted)
+            .is_empty());
+
+        assert_eq!(pipeline.lowest().0, 1);
+        assert_eq!(pipeline.get(3).unwrap().1.batched().unwrap().1, 3);
+
+        // Once the `Prepare` at height 3 is committed, the heights below
+        // it are evicted (down to `width`)
+        let committed = |handle: &PrepareHandle| handle.batched().unwrap().1 == 3;
+
+        let evicted = pipeline.push(5, commitment, handle(5), 3, committed);
+
+        assert_eq!(indices(&evicted), vec![1, 2]);
+        assert_eq!(pipeline.lowest().0, 3);
+        assert!(pipeline.get(2).is_none());
+
+        // Heights at or above the highest committed `Prepare` are never
+        // evicted, regardless of `width`
+        let evicted = pipeline.push(6, commitment, handle(6), 1, committed);
+
+        assert!(evicted.is_empty());
+        assert_eq!(pipeline.lowest().0, 3);
+        assert_eq!(pipeline.highest().0, 6);
+    }
+}
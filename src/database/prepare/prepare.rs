@@ -1,25 +1,29 @@
-use buckets::Buckets;
+use buckets::{Buckets, Split};
 
 use crate::{
-    account::Id,
+    account::{Entry, Id},
     database::{
         prepare::{Advertisement, BatchHolder, State},
         Zebras,
     },
 };
 
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    mem,
+};
 
 use talk::crypto::primitives::hash::Hash;
 
-use zebra::database::Table;
+use zebra::database::{Table, TableTransaction};
 
 pub(crate) struct Prepare {
     pub advertisements: Table<Id, Advertisement>,
     pub advertised: Buckets<HashSet<Id>>,
     pub states: Buckets<HashMap<Id, State>>,
-    pub stale: Buckets<HashSet<Id>>,
+    pub stale: HashSet<Id>,
     pub batches: HashMap<Hash, BatchHolder>,
+    pub committed: Vec<Entry>,
 }
 
 impl Prepare {
@@ -28,8 +32,41 @@ impl Prepare {
             advertisements: zebras.ids_to_prepare_advertisements.empty_table(),
             advertised: Buckets::new(),
             states: Buckets::new(),
-            stale: Buckets::new(),
+            stale: HashSet::new(),
             batches: HashMap::new(),
+            committed: Vec::new(),
         }
     }
+
+    /// Updates `advertisements` to reflect the current value of every
+    /// `stale` state. Advertisements are flushed lazily, immediately
+    /// before `advertisements` is shared (e.g., on state transfer).
+    pub fn flush_advertisements(&mut self) {
+        let stale = mem::take(&mut self.stale);
+
+        let advertisements = buckets::apply_sparse(
+            &mut self.states,
+            Split::with_key(stale, |id| *id),
+            |states, id| {
+                let advertisement = match states.get(&id)? {
+                    // Only the highest `Prepare` in `pipeline` is advertised
+                    State::Consistent(pipeline) => {
+                        let (height, commitment) = pipeline.highest();
+                        Advertisement::Consistent { height, commitment }
+                    }
+                    State::Equivocated(_) => Advertisement::Equivocated,
+                };
+
+                Some((id, advertisement))
+            },
+        );
+
+        let mut transaction = TableTransaction::new();
+
+        for (id, advertisement) in advertisements {
+            transaction.set(id, advertisement).unwrap();
+        }
+
+        self.advertisements.execute(transaction);
+    }
 }
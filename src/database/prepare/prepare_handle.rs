@@ -1,9 +1,57 @@
-use crate::prepare::Extract;
+use crate::{commit::CommitProof, database::prepare::BatchHolder, prepare::Extract};
+
+use serde::{Deserialize, Serialize};
+
+use std::collections::HashMap;
 
 use talk::crypto::primitives::hash::Hash;
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub(crate) enum PrepareHandle {
     Batched { batch: Hash, index: usize },
-    Standalone(Extract),
+    // A `Standalone` handle retains the `CommitProof` of its `Prepare` (if committed)
+    Standalone(Extract, Option<CommitProof>),
+}
+
+impl PrepareHandle {
+    /// If `self` is `Batched`, returns its batch and index.
+    pub fn batched(&self) -> Option<(Hash, usize)> {
+        match self {
+            PrepareHandle::Batched { batch, index } => Some((*batch, *index)),
+            PrepareHandle::Standalone(..) => None,
+        }
+    }
+
+    /// Retrieves the `Extract` of the `Prepare` referenced by `self`
+    /// (if `Batched`, its batch must be in `batches`).
+    pub fn extract(&self, batches: &HashMap<Hash, BatchHolder>) -> Extract {
+        match self {
+            PrepareHandle::Batched { batch, index } => batches.get(batch).unwrap().extract(*index),
+
+            // The batch was garbage collected, leaving a ready-made `Extract` behind
+            PrepareHandle::Standalone(extract, _) => extract.clone(),
+        }
+    }
+
+    /// Determines whether or not the `Prepare` referenced by `self` is known
+    /// to be committed (if `Batched`, its batch must be in `batches`).
+    pub fn committed(&self, batches: &HashMap<Hash, BatchHolder>) -> bool {
+        match self {
+            PrepareHandle::Batched { batch, index } => {
+                batches.get(batch).unwrap().committed(*index)
+            }
+            PrepareHandle::Standalone(_, proof) => proof.is_some(),
+        }
+    }
+
+    /// Retrieves a `CommitProof` for the `Prepare` referenced by `self`, if
+    /// known to be committed (if `Batched`, its batch must be in `batches`).
+    pub fn commit_proof(&self, batches: &HashMap<Hash, BatchHolder>) -> Option<CommitProof> {
+        match self {
+            PrepareHandle::Batched { batch, index } => {
+                batches.get(batch).unwrap().commit_proof(*index)
+            }
+            PrepareHandle::Standalone(_, proof) => proof.clone(),
+        }
+    }
 }
@@ -1,13 +1,9 @@
-use crate::{database::prepare::PrepareHandle, prepare::Equivocation};
+use crate::{database::prepare::Pipeline, prepare::Equivocation};
 
-use talk::crypto::primitives::hash::Hash;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub(crate) enum State {
-    Consistent {
-        height: u64,
-        commitment: Hash,
-        handle: PrepareHandle,
-    },
+    Consistent(Pipeline),
     Equivocated(Equivocation),
 }
@@ -0,0 +1,44 @@
+use crate::{
+    account::{Entry, Outcome},
+    commit::Payload,
+};
+
+use serde::{Deserialize, Serialize};
+
+use talk::crypto::primitives::hash::Hash;
+
+/// Records the application of a `Payload` to its `Account`, along with the root
+/// of the batch (and `BatchCompletion`) it was applied from. Unlike the `Payload`s
+/// in `Commit`, `Receipt`s are never garbage collected.
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct Receipt {
+    payload: Payload,
+    outcome: Outcome,
+    root: Hash,
+}
+
+impl Receipt {
+    pub fn new(payload: Payload, outcome: Outcome, root: Hash) -> Self {
+        Receipt {
+            payload,
+            outcome,
+            root,
+        }
+    }
+
+    pub fn entry(&self) -> Entry {
+        self.payload.entry()
+    }
+
+    pub fn payload(&self) -> &Payload {
+        &self.payload
+    }
+
+    pub fn outcome(&self) -> &Outcome {
+        &self.outcome
+    }
+
+    pub fn root(&self) -> Hash {
+        self.root
+    }
+}
@@ -9,11 +9,8 @@ use zebra::database::Collection;
 pub(crate) struct Signup {
     pub allocated: HashSet<Id>,
     pub allocations: HashMap<Identity, Id>,
-
-    // TODO: Include in state-transfer {
     pub claimed: Collection<Id>,
     pub claims: HashMap<Id, IdClaim>,
-    // }
 }
 
 impl Signup {
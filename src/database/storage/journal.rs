@@ -0,0 +1,114 @@
+use crate::database::storage::{Record, Storage, StorageError};
+
+use doomstack::Top;
+
+use std::{mem, sync::Arc};
+
+use tokio::{
+    sync::{Mutex, OwnedMutexGuard},
+    task,
+};
+
+type StorageGuard = OwnedMutexGuard<Box<dyn Storage>>;
+
+/// Collects the `Record`s produced by updates to a `Database`, to be
+/// flushed to its `Storage` (if any).
+pub(crate) struct Journal {
+    storage: Option<Arc<Mutex<Box<dyn Storage>>>>,
+    records: Vec<Record>,
+}
+
+impl Journal {
+    pub fn volatile() -> Self {
+        Journal {
+            storage: None,
+            records: Vec::new(),
+        }
+    }
+
+    pub fn persistent(storage: Box<dyn Storage>) -> Self {
+        Journal {
+            storage: Some(Arc::new(Mutex::new(storage))),
+            records: Vec::new(),
+        }
+    }
+
+    /// If `false`, `Record`s are discarded: callers can use this
+    /// to avoid building `Record`s altogether.
+    pub fn is_persistent(&self) -> bool {
+        self.storage.is_some()
+    }
+
+    pub fn record(&mut self, record: Record) {
+        if self.is_persistent() {
+            self.records.push(record);
+        }
+    }
+
+    pub fn extend<R>(&mut self, records: R)
+    where
+        R: IntoIterator<Item = Record>,
+    {
+        if self.is_persistent() {
+            self.records.extend(records);
+        }
+    }
+
+    /// Returns the `Storage` pending `Record`s are flushed to (if any).
+    ///
+    /// Remark: to preserve the order of `Record`s across concurrent flushes, pending
+    /// `Record`s must be taken only after locking the returned `Storage`, which must
+    /// stay locked until they are appended (or restored).
+    pub fn storage(&self) -> Option<Arc<Mutex<Box<dyn Storage>>>> {
+        self.storage.clone()
+    }
+
+    /// Takes all pending `Record`s.
+    pub fn take(&mut self) -> Vec<Record> {
+        mem::take(&mut self.records)
+    }
+
+    /// Puts back `records` that could not be appended to `Storage`.
+    pub fn restore(&mut self, mut records: Vec<Record>) {
+        records.append(&mut self.records);
+        self.records = records;
+    }
+
+    /// Durably appends all pending `Record`s to `Storage`. Upon failure,
+    /// pending `Record`s are retained for the next flush.
+    pub async fn flush(&mut self) -> Result<(), Top<StorageError>> {
+        let storage = match self.storage() {
+            Some(storage) => storage.lock_owned().await,
+            None => return Ok(()),
+        };
+
+        let records = self.take();
+        let (_storage, records, result) = Journal::append(storage, records).await;
+
+        if result.is_err() {
+            self.restore(records);
+        }
+
+        result
+    }
+
+    /// Appends `records` to the locked `storage`. As `Storage::append` blocks until
+    /// `records` are synced to disk, it is run off the async runtime. Both `storage`
+    /// and `records` are handed back (the latter, to be restored upon failure).
+    pub async fn append(
+        mut storage: StorageGuard,
+        records: Vec<Record>,
+    ) -> (StorageGuard, Vec<Record>, Result<(), Top<StorageError>>) {
+        if records.is_empty() {
+            return (storage, records, Ok(()));
+        }
+
+        // A panic in `Storage::append` is propagated
+        task::spawn_blocking(move || {
+            let result = storage.append(records.as_slice());
+            (storage, records, result)
+        })
+        .await
+        .unwrap()
+    }
+}
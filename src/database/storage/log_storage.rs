@@ -0,0 +1,371 @@
+use crate::database::storage::{LogStorageSettings, Record, Storage, StorageError};
+
+use doomstack::{here, Doom, ResultExt, Top};
+
+use std::{
+    convert::TryInto,
+    fs::{self, File, OpenOptions},
+    io::{self, BufWriter, Write},
+    mem,
+    path::{Path, PathBuf},
+};
+
+const SNAPSHOT: &str = "snapshot";
+const SNAPSHOT_SWAP: &str = "snapshot.swap";
+const LOG: &str = "log";
+
+// Each `Record` is framed by its serialized length, encoded as a little-endian `u64`
+const FRAME_HEADER: usize = mem::size_of::<u64>();
+
+/// A `Storage` backed by a directory, holding an append-only log of `Record`s
+/// and a snapshot of the `Record`s that survived the latest compaction.
+pub(crate) struct LogStorage {
+    path: PathBuf,
+    log: File,
+    logged: usize,
+    settings: LogStorageSettings,
+}
+
+impl LogStorage {
+    /// Opens the `LogStorage` at `path`, creating an empty one if `path` does not exist.
+    pub fn open<P>(path: P, settings: LogStorageSettings) -> Result<Self, Top<StorageError>>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref().to_path_buf();
+
+        fs::create_dir_all(&path)
+            .map_err(StorageError::open_failed)
+            .map_err(Doom::into_top)
+            .spot(here!())?;
+
+        let log = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path.join(LOG))
+            .map_err(StorageError::open_failed)
+            .map_err(Doom::into_top)
+            .spot(here!())?;
+
+        Ok(LogStorage {
+            path,
+            log,
+            logged: 0,
+            settings,
+        })
+    }
+
+    fn read(&self) -> Result<Vec<Record>, Top<StorageError>> {
+        // The snapshot is replaced atomically, and must always be well-formed
+        let mut records = match fs::read(self.path.join(SNAPSHOT)) {
+            Ok(buffer) => {
+                let (records, complete) = LogStorage::parse(buffer.as_slice());
+
+                if !complete {
+                    return StorageError::MalformedRecord.fail().spot(here!());
+                }
+
+                records
+            }
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(error) => {
+                return Err(StorageError::read_failed(error).into_top()).spot(here!());
+            }
+        };
+
+        let buffer = fs::read(self.path.join(LOG))
+            .map_err(StorageError::read_failed)
+            .map_err(Doom::into_top)
+            .spot(here!())?;
+
+        // A crash while appending to the log can leave a partial `Record` at its tail:
+        // such a `Record` was never acknowledged by `append`, and is safely discarded
+        // (the next compaction removes it from the log)
+        let (log, _) = LogStorage::parse(buffer.as_slice());
+        records.extend(log);
+
+        Ok(records)
+    }
+
+    fn parse(mut buffer: &[u8]) -> (Vec<Record>, bool) {
+        let mut records = Vec::new();
+
+        while !buffer.is_empty() {
+            if buffer.len() < FRAME_HEADER {
+                return (records, false);
+            }
+
+            let (header, rest) = buffer.split_at(FRAME_HEADER);
+            let length = u64::from_le_bytes(header.try_into().unwrap()) as usize;
+
+            if rest.len() < length {
+                return (records, false);
+            }
+
+            let (frame, rest) = rest.split_at(length);
+
+            match bincode::deserialize::<Record>(frame) {
+                Ok(record) => records.push(record),
+                Err(_) => return (records, false),
+            }
+
+            buffer = rest;
+        }
+
+        (records, true)
+    }
+
+    fn write<W>(writer: &mut W, records: &[Record]) -> io::Result<()>
+    where
+        W: Write,
+    {
+        for record in records {
+            // `Record`s contain only types that serialize infallibly
+            let frame = bincode::serialize(record).unwrap();
+
+            writer.write_all(&(frame.len() as u64).to_le_bytes())?;
+            writer.write_all(frame.as_slice())?;
+        }
+
+        writer.flush()
+    }
+
+    fn compact(&mut self, records: &[Record]) -> Result<(), Top<StorageError>> {
+        // Write `records` to a swap file, then atomically replace the snapshot with it
+
+        let swap_path = self.path.join(SNAPSHOT_SWAP);
+
+        {
+            let swap = File::create(&swap_path)
+                .map_err(StorageError::write_failed)
+                .map_err(Doom::into_top)
+                .spot(here!())?;
+
+            let mut writer = BufWriter::new(&swap);
+
+            LogStorage::write(&mut writer, records)
+                .map_err(StorageError::write_failed)
+                .map_err(Doom::into_top)
+                .spot(here!())?;
+
+            drop(writer);
+
+            swap.sync_all()
+                .map_err(StorageError::write_failed)
+                .map_err(Doom::into_top)
+                .spot(here!())?;
+        }
+
+        fs::rename(&swap_path, self.path.join(SNAPSHOT))
+            .map_err(StorageError::write_failed)
+            .map_err(Doom::into_top)
+            .spot(here!())?;
+
+        // Every `Record` in the log is now reflected in the snapshot
+
+        self.log
+            .set_len(0)
+            .map_err(StorageError::write_failed)
+            .map_err(Doom::into_top)
+            .spot(here!())?;
+
+        self.log
+            .sync_all()
+            .map_err(StorageError::write_failed)
+            .map_err(Doom::into_top)
+            .spot(here!())?;
+
+        self.logged = 0;
+
+        Ok(())
+    }
+}
+
+impl Storage for LogStorage {
+    fn load(&mut self) -> Result<Vec<Record>, Top<StorageError>> {
+        let records = Record::compact(self.read()?);
+
+        // Compacting upon loading truncates any partial `Record` off the log
+        self.compact(records.as_slice())?;
+
+        Ok(records)
+    }
+
+    fn append(&mut self, records: &[Record]) -> Result<(), Top<StorageError>> {
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        {
+            let mut writer = BufWriter::new(&self.log);
+
+            LogStorage::write(&mut writer, records)
+                .map_err(StorageError::write_failed)
+                .map_err(Doom::into_top)
+                .spot(here!())?;
+        }
+
+        self.log
+            .sync_data()
+            .map_err(StorageError::write_failed)
+            .map_err(Doom::into_top)
+            .spot(here!())?;
+
+        self.logged += records.len();
+
+        if self.logged >= self.settings.compaction_threshold {
+            let records = Record::compact(self.read()?);
+            self.compact(records.as_slice())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::account::{Account, AccountSettings, AccountSummary, Id};
+
+    use std::env;
+
+    fn account(id: Id, initial_balance: u64) -> Account {
+        let settings = AccountSettings {
+            initial_balance,
+            ..Default::default()
+        };
+
+        Account::new(id, &settings)
+    }
+
+    fn summaries(records: &[Record]) -> Vec<(Id, AccountSummary)> {
+        records
+            .iter()
+            .map(|record| match record {
+                Record::Account { id, account } => (*id, account.summarize()),
+                _ => unreachable!(),
+            })
+            .collect()
+    }
+
+    fn temp_path() -> PathBuf {
+        env::temp_dir().join(format!("carbon-log-storage-{}", rand::random::<u64>()))
+    }
+
+    #[test]
+    fn reopen() {
+        let path = temp_path();
+
+        let records = vec![
+            Record::Account {
+                id: 0,
+                account: account(0, 0),
+            },
+            Record::Account {
+                id: 1,
+                account: account(1, 0),
+            },
+            Record::Account {
+                id: 0,
+                account: account(0, 10),
+            },
+        ];
+
+        {
+            let mut storage = LogStorage::open(&path, Default::default()).unwrap();
+            assert!(storage.load().unwrap().is_empty());
+
+            storage.append(&records[..2]).unwrap();
+            storage.append(&records[2..]).unwrap();
+        }
+
+        {
+            let mut storage = LogStorage::open(&path, Default::default()).unwrap();
+            let loaded = storage.load().unwrap();
+
+            // The first `Record` for `0` is superseded by the third
+            assert_eq!(summaries(&loaded), summaries(&records[1..]));
+        }
+
+        fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[test]
+    fn compaction() {
+        let path = temp_path();
+
+        let settings = LogStorageSettings {
+            compaction_threshold: 4,
+        };
+
+        let records = (0..10)
+            .map(|balance| Record::Account {
+                id: balance % 3,
+                account: account(balance % 3, balance),
+            })
+            .collect::<Vec<_>>();
+
+        {
+            let mut storage = LogStorage::open(&path, settings.clone()).unwrap();
+
+            for record in records.iter() {
+                storage.append(&[record.clone()]).unwrap();
+            }
+
+            assert!(storage.logged < 4);
+        }
+
+        {
+            let mut storage = LogStorage::open(&path, settings).unwrap();
+            let loaded = storage.load().unwrap();
+
+            assert_eq!(summaries(&loaded), summaries(&records[7..]));
+        }
+
+        fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[test]
+    fn truncated_log() {
+        let path = temp_path();
+
+        let records = vec![
+            Record::Account {
+                id: 0,
+                account: account(0, 0),
+            },
+            Record::Account {
+                id: 1,
+                account: account(1, 0),
+            },
+        ];
+
+        {
+            let mut storage = LogStorage::open(&path, Default::default()).unwrap();
+            storage.append(&records).unwrap();
+        }
+
+        // Simulate a crash halfway through an `append`
+
+        {
+            let mut log = OpenOptions::new()
+                .append(true)
+                .open(path.join(LOG))
+                .unwrap();
+
+            log.write_all(&1024u64.to_le_bytes()).unwrap();
+            log.write_all(&[0; 12]).unwrap();
+        }
+
+        {
+            let mut storage = LogStorage::open(&path, Default::default()).unwrap();
+            let loaded = storage.load().unwrap();
+
+            assert_eq!(summaries(&loaded), summaries(&records));
+            assert_eq!(fs::metadata(path.join(LOG)).unwrap().len(), 0);
+        }
+
+        fs::remove_dir_all(&path).unwrap();
+    }
+}
@@ -0,0 +1,15 @@
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub(crate) struct LogStorageSettings {
+    pub compaction_threshold: usize,
+}
+
+impl Default for LogStorageSettings {
+    fn default() -> Self {
+        LogStorageSettings {
+            compaction_threshold: 65536,
+        }
+    }
+}
@@ -0,0 +1,13 @@
+mod journal;
+mod log_storage;
+mod log_storage_settings;
+mod record;
+mod storage;
+mod storage_error;
+
+pub(crate) use journal::Journal;
+pub(crate) use log_storage::LogStorage;
+pub(crate) use log_storage_settings::LogStorageSettings;
+pub(crate) use record::Record;
+pub(crate) use storage::Storage;
+pub(crate) use storage_error::StorageError;
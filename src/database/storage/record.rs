@@ -0,0 +1,119 @@
+use crate::{
+    account::{Account, Entry, Id},
+    commit::{BatchCompletion, WitnessedBatch as CommitWitnessedBatch},
+    database::{commit::PayloadHandle, prepare::State as PrepareState, Receipt},
+    prepare::{BatchCommit, WitnessedBatch as PrepareWitnessedBatch},
+    signup::{IdAssignment, IdClaim},
+};
+
+use serde::{Deserialize, Serialize};
+
+use std::collections::{HashMap, HashSet};
+
+use talk::crypto::{primitives::hash::Hash, Identity};
+
+// Each `Record` carries the latest value of one element of `Database`:
+// replaying a sequence of `Record`s in order onto an empty `Database`
+// reproduces the state from which the sequence was journaled.
+// `Record`s whose name ends in `Collect` mark the removal of an
+// element by garbage collection.
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) enum Record {
+    Allocation { identity: Identity, id: Id },
+    Claim(IdClaim),
+    Assignment(IdAssignment),
+    PrepareState { id: Id, state: PrepareState },
+    PrepareBatch(PrepareWitnessedBatch),
+    PrepareCommit(BatchCommit),
+    PrepareCollect(Hash),
+    CommitBatch(CommitWitnessedBatch),
+    CommitCollect(Hash),
+    Account { id: Id, account: Account },
+    Payload { entry: Entry, handle: PayloadHandle },
+    PayloadCollect(Entry),
+    Completion(BatchCompletion),
+    Receipt(Receipt),
+}
+
+#[derive(PartialEq, Eq, Hash)]
+enum RecordKey {
+    Allocation(Identity),
+    Claim(Id),
+    Assignment(Id),
+    PrepareState(Id),
+    PrepareBatch(Hash),
+    PrepareCommit(Hash),
+    CommitBatch(Hash),
+    Account(Id),
+    Payload(Entry),
+    Completion(Hash),
+    Receipt(Entry),
+}
+
+impl Record {
+    fn key(&self) -> RecordKey {
+        match self {
+            Record::Allocation { identity, .. } => RecordKey::Allocation(*identity),
+            Record::Claim(claim) => RecordKey::Claim(claim.id()),
+            Record::Assignment(assignment) => RecordKey::Assignment(assignment.id()),
+            Record::PrepareState { id, .. } => RecordKey::PrepareState(*id),
+            Record::PrepareBatch(batch) => RecordKey::PrepareBatch(batch.root()),
+            Record::PrepareCommit(commit) => RecordKey::PrepareCommit(commit.root()),
+            Record::PrepareCollect(root) => RecordKey::PrepareBatch(*root),
+            Record::CommitBatch(batch) => RecordKey::CommitBatch(batch.root()),
+            Record::CommitCollect(root) => RecordKey::CommitBatch(*root),
+            Record::Account { id, .. } => RecordKey::Account(*id),
+            Record::Payload { entry, .. } => RecordKey::Payload(*entry),
+            Record::PayloadCollect(entry) => RecordKey::Payload(*entry),
+            Record::Completion(completion) => RecordKey::Completion(completion.root()),
+            Record::Receipt(receipt) => RecordKey::Receipt(receipt.entry()),
+        }
+    }
+
+    /// Drops every `Record` that is superseded by a later `Record` for the same
+    /// element of `Database` (or by its collection), preserving the order of the
+    /// surviving `Record`s.
+    pub fn compact(records: Vec<Record>) -> Vec<Record> {
+        let mut latest = HashMap::with_capacity(records.len());
+
+        for (index, record) in records.into_iter().enumerate() {
+            latest.insert(record.key(), (index, record));
+        }
+
+        // Once compacted, a collected element leaves nothing behind: its `*Collect`
+        // `Record` is dropped, along with any `BatchCommit` or `BatchCompletion`
+        // attached to a collected batch
+
+        let prepare_batches = latest
+            .values()
+            .filter_map(|(_, record)| match record {
+                Record::PrepareBatch(batch) => Some(batch.root()),
+                _ => None,
+            })
+            .collect::<HashSet<_>>();
+
+        let commit_batches = latest
+            .values()
+            .filter_map(|(_, record)| match record {
+                Record::CommitBatch(batch) => Some(batch.root()),
+                _ => None,
+            })
+            .collect::<HashSet<_>>();
+
+        let mut records = latest
+            .into_iter()
+            .map(|(_, entry)| entry)
+            .filter(|(_, record)| match record {
+                Record::PrepareCollect(_)
+                | Record::CommitCollect(_)
+                | Record::PayloadCollect(_) => false,
+                Record::PrepareCommit(commit) => prepare_batches.contains(&commit.root()),
+                Record::Completion(completion) => commit_batches.contains(&completion.root()),
+                _ => true,
+            })
+            .collect::<Vec<_>>();
+        records.sort_unstable_by_key(|(index, _)| *index);
+
+        records.into_iter().map(|(_, record)| record).collect()
+    }
+}
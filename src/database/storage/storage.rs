@@ -0,0 +1,14 @@
+use crate::database::storage::{Record, StorageError};
+
+use doomstack::Top;
+
+/// A durable backend for the `Record`s journaled by a `Database`.
+pub(crate) trait Storage: Send {
+    /// Returns all `Record`s previously appended, in order. Superseded
+    /// `Record`s may have been compacted away.
+    fn load(&mut self) -> Result<Vec<Record>, Top<StorageError>>;
+
+    /// Durably appends `records`: upon returning `Ok`, `records`
+    /// must survive a crash of the local process.
+    fn append(&mut self, records: &[Record]) -> Result<(), Top<StorageError>>;
+}
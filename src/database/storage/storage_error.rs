@@ -0,0 +1,18 @@
+use doomstack::Doom;
+
+use std::io;
+
+#[derive(Doom)]
+pub(crate) enum StorageError {
+    #[doom(description("Failed to open storage: {}", source))]
+    #[doom(wrap(open_failed))]
+    OpenFailed { source: io::Error },
+    #[doom(description("Failed to read from storage: {}", source))]
+    #[doom(wrap(read_failed))]
+    ReadFailed { source: io::Error },
+    #[doom(description("Failed to write to storage: {}", source))]
+    #[doom(wrap(write_failed))]
+    WriteFailed { source: io::Error },
+    #[doom(description("Malformed record"))]
+    MalformedRecord,
+}
@@ -34,7 +34,7 @@ use zebra::database::{Collection, CollectionTransaction, Family};
 type TransitionInlet = Sender<Option<Transition>>;
 type TransitionOutlet = Receiver<Option<Transition>>;
 
-pub(crate) struct Client {
+pub struct Client {
     server: Box<dyn TcpConnect>,
     database: Arc<StdMutex<Database>>,
     transition_outlet: TokioMutex<TransitionOutlet>,
@@ -109,7 +109,7 @@ enum AcquireError {
 }
 
 impl Client {
-    pub(crate) fn new<T>(genesis: View, server: T, settings: ClientSettings) -> Self
+    pub fn new<T>(genesis: View, server: T, settings: ClientSettings) -> Self
     where
         T: 'static + Clone + TcpConnect,
     {
@@ -153,15 +153,15 @@ impl Client {
         }
     }
 
-    pub(crate) fn view(&self, identifier: &Hash) -> Option<View> {
+    pub fn view(&self, identifier: &Hash) -> Option<View> {
         self.database.lock().unwrap().views.get(identifier).cloned()
     }
 
-    pub(crate) fn install(&self, hash: &Hash) -> Option<Install> {
+    pub fn install(&self, hash: &Hash) -> Option<Install> {
         self.database.lock().unwrap().installs.get(hash).cloned()
     }
 
-    pub(crate) async fn next(&self) -> Transition {
+    pub async fn next(&self) -> Transition {
         let mut transition_outlet = self.transition_outlet.lock().await;
 
         // This cannot fail: the corresponding `transition_inlet` is
@@ -177,7 +177,7 @@ impl Client {
         transition
     }
 
-    pub(crate) async fn beyond(&self, height: usize) -> Transition {
+    pub async fn beyond(&self, height: usize) -> Transition {
         let mut transition_outlet = self.transition_outlet.lock().await;
 
         loop {
@@ -192,7 +192,7 @@ impl Client {
         }
     }
 
-    pub(crate) async fn publish(&self, install: Install) {
+    pub async fn publish(&self, install: Install) {
         let mut sleep_agent = self.settings.retry_schedule.agent();
 
         while self.publish_attempt(install.clone()).await.is_err() {
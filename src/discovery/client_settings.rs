@@ -5,7 +5,7 @@ use std::{sync::Arc, time::Duration};
 use talk::time::{sleep_schedules::CappedExponential, SleepSchedule};
 
 #[derive(Debug, Clone)]
-pub(crate) struct ClientSettings {
+pub struct ClientSettings {
     pub mode: Mode,
     pub keepalive_interval: Duration,
     pub retry_schedule: Arc<dyn SleepSchedule>,
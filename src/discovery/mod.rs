@@ -14,13 +14,8 @@ use frame::Frame;
 use request::Request;
 use response::Response;
 
-#[allow(unused_imports)]
-pub(crate) use client::Client;
-
-pub(crate) use client_settings::ClientSettings;
-pub(crate) use mode::Mode;
-
-#[allow(unused_imports)]
-pub(crate) use server::Server;
-
-pub(crate) use server_settings::ServerSettings;
+pub use client::Client;
+pub use client_settings::ClientSettings;
+pub use mode::Mode;
+pub use server::{Server, ServerError};
+pub use server_settings::ServerSettings;
@@ -1,5 +1,5 @@
 #[derive(Debug, Clone, Copy)]
-pub(crate) enum Mode {
+pub enum Mode {
     Light,
     Full,
 }
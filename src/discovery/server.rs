@@ -36,7 +36,7 @@ type InstallOutlet = BroadcastReceiver<Install>;
 type FrameInlet = WatchSender<Arc<Frame>>;
 type FrameOutlet = WatchReceiver<Arc<Frame>>;
 
-pub(crate) struct Server {
+pub struct Server {
     address: SocketAddr,
     _fuse: Fuse,
 }
@@ -58,7 +58,7 @@ struct Publish {
 }
 
 #[derive(Doom)]
-pub(crate) enum ServerError {
+pub enum ServerError {
     #[doom(description("Failed to initialize server: {}", source))]
     #[doom(wrap(initialize_failed))]
     InitializeFailed { source: io::Error },
@@ -143,7 +143,7 @@ impl Server {
         })
     }
 
-    pub(crate) fn address(&self) -> SocketAddr {
+    pub fn address(&self) -> SocketAddr {
         self.address
     }
 
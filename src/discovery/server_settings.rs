@@ -1,5 +1,8 @@
-#[derive(Debug, Clone)]
-pub(crate) struct ServerSettings {
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ServerSettings {
     pub install_channel_capacity: usize,
     pub update_channel_capacity: usize,
 }
@@ -1,14 +1,20 @@
+//! Only the modules and types needed to operate accounts, discover `View`s and
+//! run nodes are public. Applications should import them through [`prelude`].
+
 #[allow(dead_code)]
-mod account;
+pub mod account;
 
 #[allow(dead_code)]
 mod brokers;
 
 #[allow(dead_code)]
-mod commit;
+pub mod client;
+
+#[allow(dead_code)]
+pub mod commit;
 
 #[allow(dead_code)]
-mod crypto;
+pub mod crypto;
 
 #[allow(dead_code)]
 mod data;
@@ -17,11 +23,18 @@ mod data;
 mod database;
 
 #[allow(dead_code)]
-mod discovery;
+pub mod discovery;
 
 #[allow(dead_code)]
 mod lattice;
 
+#[allow(dead_code)]
+mod motion;
+
+pub mod node;
+
+pub mod prelude;
+
 #[allow(dead_code)]
 mod prepare;
 
@@ -29,10 +42,19 @@ mod prepare;
 mod processing;
 
 #[allow(dead_code)]
-mod signup;
+mod query;
+
+#[allow(dead_code)]
+mod replica;
+
+#[allow(dead_code)]
+pub mod signup;
+
+#[allow(dead_code)]
+mod transport;
 
 #[allow(dead_code)]
-mod view;
+pub mod view;
 
 #[allow(dead_code)]
 mod view_generator;
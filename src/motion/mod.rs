@@ -0,0 +1,9 @@
+mod pass;
+mod pass_shard;
+mod pass_statement;
+
+#[allow(unused_imports)]
+pub(crate) use pass::{Pass, PassAggregator, PassError};
+
+pub(crate) use pass_shard::PassShard;
+pub(crate) use pass_statement::PassStatement;
@@ -0,0 +1,118 @@
+use crate::{
+    crypto::{Aggregator, Certificate, Identify},
+    discovery::Client,
+    motion::{PassShard, PassStatement},
+    view::View,
+};
+
+use doomstack::{here, Doom, ResultExt, Top};
+
+use serde::{Deserialize, Serialize};
+
+use talk::crypto::{primitives::hash::Hash, KeyCard};
+
+/// Proof that a motion passed in some `View`: a plurality of the `View`'s
+/// members (hence at least one correct member) observed the motion's
+/// support reach the pass threshold.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Pass {
+    view: Hash,
+    motion: Hash,
+    certificate: Certificate,
+}
+
+pub(crate) struct PassAggregator(Aggregator<PassStatement>);
+
+#[derive(Doom)]
+pub(crate) enum PassError {
+    #[doom(description("`View` unknown"))]
+    ViewUnknown,
+    #[doom(description("`Certificate` invalid"))]
+    CertificateInvalid,
+}
+
+impl Pass {
+    pub fn view(&self) -> Hash {
+        self.view
+    }
+
+    pub fn motion(&self) -> Hash {
+        self.motion
+    }
+
+    pub fn validate(&self, discovery: &Client) -> Result<(), Top<PassError>> {
+        let view = discovery
+            .view(&self.view)
+            .ok_or(PassError::ViewUnknown.into_top())
+            .spot(here!())?;
+
+        let statement = PassStatement::new(self.view, self.motion);
+
+        self.certificate
+            .verify_plurality(&view, &statement)
+            .pot(PassError::CertificateInvalid, here!())
+    }
+}
+
+impl PassAggregator {
+    pub fn new(view: View, motion: Hash) -> Self {
+        let statement = PassStatement::new(view.identifier(), motion);
+        PassAggregator(Aggregator::new(view, statement))
+    }
+
+    pub fn add(&mut self, passer: &KeyCard, shard: PassShard) {
+        // Assuming that `shard` is valid, `shard.signature()` is valid
+        self.0.add(passer, shard.signature()).unwrap();
+    }
+
+    pub fn complete(&self) -> bool {
+        self.0.multiplicity() >= self.0.view().plurality()
+    }
+
+    pub fn finalize(self) -> Pass {
+        let view = self.0.view().identifier();
+        let (statement, certificate) = self.0.finalize_plurality();
+
+        Pass {
+            view,
+            motion: statement.motion(),
+            certificate,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::discovery::{self, Mode};
+
+    use talk::crypto::primitives::hash;
+
+    #[tokio::test]
+    async fn plurality() {
+        let (install_generator, _discovery_server, _, mut discovery_clients, _) =
+            discovery::test::setup(4, 4, Mode::Full).await;
+
+        let view = install_generator.view(4);
+        let motion = hash::hash(&42u64).unwrap();
+
+        let mut aggregator = PassAggregator::new(view.clone(), motion);
+
+        for keychain in install_generator.keychains.iter().take(view.plurality()) {
+            assert!(!aggregator.complete());
+
+            let shard = PassShard::new(keychain, view.identifier(), motion);
+            shard.validate(&view, motion, &keychain.keycard()).unwrap();
+
+            aggregator.add(&keychain.keycard(), shard);
+        }
+
+        assert!(aggregator.complete());
+
+        let pass = aggregator.finalize();
+        assert_eq!(pass.motion(), motion);
+
+        pass.validate(&discovery_clients.next().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,49 @@
+use crate::{crypto::Identify, motion::PassStatement, view::View};
+
+use doomstack::{here, Doom, ResultExt, Top};
+
+use serde::{Deserialize, Serialize};
+
+use talk::crypto::{
+    primitives::{hash::Hash, multi::Signature as MultiSignature},
+    KeyCard, KeyChain,
+};
+
+/// A single replica's attestation that, in its `Database`, the number of
+/// (correct) accounts supporting a motion reached the pass threshold.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct PassShard {
+    signature: MultiSignature,
+}
+
+#[derive(Doom)]
+pub(crate) enum PassShardError {
+    #[doom(description("`Signature` invalid"))]
+    SignatureInvalid,
+}
+
+impl PassShard {
+    pub fn new(keychain: &KeyChain, view: Hash, motion: Hash) -> Self {
+        let statement = PassStatement::new(view, motion);
+        let signature = keychain.multisign(&statement).unwrap();
+
+        PassShard { signature }
+    }
+
+    pub fn signature(&self) -> MultiSignature {
+        self.signature.clone()
+    }
+
+    pub fn validate(
+        &self,
+        view: &View,
+        motion: Hash,
+        passer: &KeyCard,
+    ) -> Result<(), Top<PassShardError>> {
+        let statement = PassStatement::new(view.identifier(), motion);
+
+        self.signature
+            .verify([passer], &statement)
+            .pot(PassShardError::SignatureInvalid, here!())
+    }
+}
@@ -0,0 +1,26 @@
+use crate::crypto::Header;
+
+use serde::Serialize;
+
+use talk::crypto::{primitives::hash::Hash, Statement};
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct PassStatement {
+    view: Hash,
+    motion: Hash,
+}
+
+impl PassStatement {
+    pub fn new(view: Hash, motion: Hash) -> Self {
+        PassStatement { view, motion }
+    }
+
+    pub fn motion(&self) -> Hash {
+        self.motion
+    }
+}
+
+impl Statement for PassStatement {
+    type Header = Header;
+    const HEADER: Header = Header::Pass;
+}
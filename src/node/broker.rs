@@ -0,0 +1,130 @@
+use crate::{
+    brokers::{commit, pipeline, prepare, signup},
+    discovery::Client,
+    node::{
+        config::{self, BrokerConfig},
+        network, shutdown, NodeError,
+    },
+};
+
+use doomstack::{here, Doom, ResultExt, Top};
+
+use std::{path::Path, str::FromStr, sync::Arc};
+
+use talk::crypto::KeyChain;
+
+/// Selects which broker `run_broker` runs (a `Pipeline` broker
+/// does the work of both a `Prepare` and a `Commit` broker).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BrokerKind {
+    Signup,
+    Prepare,
+    Commit,
+    Pipeline,
+}
+
+/// Runs a broker of kind `kind` configured by the (TOML) file at `path`,
+/// until a shutdown signal is received.
+pub async fn run_broker(kind: BrokerKind, path: &Path) -> Result<(), Top<NodeError>> {
+    let BrokerConfig {
+        keychain,
+        address,
+        network,
+        signup,
+        prepare,
+        commit,
+        pipeline,
+    } = config::load(path)?;
+
+    let keychain = config::read::<KeyChain>(&keychain)?;
+    let genesis = network.genesis()?;
+
+    let connector = network::connector(&keychain, network.rendezvous, &network.settings);
+
+    // Brokers are dropped (hence stopped) when `run_broker` returns
+    match kind {
+        BrokerKind::Signup => {
+            let _broker = signup::Broker::new(keychain, genesis, address, connector, signup)
+                .await
+                .pot(NodeError::BrokerFailed, here!())?;
+
+            shutdown::requested().await
+        }
+        BrokerKind::Prepare => {
+            let discovery = Arc::new(Client::new(
+                genesis.clone(),
+                network.discovery,
+                Default::default(),
+            ));
+
+            let _broker =
+                prepare::Broker::new(keychain, discovery, genesis, address, connector, prepare)
+                    .await
+                    .pot(NodeError::BrokerFailed, here!())?;
+
+            shutdown::requested().await
+        }
+        BrokerKind::Commit => {
+            let discovery = Arc::new(Client::new(
+                genesis.clone(),
+                network.discovery,
+                Default::default(),
+            ));
+
+            let _broker =
+                commit::Broker::new(keychain, discovery, genesis, address, connector, commit)
+                    .await
+                    .pot(NodeError::BrokerFailed, here!())?;
+
+            shutdown::requested().await
+        }
+        BrokerKind::Pipeline => {
+            let discovery = Arc::new(Client::new(
+                genesis.clone(),
+                network.discovery,
+                Default::default(),
+            ));
+
+            let _broker =
+                pipeline::Broker::new(keychain, discovery, genesis, address, connector, pipeline)
+                    .await
+                    .pot(NodeError::BrokerFailed, here!())?;
+
+            shutdown::requested().await
+        }
+    }
+}
+
+impl FromStr for BrokerKind {
+    type Err = Top<NodeError>;
+
+    fn from_str(kind: &str) -> Result<Self, Self::Err> {
+        match kind {
+            "signup" => Ok(BrokerKind::Signup),
+            "prepare" => Ok(BrokerKind::Prepare),
+            "commit" => Ok(BrokerKind::Commit),
+            "pipeline" => Ok(BrokerKind::Pipeline),
+            _ => NodeError::BrokerKindUnknown.fail().spot(here!()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kinds() {
+        assert_eq!("signup".parse::<BrokerKind>().unwrap(), BrokerKind::Signup);
+        assert_eq!(
+            "prepare".parse::<BrokerKind>().unwrap(),
+            BrokerKind::Prepare
+        );
+        assert_eq!("commit".parse::<BrokerKind>().unwrap(), BrokerKind::Commit);
+        assert_eq!(
+            "pipeline".parse::<BrokerKind>().unwrap(),
+            BrokerKind::Pipeline
+        );
+        assert!("witness".parse::<BrokerKind>().is_err());
+    }
+}
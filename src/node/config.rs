@@ -0,0 +1,127 @@
+use crate::{
+    brokers::{
+        commit::BrokerSettings as CommitBrokerSettings,
+        pipeline::BrokerSettings as PipelineBrokerSettings,
+        prepare::BrokerSettings as PrepareBrokerSettings,
+        signup::BrokerSettings as SignupBrokerSettings,
+    },
+    database::storage::LogStorageSettings,
+    discovery::ServerSettings,
+    node::{network::NetworkSettings, NodeError},
+    replica::ReplicaSettings,
+    view::View,
+};
+
+use doomstack::{here, Doom, ResultExt, Top};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use std::{
+    fs,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+};
+
+use talk::crypto::KeyCard;
+
+// Configuration shared by all nodes connecting to the genesis members
+#[derive(Deserialize)]
+pub(in crate::node) struct Network {
+    // File containing the genesis members' `KeyCard`s (see `generate_keys`)
+    pub genesis: PathBuf,
+    // Both hosted by the discovery server (see `DiscoveryConfig`)
+    pub rendezvous: SocketAddr,
+    pub discovery: SocketAddr,
+    #[serde(default)]
+    pub settings: NetworkSettings,
+}
+
+#[derive(Deserialize)]
+pub(in crate::node) struct ReplicaConfig {
+    pub keychain: PathBuf,
+    // If `None`, the replica's `Database` is volatile
+    pub database: Option<PathBuf>,
+    pub network: Network,
+    #[serde(default)]
+    pub storage: LogStorageSettings,
+    #[serde(default)]
+    pub settings: ReplicaSettings,
+}
+
+#[derive(Deserialize)]
+pub(in crate::node) struct BrokerConfig {
+    pub keychain: PathBuf,
+    pub address: SocketAddr,
+    pub network: Network,
+    #[serde(default)]
+    pub signup: SignupBrokerSettings,
+    #[serde(default)]
+    pub prepare: PrepareBrokerSettings,
+    #[serde(default)]
+    pub commit: CommitBrokerSettings,
+    #[serde(default)]
+    pub pipeline: PipelineBrokerSettings,
+}
+
+#[derive(Deserialize)]
+pub(in crate::node) struct DiscoveryConfig {
+    pub genesis: PathBuf,
+    pub address: SocketAddr,
+    pub rendezvous: SocketAddr,
+    #[serde(default)]
+    pub settings: ServerSettings,
+    #[serde(default)]
+    pub network: NetworkSettings,
+}
+
+impl Network {
+    pub fn genesis(&self) -> Result<View, Top<NodeError>> {
+        let members = read::<Vec<KeyCard>>(&self.genesis)?;
+        Ok(View::genesis(members))
+    }
+}
+
+// Configuration files are TOML-encoded
+pub(in crate::node) fn load<C>(path: &Path) -> Result<C, Top<NodeError>>
+where
+    C: DeserializeOwned,
+{
+    let config = fs::read_to_string(path)
+        .map_err(NodeError::config_unreadable)
+        .map_err(Doom::into_top)
+        .spot(here!())?;
+
+    toml::from_str(&config)
+        .map_err(NodeError::config_malformed)
+        .map_err(Doom::into_top)
+        .spot(here!())
+}
+
+// Key material is `bincode`-encoded
+pub(in crate::node) fn read<T>(path: &Path) -> Result<T, Top<NodeError>>
+where
+    T: DeserializeOwned,
+{
+    let bytes = fs::read(path)
+        .map_err(NodeError::file_unreadable)
+        .map_err(Doom::into_top)
+        .spot(here!())?;
+
+    bincode::deserialize(&bytes)
+        .map_err(NodeError::file_malformed)
+        .map_err(Doom::into_top)
+        .spot(here!())
+}
+
+pub(in crate::node) fn write<T>(path: &Path, value: &T) -> Result<(), Top<NodeError>>
+where
+    T: Serialize,
+{
+    // Serializing key material to memory cannot fail
+    let bytes = bincode::serialize(value).unwrap();
+
+    fs::write(path, bytes)
+        .map_err(NodeError::file_unwritable)
+        .map_err(Doom::into_top)
+        .spot(here!())
+}
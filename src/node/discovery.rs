@@ -0,0 +1,37 @@
+use crate::{
+    discovery::Server,
+    node::{
+        config::{self, DiscoveryConfig},
+        network, shutdown, NodeError,
+    },
+    view::View,
+};
+
+use doomstack::{here, ResultExt, Top};
+
+use std::path::Path;
+
+use talk::crypto::KeyCard;
+
+/// Runs a discovery server (along with the rendezvous server at which nodes
+/// publish their addresses) configured by the (TOML) file at `path`,
+/// until a shutdown signal is received.
+pub async fn run_discovery(path: &Path) -> Result<(), Top<NodeError>> {
+    let DiscoveryConfig {
+        genesis,
+        address,
+        rendezvous,
+        settings,
+        network,
+    } = config::load(path)?;
+
+    let genesis = View::genesis(config::read::<Vec<KeyCard>>(&genesis)?);
+
+    let _server = Server::new(genesis, address, settings)
+        .await
+        .pot(NodeError::DiscoveryFailed, here!())?;
+
+    let _rendezvous = network::rendezvous(rendezvous, &network).await?;
+
+    shutdown::requested().await
+}
@@ -0,0 +1,37 @@
+use doomstack::Doom;
+
+use std::io;
+
+#[derive(Doom)]
+pub enum NodeError {
+    #[doom(description("Failed to read configuration file: {}", source))]
+    #[doom(wrap(config_unreadable))]
+    ConfigUnreadable { source: io::Error },
+    #[doom(description("Malformed configuration file: {}", source))]
+    #[doom(wrap(config_malformed))]
+    ConfigMalformed { source: toml::de::Error },
+    #[doom(description("Failed to read file: {}", source))]
+    #[doom(wrap(file_unreadable))]
+    FileUnreadable { source: io::Error },
+    #[doom(description("Failed to write file: {}", source))]
+    #[doom(wrap(file_unwritable))]
+    FileUnwritable { source: io::Error },
+    #[doom(description("Malformed file: {}", source))]
+    #[doom(wrap(file_malformed))]
+    FileMalformed { source: bincode::Error },
+    #[doom(description(
+        "Unknown broker kind (expected `signup`, `prepare`, `commit` or `pipeline`)"
+    ))]
+    BrokerKindUnknown,
+    #[doom(description("Database unavailable"))]
+    DatabaseUnavailable,
+    #[doom(description("Failed to start broker"))]
+    BrokerFailed,
+    #[doom(description("Failed to start discovery server"))]
+    DiscoveryFailed,
+    #[doom(description("Failed to start rendezvous server"))]
+    RendezvousFailed,
+    #[doom(description("Failed to await shutdown signal: {}", source))]
+    #[doom(wrap(signal_failed))]
+    SignalFailed { source: io::Error },
+}
@@ -0,0 +1,24 @@
+use crate::node::{config, NodeError};
+
+use doomstack::Top;
+
+use std::path::Path;
+
+use talk::crypto::{KeyCard, KeyChain};
+
+/// Generates `count` random `KeyChain`s, writing each to `keychain-<index>.bin`
+/// in `directory`, and the corresponding genesis `KeyCard`s to `genesis.bin`.
+pub fn generate_keys(count: usize, directory: &Path) -> Result<(), Top<NodeError>> {
+    let keychains = (0..count).map(|_| KeyChain::random()).collect::<Vec<_>>();
+
+    for (index, keychain) in keychains.iter().enumerate() {
+        config::write(&directory.join(format!("keychain-{}.bin", index)), keychain)?;
+    }
+
+    let genesis = keychains
+        .iter()
+        .map(KeyChain::keycard)
+        .collect::<Vec<KeyCard>>();
+
+    config::write(&directory.join("genesis.bin"), &genesis)
+}
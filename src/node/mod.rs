@@ -0,0 +1,16 @@
+//! Entry points for the `carbon-*` executables (see `src/bin`).
+
+mod broker;
+mod config;
+mod discovery;
+mod errors;
+mod keys;
+mod network;
+mod replica;
+mod shutdown;
+
+pub use broker::{run_broker, BrokerKind};
+pub use discovery::run_discovery;
+pub use errors::NodeError;
+pub use keys::generate_keys;
+pub use replica::run_replica;
@@ -0,0 +1,62 @@
+use crate::node::NodeError;
+
+use doomstack::{here, ResultExt, Top};
+
+use serde::Deserialize;
+
+use std::net::SocketAddr;
+
+use talk::{
+    crypto::KeyChain,
+    link::rendezvous::{
+        Connector, ConnectorSettings, Listener, ListenerSettings, Server, ServerSettings,
+    },
+};
+
+// Nodes publish (and look up) the addresses they listen on at the
+// rendezvous server hosted by the discovery server (see `run_discovery`)
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub(in crate::node) struct NetworkSettings {
+    #[serde(skip)]
+    pub connector_settings: ConnectorSettings,
+    #[serde(skip)]
+    pub listener_settings: ListenerSettings,
+    #[serde(skip)]
+    pub server_settings: ServerSettings,
+}
+
+pub(in crate::node) fn connector(
+    keychain: &KeyChain,
+    rendezvous: SocketAddr,
+    settings: &NetworkSettings,
+) -> Connector {
+    Connector::new(
+        rendezvous,
+        keychain.clone(),
+        settings.connector_settings.clone(),
+    )
+}
+
+pub(in crate::node) async fn listener(
+    keychain: &KeyChain,
+    rendezvous: SocketAddr,
+    settings: &NetworkSettings,
+) -> Listener {
+    Listener::new(
+        rendezvous,
+        keychain.clone(),
+        settings.listener_settings.clone(),
+    )
+    .await
+}
+
+pub(in crate::node) async fn rendezvous(
+    address: SocketAddr,
+    settings: &NetworkSettings,
+) -> Result<Server, Top<NodeError>> {
+    Server::new(address, settings.server_settings.clone())
+        .await
+        .pot(NodeError::RendezvousFailed, here!())
+}
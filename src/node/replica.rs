@@ -0,0 +1,233 @@
+use crate::{
+    database::{storage::LogStorage, Database},
+    discovery::Client,
+    node::{
+        config::{self, ReplicaConfig},
+        network, shutdown, NodeError,
+    },
+    replica::Replica,
+};
+
+use doomstack::{here, ResultExt, Top};
+
+use std::{path::Path, sync::Arc};
+
+use talk::crypto::KeyChain;
+
+use tokio::task;
+
+/// Runs a replica configured by the (TOML) file at `path`, until a shutdown
+/// signal is received. Upon shutdown, the replica's `Database` is flushed.
+pub async fn run_replica(path: &Path) -> Result<(), Top<NodeError>> {
+    let ReplicaConfig {
+        keychain,
+        database,
+        network,
+        storage,
+        settings,
+    } = config::load(path)?;
+
+    let keychain = config::read::<KeyChain>(&keychain)?;
+    let genesis = network.genesis()?;
+
+    // Loading `Storage` blocks on disk, and is run off the async runtime
+    let database = match database {
+        Some(path) => {
+            task::spawn_blocking(move || LogStorage::open(path, storage).and_then(Database::open))
+                .await
+                .unwrap()
+                .pot(NodeError::DatabaseUnavailable, here!())?
+        }
+        None => Database::new(),
+    };
+
+    let discovery = Arc::new(Client::new(
+        genesis.clone(),
+        network.discovery,
+        Default::default(),
+    ));
+
+    let connector = network::connector(&keychain, network.rendezvous, &network.settings);
+    let listener = network::listener(&keychain, network.rendezvous, &network.settings).await;
+
+    let replica = Replica::new(
+        keychain, genesis, discovery, database, connector, listener, settings,
+    );
+
+    shutdown::requested().await?;
+
+    let mut database = replica.shutdown().await;
+
+    database
+        .journal
+        .flush()
+        .await
+        .pot(NodeError::DatabaseUnavailable, here!())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        brokers::signup::BrokerFailure,
+        discovery::Client,
+        node::{self, BrokerKind},
+        signup::{IdAssignment, IdRequest, SignupSettings},
+        view::View,
+    };
+
+    use std::{
+        env, fs,
+        net::{SocketAddr, TcpListener as StdTcpListener},
+        path::Path,
+        time::Duration,
+    };
+
+    use talk::{
+        crypto::{KeyCard, KeyChain},
+        net::PlainConnection,
+    };
+
+    use tokio::{net::TcpStream, time};
+
+    fn free_address() -> SocketAddr {
+        StdTcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+    }
+
+    fn network(directory: &Path, rendezvous: SocketAddr, discovery: SocketAddr) -> String {
+        format!(
+            "[network]\ngenesis = {:?}\nrendezvous = \"{}\"\ndiscovery = \"{}\"\n",
+            directory.join("genesis.bin"),
+            rendezvous,
+            discovery
+        )
+    }
+
+    // Boots a discovery server, four replicas and a signup broker from configuration
+    // files (as the `carbon-*` executables do), then signs up a client
+    #[tokio::test(flavor = "multi_thread")]
+    async fn testnet() {
+        let directory = env::temp_dir().join(format!("carbon-testnet-{}", rand::random::<u64>()));
+        let brokers = directory.join("broker");
+
+        fs::create_dir_all(&brokers).unwrap();
+
+        node::generate_keys(4, &directory).unwrap();
+        node::generate_keys(1, &brokers).unwrap();
+
+        let discovery = free_address();
+        let rendezvous = free_address();
+        let broker = free_address();
+
+        fs::write(
+            directory.join("discovery.toml"),
+            format!(
+                "genesis = {:?}\naddress = \"{}\"\nrendezvous = \"{}\"\n",
+                directory.join("genesis.bin"),
+                discovery,
+                rendezvous
+            ),
+        )
+        .unwrap();
+
+        for index in 0..4 {
+            fs::write(
+                directory.join(format!("replica-{}.toml", index)),
+                format!(
+                    "keychain = {:?}\n{}",
+                    directory.join(format!("keychain-{}.bin", index)),
+                    network(&directory, rendezvous, discovery)
+                ),
+            )
+            .unwrap();
+        }
+
+        fs::write(
+            directory.join("broker.toml"),
+            format!(
+                "keychain = {:?}\naddress = \"{}\"\n{}",
+                brokers.join("keychain-0.bin"),
+                broker,
+                network(&directory, rendezvous, discovery)
+            ),
+        )
+        .unwrap();
+
+        let mut nodes = Vec::new();
+
+        {
+            let path = directory.join("discovery.toml");
+            nodes.push(tokio::spawn(
+                async move { node::run_discovery(&path).await },
+            ));
+        }
+
+        time::sleep(Duration::from_millis(500)).await;
+
+        for index in 0..4 {
+            let path = directory.join(format!("replica-{}.toml", index));
+            nodes.push(tokio::spawn(async move { node::run_replica(&path).await }));
+        }
+
+        {
+            let path = directory.join("broker.toml");
+            nodes.push(tokio::spawn(async move {
+                node::run_broker(BrokerKind::Signup, &path).await
+            }));
+        }
+
+        let genesis = View::genesis(
+            node::config::read::<Vec<KeyCard>>(&directory.join("genesis.bin")).unwrap(),
+        );
+
+        let allocator = node::config::read::<KeyChain>(&directory.join("keychain-0.bin"))
+            .unwrap()
+            .keycard()
+            .identity();
+
+        let client = KeyChain::random();
+
+        let request = IdRequest::new(
+            &client,
+            &genesis,
+            allocator,
+            SignupSettings::default().work_difficulty,
+        );
+
+        // Nodes might still be booting: attempts are repeated until one succeeds
+        let assignment = loop {
+            time::sleep(Duration::from_millis(500)).await;
+
+            let stream = match TcpStream::connect(broker).await {
+                Ok(stream) => stream,
+                Err(_) => continue,
+            };
+
+            let mut connection: PlainConnection = stream.into();
+
+            if connection.send(&request).await.is_err() {
+                continue;
+            }
+
+            if let Ok(Ok(assignment)) = connection
+                .receive::<Result<IdAssignment, BrokerFailure>>()
+                .await
+            {
+                break assignment;
+            }
+        };
+
+        let discovery = Client::new(genesis, discovery, Default::default());
+
+        assignment.validate(&discovery).unwrap();
+        assert_eq!(*assignment.keycard(), client.keycard());
+
+        for node in nodes {
+            node.abort();
+        }
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
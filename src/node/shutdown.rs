@@ -0,0 +1,14 @@
+use crate::node::NodeError;
+
+use doomstack::{here, Doom, ResultExt, Top};
+
+use tokio::signal;
+
+// Resolves when the process is asked to terminate (Ctrl-C / SIGINT)
+pub(in crate::node) async fn requested() -> Result<(), Top<NodeError>> {
+    signal::ctrl_c()
+        .await
+        .map_err(NodeError::signal_failed)
+        .map_err(Doom::into_top)
+        .spot(here!())
+}
@@ -0,0 +1,19 @@
+//! Re-exports the types needed to operate accounts against a running system.
+//!
+//! ```ignore
+//! use carbon::prelude::*;
+//! ```
+
+pub use crate::{
+    account::{
+        operations::{Abandon, Deposit, Rotate, Support, Transfer, Withdraw},
+        Corruption, Entry, Id, Operation, OperationError,
+    },
+    client::{Client, ClientError, ClientSettings, DepositTracker, DepositTrackerError},
+    commit::{Completion, CompletionProofError, Payload},
+    crypto::Identify,
+    discovery::{Client as DiscoveryClient, ClientSettings as DiscoveryClientSettings},
+    signup::{IdAssignment, IdAssignmentError, KeyRotation, KeyRotationError, SignupSettings},
+    transport::Transport,
+    view::{Change, Install, Transition, View},
+};
@@ -2,7 +2,7 @@ use crate::{
     account::Id,
     crypto::Identify,
     discovery::Client,
-    prepare::{BatchCommitStatement, Equivocation, Prepare},
+    prepare::{BatchCommitStatement, Continuity, Exception, Prepare},
     view::View,
 };
 
@@ -19,7 +19,7 @@ use talk::crypto::{
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct BatchCommitShard {
-    exceptions: HashMap<Id, Equivocation>,
+    exceptions: HashMap<Id, Exception>,
     signature: MultiSignature,
 }
 
@@ -27,10 +27,8 @@ pub(crate) struct BatchCommitShard {
 pub(crate) enum BatchCommitShardError {
     #[doom(description("Foreign exception"))]
     ForeignException,
-    #[doom(description("Mismatched id"))]
-    MismatchedId,
-    #[doom(description("Extract invalid"))]
-    EquivocationInvalid,
+    #[doom(description("Exception invalid"))]
+    ExceptionInvalid,
     #[doom(description("Signature invalid"))]
     SignatureInvalid,
 }
@@ -38,11 +36,11 @@ pub(crate) enum BatchCommitShardError {
 impl BatchCommitShard {
     pub fn new<E>(keychain: &KeyChain, view: Hash, root: Hash, exceptions: E) -> Self
     where
-        E: IntoIterator<Item = Equivocation>,
+        E: IntoIterator<Item = Exception>,
     {
         let exceptions = exceptions
             .into_iter()
-            .map(|equivocation| (equivocation.id(), equivocation))
+            .map(|exception| (exception.id(), exception))
             .collect::<HashMap<_, _>>();
 
         let statement = BatchCommitStatement::new(view, root, exceptions.keys().copied().collect());
@@ -68,23 +66,31 @@ impl BatchCommitShard {
         view: &View,
         root: Hash,
         prepares: &[Prepare],
+        continuities: &[Option<Continuity>],
+        queried: &[Id],
         committer: &KeyCard,
     ) -> Result<(), Top<BatchCommitShardError>> {
-        for (id, equivocation) in self.exceptions.iter() {
+        // `continuities` is aligned with `prepares`, `queried` lists the `Id`s whose
+        // `Continuity` was queried by the replica that produced `self`
+        for (id, exception) in self.exceptions.iter() {
             // Assuming that `prepares` was generated locally, it is is sorted by `Id`,
             // and can therefore be searched using `binary_search*`
-            prepares
+            let index = prepares
                 .binary_search_by_key(id, Prepare::id)
                 .map_err(|_| BatchCommitShardError::ForeignException.into_top())
                 .spot(here!())?;
 
-            if equivocation.id() != *id {
-                return BatchCommitShardError::MismatchedId.fail().spot(here!());
-            }
-
-            equivocation
-                .validate(discovery)
-                .pot(BatchCommitShardError::EquivocationInvalid, here!())?;
+            // A replica can only except as discontinuous (resp., premature) a `Prepare`
+            // whose `Continuity` it queried, and was not provided (resp., was not a
+            // `Completion`)
+            exception
+                .validate(
+                    discovery,
+                    &prepares[index],
+                    queried.contains(id),
+                    continuities[index].as_ref(),
+                )
+                .pot(BatchCommitShardError::ExceptionInvalid, here!())?;
         }
 
         let exceptions = self.exceptions.keys().copied().collect();
@@ -0,0 +1,55 @@
+use crate::{
+    account::Entry,
+    commit::{Commit, Completion},
+    discovery::Client,
+    prepare::Prepare,
+};
+
+use doomstack::{here, Doom, ResultExt, Top};
+
+use serde::{Deserialize, Serialize};
+
+/// Proves that a client reached `entry()`, i.e., that its `Prepare` at
+/// `entry()` was committed (and, in the case of a `Completion`, that the
+/// corresponding `Payload` was processed).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) enum Continuity {
+    Commit(Commit),
+    Completion(Completion),
+}
+
+#[derive(Doom)]
+pub(crate) enum ContinuityError {
+    #[doom(description("`Commit` invalid"))]
+    CommitInvalid,
+    #[doom(description("`Completion` invalid"))]
+    CompletionInvalid,
+}
+
+impl Continuity {
+    pub fn entry(&self) -> Entry {
+        match self {
+            Continuity::Commit(commit) => commit.payload().entry(),
+            Continuity::Completion(completion) => completion.entry(),
+        }
+    }
+
+    /// Returns the `Prepare` committed at `self.entry()`.
+    pub fn prepare(&self) -> Prepare {
+        match self {
+            Continuity::Commit(commit) => commit.payload().prepare(),
+            Continuity::Completion(completion) => completion.payload().prepare(),
+        }
+    }
+
+    pub fn validate(&self, discovery: &Client) -> Result<(), Top<ContinuityError>> {
+        match self {
+            Continuity::Commit(commit) => commit
+                .validate(discovery)
+                .pot(ContinuityError::CommitInvalid, here!()),
+            Continuity::Completion(completion) => completion
+                .validate(discovery)
+                .pot(ContinuityError::CompletionInvalid, here!()),
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use crate::{
+    account::Id,
+    commit::CommitProof,
+    discovery::Client,
+    prepare::{Continuity, Equivocation, Prepare},
+};
+
+use doomstack::{here, Doom, ResultExt, Top};
+
+use serde::{Deserialize, Serialize};
+
+/// Explains why a `Prepare` was excepted from a `BatchCommitShard`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) enum Exception {
+    // Two conflicting `Prepare`s were witnessed at the same height
+    Equivocation(Equivocation),
+    // A `Prepare` was committed at a higher height (as proven by its `CommitProof`):
+    // the excepted `Prepare` is stale (the client either regressed or is delayed)
+    Stale(Prepare, CommitProof),
+    // The client reached a height at or above the excepted `Prepare` (as proven by a
+    // `Continuity`), committing a different `Prepare` at its height if equal: like
+    // `Stale`, the excepted `Prepare` is stale
+    Overtaken(Continuity),
+    // No proof was available that the client reached the height below the excepted
+    // `Prepare` (the client skipped a height). A `Discontinuous` exception is evidenced
+    // by the exchange that produced it: the replica queried the `Continuity` of the
+    // excepted `Prepare`, and none was provided (see `BatchCommitShard::validate`)
+    Discontinuous(Id),
+    // The excepted `Prepare` is beyond the pipeline window of the replica (its account
+    // is not known to be committed up to `height - pipeline_width`, see `AccountSettings`),
+    // and the client provided no `Completion` of the height below. Like `Discontinuous`,
+    // a `Premature` exception is evidenced by the exchange that produced it
+    Premature(Id),
+}
+
+#[derive(Doom)]
+pub(crate) enum ExceptionError {
+    #[doom(description("Mismatched id"))]
+    MismatchedId,
+    #[doom(description("Equivocation invalid"))]
+    EquivocationInvalid,
+    #[doom(description("Committed `Prepare` not higher than `Prepare`"))]
+    CommitNotHigher,
+    #[doom(description("`CommitProof` invalid"))]
+    CommitProofInvalid,
+    #[doom(description("`Continuity` neither higher than nor conflicting with `Prepare`"))]
+    ContinuityNotOvertaking,
+    #[doom(description("`Continuity` invalid"))]
+    ContinuityInvalid,
+    #[doom(description("`Prepare` continuous"))]
+    PrepareContinuous,
+    #[doom(description("`Prepare` within window"))]
+    PrepareWithinWindow,
+}
+
+impl Exception {
+    pub fn id(&self) -> Id {
+        match self {
+            Exception::Equivocation(equivocation) => equivocation.id(),
+            Exception::Stale(committed, _) => committed.id(),
+            Exception::Overtaken(continuity) => continuity.entry().id,
+            Exception::Discontinuous(id) => *id,
+            Exception::Premature(id) => *id,
+        }
+    }
+
+    /// Validates `self` as an exception for `prepare`. `queried` is `true` only if the
+    /// `Continuity` of `prepare` was queried, in which case `continuity` is the
+    /// `Continuity` (if any) that was provided.
+    pub fn validate(
+        &self,
+        discovery: &Client,
+        prepare: &Prepare,
+        queried: bool,
+        continuity: Option<&Continuity>,
+    ) -> Result<(), Top<ExceptionError>> {
+        if self.id() != prepare.id() {
+            return ExceptionError::MismatchedId.fail().spot(here!());
+        }
+
+        match self {
+            Exception::Equivocation(equivocation) => equivocation
+                .validate(discovery)
+                .pot(ExceptionError::EquivocationInvalid, here!()),
+            Exception::Stale(committed, proof) => {
+                if committed.height() <= prepare.height() {
+                    return ExceptionError::CommitNotHigher.fail().spot(here!());
+                }
+
+                proof
+                    .validate(discovery, committed)
+                    .pot(ExceptionError::CommitProofInvalid, here!())
+            }
+            Exception::Overtaken(continuity) => {
+                let committed = continuity.prepare();
+
+                let overtaking = committed.height() > prepare.height()
+                    || (committed.height() == prepare.height()
+                        && committed.commitment() != prepare.commitment());
+
+                if !overtaking {
+                    return ExceptionError::ContinuityNotOvertaking.fail().spot(here!());
+                }
+
+                continuity
+                    .validate(discovery)
+                    .pot(ExceptionError::ContinuityInvalid, here!())
+            }
+            Exception::Discontinuous(_) => {
+                // Heights up to 1 need no proof of continuity
+                if !queried || continuity.is_some() || prepare.height() <= 1 {
+                    ExceptionError::PrepareContinuous.fail().spot(here!())
+                } else {
+                    Ok(())
+                }
+            }
+            Exception::Premature(_) => {
+                // A `Completion` of the height below proves that `prepare` is within
+                // any window (heights up to 1 are within any window)
+                let completed = matches!(continuity, Some(Continuity::Completion(_)));
+
+                if !queried || completed || prepare.height() <= 1 {
+                    ExceptionError::PrepareWithinWindow.fail().spot(here!())
+                } else {
+                    Ok(())
+                }
+            }
+        }
+    }
+}
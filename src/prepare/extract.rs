@@ -53,10 +53,18 @@ impl Extract {
         self.prepare.id()
     }
 
+    pub fn height(&self) -> u64 {
+        self.prepare.height()
+    }
+
     pub fn commitment(&self) -> Hash {
         self.prepare.commitment()
     }
 
+    pub fn prepare(&self) -> &Prepare {
+        &self.prepare
+    }
+
     pub fn validate(&self, discovery: &Client) -> Result<(), Top<ExtractError>> {
         let view = discovery
             .view(&self.view)
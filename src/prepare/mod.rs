@@ -1,7 +1,9 @@
 mod batch_commit;
 mod batch_commit_shard;
 mod batch_commit_statement;
+mod continuity;
 mod equivocation;
+mod exception;
 mod extract;
 mod prepare;
 mod reduction_statement;
@@ -12,7 +14,9 @@ mod witnessed_batch;
 pub(crate) use batch_commit::BatchCommit;
 pub(crate) use batch_commit_shard::BatchCommitShard;
 pub(crate) use batch_commit_statement::BatchCommitStatement;
+pub(crate) use continuity::Continuity;
 pub(crate) use equivocation::Equivocation;
+pub(crate) use exception::Exception;
 pub(crate) use extract::Extract;
 pub(crate) use prepare::Prepare;
 pub(crate) use reduction_statement::ReductionStatement;
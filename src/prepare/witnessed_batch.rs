@@ -10,7 +10,7 @@ use serde::{Deserialize, Serialize};
 
 use talk::crypto::primitives::hash::Hash;
 
-use zebra::vector::Vector;
+use zebra::vector::{Proof, Vector};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct WitnessedBatch {
@@ -44,6 +44,10 @@ impl WitnessedBatch {
         self.prepares.items()
     }
 
+    pub fn prove(&self, index: usize) -> Proof {
+        self.prepares.prove(index)
+    }
+
     pub fn extract(&self, index: usize) -> Extract {
         Extract::new(
             self.view,
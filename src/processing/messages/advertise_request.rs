@@ -0,0 +1,10 @@
+use crate::account::Id;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+pub(crate) enum AdvertiseRequest {
+    Advertisements,
+    Equivocations(Vec<Id>),
+    End,
+}
@@ -0,0 +1,8 @@
+use crate::prepare::Equivocation;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+pub(crate) enum AdvertiseResponse {
+    Equivocations(Vec<Option<Equivocation>>),
+}
@@ -1,9 +1,18 @@
+mod advertise_request;
+mod advertise_response;
 mod commit_request;
 mod commit_response;
 mod prepare_request;
 mod prepare_response;
+mod query_request;
+mod query_response;
 mod signup_request;
 mod signup_response;
+mod transfer_request;
+mod transfer_response;
+
+pub(crate) use advertise_request::AdvertiseRequest;
+pub(crate) use advertise_response::AdvertiseResponse;
 
 #[allow(unused_imports)]
 pub(crate) use commit_request::CommitRequest;
@@ -13,5 +22,9 @@ pub(crate) use commit_response::CommitResponse;
 
 pub(crate) use prepare_request::PrepareRequest;
 pub(crate) use prepare_response::PrepareResponse;
+pub(crate) use query_request::QueryRequest;
+pub(crate) use query_response::QueryResponse;
 pub(crate) use signup_request::SignupRequest;
 pub(crate) use signup_response::SignupResponse;
+pub(crate) use transfer_request::TransferRequest;
+pub(crate) use transfer_response::TransferResponse;
@@ -1,7 +1,8 @@
 use crate::{
     crypto::Certificate,
-    prepare::{BatchCommit, Prepare},
-    signup::IdAssignment,
+    prepare::{BatchCommit, Continuity, Prepare},
+    query::AccountProof,
+    signup::{IdAssignment, KeyRotation},
 };
 
 use serde::{Deserialize, Serialize};
@@ -16,6 +17,8 @@ pub(crate) enum PrepareRequest {
     Batch(Vector<Prepare>),
     Signatures(MultiSignature, Vec<Option<Signature>>),
     Assignments(Vec<IdAssignment>),
+    Rotations(Vec<(AccountProof, Option<KeyRotation>)>),
     Witness(Certificate),
+    Continuities(Vec<Option<Continuity>>),
     Commit(BatchCommit),
 }
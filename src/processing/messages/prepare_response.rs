@@ -8,6 +8,8 @@ use talk::crypto::primitives::multi::Signature as MultiSignature;
 pub(crate) enum PrepareResponse {
     Pong,
     UnknownIds(Vec<Id>),
+    LaggingIds(Vec<Id>),
     WitnessShard(MultiSignature),
+    MissingContinuities(Vec<Id>),
     CommitShard(BatchCommitShard),
 }
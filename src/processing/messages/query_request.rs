@@ -0,0 +1,16 @@
+use crate::account::Id;
+
+use serde::{Deserialize, Serialize};
+
+use std::ops::Range;
+
+use talk::crypto::primitives::hash::Hash;
+
+#[derive(Serialize, Deserialize)]
+pub(crate) enum QueryRequest {
+    Ping,
+    Summaries(Vec<Id>),
+    Proof(Id),
+    Motion(Hash),
+    History { id: Id, heights: Range<u64> },
+}
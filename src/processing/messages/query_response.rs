@@ -0,0 +1,19 @@
+use crate::{
+    account::AccountSummary, database::Receipt, motion::PassShard, query::AccountProofShard,
+};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+pub(crate) enum QueryResponse {
+    Pong,
+    Summaries(Vec<Option<AccountSummary>>),
+    Proof(AccountProofShard),
+    // `shard` is provided only if `tally` reached the pass threshold
+    Motion {
+        tally: u64,
+        shard: Option<PassShard>,
+    },
+    // `Receipt`s are sorted by height
+    History(Vec<Receipt>),
+}
@@ -0,0 +1,13 @@
+use crate::account::Id;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+pub(crate) enum TransferRequest {
+    Frontier,
+    Accounts(Vec<Id>),
+    Assignments(Vec<Id>),
+    Claims(Vec<Id>),
+    States(Vec<Id>),
+    End,
+}
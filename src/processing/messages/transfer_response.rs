@@ -0,0 +1,16 @@
+use crate::{
+    account::Account,
+    database::prepare::State,
+    signup::{IdAssignment, IdClaim},
+};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+pub(crate) enum TransferResponse {
+    Accounts(Vec<Option<Account>>),
+    Assignments(Vec<Option<IdAssignment>>),
+    Claims(Vec<Option<IdClaim>>),
+    // Every `PrepareHandle` in a `State` is `Standalone`
+    States(Vec<Option<State>>),
+}
@@ -1,7 +1,7 @@
 mod processor;
 
 #[cfg(test)]
-mod test;
+pub(crate) mod test;
 
 pub(crate) mod messages;
 pub(crate) mod processor_settings;
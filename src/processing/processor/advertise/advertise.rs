@@ -0,0 +1,142 @@
+use buckets::Split;
+
+use crate::{
+    database::{prepare::State, Database},
+    processing::{
+        messages::{AdvertiseRequest, AdvertiseResponse},
+        processor::advertise::errors::ServeAdvertiseError,
+        Processor,
+    },
+};
+
+use doomstack::{here, Doom, ResultExt, Top};
+
+use std::sync::Arc;
+
+use talk::{
+    net::{Listener, Session, SessionListener},
+    sync::{fuse::Fuse, voidable::Voidable},
+};
+
+use zebra::database::Question;
+
+impl Processor {
+    pub(in crate::processing) async fn run_advertise<L>(
+        database: Arc<Voidable<Database>>,
+        listener: L,
+    ) where
+        L: Listener,
+    {
+        let mut listener = SessionListener::new(listener);
+        let fuse = Fuse::new();
+
+        loop {
+            let (_, session) = listener.accept().await;
+            let database = database.clone();
+
+            fuse.spawn(async move {
+                let _ = Processor::serve_advertise(database, session).await;
+            });
+        }
+    }
+
+    async fn serve_advertise(
+        database: Arc<Voidable<Database>>,
+        mut session: Session,
+    ) -> Result<(), Top<ServeAdvertiseError>> {
+        let request = session
+            .receive::<AdvertiseRequest>()
+            .await
+            .pot(ServeAdvertiseError::ConnectionError, here!())?;
+
+        if !matches!(request, AdvertiseRequest::Advertisements) {
+            return ServeAdvertiseError::UnexpectedRequest.fail().spot(here!());
+        }
+
+        // Snapshot `prepare.advertisements` (flushed first, as it is updated lazily)
+
+        let advertisements = {
+            let mut database = database
+                .lock()
+                .pot(ServeAdvertiseError::DatabaseVoid, here!())?;
+
+            database.prepare.flush_advertisements();
+            database.prepare.advertisements.clone()
+        };
+
+        // Synchronize `advertisements` (zebra `Answer`s are sent raw, with
+        // the peer ending the synchronization with `None`)
+
+        let mut sender = advertisements.send();
+        let mut next = sender.hello();
+
+        loop {
+            session
+                .send(&next)
+                .await
+                .pot(ServeAdvertiseError::ConnectionError, here!())?;
+
+            let question = session
+                .receive::<Option<Question>>()
+                .await
+                .pot(ServeAdvertiseError::ConnectionError, here!())?;
+
+            match question {
+                Some(question) => {
+                    next = sender
+                        .answer(&question)
+                        .pot(ServeAdvertiseError::MalformedQuestion, here!())?
+                }
+                None => break,
+            }
+        }
+
+        // Serve the `Equivocation`s backing the peer's missing
+        // `Advertisement::Equivocated`s, until `AdvertiseRequest::End`
+
+        loop {
+            let request = session
+                .receive::<AdvertiseRequest>()
+                .await
+                .pot(ServeAdvertiseError::ConnectionError, here!())?;
+
+            let response = match request {
+                AdvertiseRequest::Advertisements => {
+                    return ServeAdvertiseError::UnexpectedRequest.fail().spot(here!());
+                }
+                AdvertiseRequest::Equivocations(ids) => {
+                    let ids = Split::with_key(ids, |id| *id);
+
+                    let equivocations = {
+                        let mut database = database
+                            .lock()
+                            .pot(ServeAdvertiseError::DatabaseVoid, here!())?;
+
+                        database
+                            .prepare
+                            .states
+                            .apply(ids, |states, id| match states.get(&id) {
+                                Some(State::Equivocated(equivocation)) => {
+                                    Some(equivocation.clone())
+                                }
+                                _ => None,
+                            })
+                    }
+                    .join();
+
+                    AdvertiseResponse::Equivocations(equivocations)
+                }
+                AdvertiseRequest::End => break,
+            };
+
+            session
+                .send(&response)
+                .await
+                .pot(ServeAdvertiseError::ConnectionError, here!())?;
+        }
+
+        session.end();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,31 @@
+use doomstack::Doom;
+
+#[derive(Doom)]
+pub(in crate::processing::processor::advertise) enum ServeAdvertiseError {
+    #[doom(description("Connection error"))]
+    ConnectionError,
+    #[doom(description("Database void"))]
+    DatabaseVoid,
+    #[doom(description("Malformed `Question`"))]
+    MalformedQuestion,
+    #[doom(description("Unexpected request"))]
+    UnexpectedRequest,
+}
+
+#[derive(Doom)]
+pub(in crate::processing::processor::advertise) enum SynchronizeError {
+    #[doom(description("Failed to connect"))]
+    ConnectFailed,
+    #[doom(description("Connection error"))]
+    ConnectionError,
+    #[doom(description("Database void"))]
+    DatabaseVoid,
+    #[doom(description("Malformed `Answer`"))]
+    MalformedAnswer,
+    #[doom(description("Unexpected response"))]
+    UnexpectedResponse,
+    #[doom(description("Mismatched `Equivocation`"))]
+    MismatchedEquivocation,
+    #[doom(description("Invalid `Equivocation`"))]
+    InvalidEquivocation,
+}
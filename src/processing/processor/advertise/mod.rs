@@ -0,0 +1,3 @@
+mod advertise;
+mod errors;
+mod synchronize;
@@ -0,0 +1,440 @@
+use buckets::Split;
+
+use crate::{
+    database::{
+        prepare::{Advertisement, PrepareHandle, State},
+        storage::Record,
+        Database,
+    },
+    discovery::Client,
+    processing::{
+        messages::{AdvertiseRequest, AdvertiseResponse},
+        processor::advertise::errors::SynchronizeError,
+        processor_settings::Advertise,
+        Processor,
+    },
+    view::View,
+};
+
+use doomstack::{here, Doom, ResultExt, Top};
+
+use rayon::prelude::*;
+
+use std::sync::Arc;
+
+use talk::{
+    crypto::Identity,
+    net::{Session, SessionConnector},
+    sync::voidable::Voidable,
+};
+
+use tokio::time;
+
+use zebra::database::{Question, Table, TableStatus};
+
+impl Processor {
+    /// Periodically synchronizes `prepare.advertisements` with the other
+    /// members of `view` (one at a time, in turn). Whenever a member advertises
+    /// an `Id` as equivocated, its `Equivocation` is fetched and adopted, so
+    /// that future `Prepare`s for that `Id` are rejected by every replica.
+    pub(in crate::processing) async fn run_synchronize(
+        identity: Identity,
+        discovery: Arc<Client>,
+        view: View,
+        database: Arc<Voidable<Database>>,
+        connector: SessionConnector,
+        settings: Advertise,
+    ) {
+        let peers = view
+            .members()
+            .keys()
+            .copied()
+            .filter(|member| *member != identity)
+            .collect::<Vec<_>>();
+
+        if peers.is_empty() {
+            return;
+        }
+
+        for peer in peers.iter().cycle() {
+            time::sleep(settings.interval).await;
+
+            // A failed synchronization is simply retried with the next peer
+            let _ =
+                Processor::synchronize(discovery.as_ref(), database.as_ref(), &connector, *peer)
+                    .await;
+        }
+    }
+
+    async fn synchronize(
+        discovery: &Client,
+        database: &Voidable<Database>,
+        connector: &SessionConnector,
+        peer: Identity,
+    ) -> Result<(), Top<SynchronizeError>> {
+        // Snapshot `prepare.advertisements` (flushed first, as it is updated lazily)
+
+        let (mut local, mut receiver) = {
+            let mut database = database
+                .lock()
+                .pot(SynchronizeError::DatabaseVoid, here!())?;
+
+            database.prepare.flush_advertisements();
+
+            (
+                database.prepare.advertisements.clone(),
+                database.families.ids_to_prepare_advertisements.receive(),
+            )
+        };
+
+        let mut session = connector
+            .connect(peer)
+            .await
+            .pot(SynchronizeError::ConnectFailed, here!())?;
+
+        session
+            .send(&AdvertiseRequest::Advertisements)
+            .await
+            .pot(SynchronizeError::ConnectionError, here!())?;
+
+        // Receive `peer`'s advertisements (see `Processor::serve_advertise`)
+
+        let mut remote = loop {
+            let answer = session
+                .receive()
+                .await
+                .pot(SynchronizeError::ConnectionError, here!())?;
+
+            match receiver
+                .learn(answer)
+                .pot(SynchronizeError::MalformedAnswer, here!())?
+            {
+                TableStatus::Complete(table) => break table,
+                TableStatus::Incomplete(next, question) => {
+                    receiver = next;
+
+                    session
+                        .send(&Some(question))
+                        .await
+                        .pot(SynchronizeError::ConnectionError, here!())?;
+                }
+            }
+        };
+
+        session
+            .send::<Option<Question>>(&None)
+            .await
+            .pot(SynchronizeError::ConnectionError, here!())?;
+
+        // Only `Advertisement::Equivocated`s can be adopted, as they are backed
+        // by evidence (a `Consistent` advertisement cannot be verified)
+
+        let ids = Table::diff(&mut local, &mut remote)
+            .into_iter()
+            .filter_map(|(id, (_, remote))| match remote {
+                Some(Advertisement::Equivocated) => Some(id),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        if ids.is_empty() {
+            return end(session).await;
+        }
+
+        session
+            .send(&AdvertiseRequest::Equivocations(ids.clone()))
+            .await
+            .pot(SynchronizeError::ConnectionError, here!())?;
+
+        let equivocations = match session
+            .receive::<AdvertiseResponse>()
+            .await
+            .pot(SynchronizeError::ConnectionError, here!())?
+        {
+            AdvertiseResponse::Equivocations(equivocations) if equivocations.len() == ids.len() => {
+                equivocations
+            }
+            _ => return SynchronizeError::UnexpectedResponse.fail().spot(here!()),
+        };
+
+        end(session).await?;
+
+        // `peer` might have garbage collected (or never held) the evidence
+        // backing its advertisements: missing `Equivocation`s are skipped
+
+        let equivocations = ids
+            .into_iter()
+            .zip(equivocations)
+            .filter_map(|(id, equivocation)| equivocation.map(|equivocation| (id, equivocation)))
+            .collect::<Vec<_>>();
+
+        equivocations
+            .par_iter()
+            .map(|(id, equivocation)| {
+                if equivocation.id() != *id {
+                    return SynchronizeError::MismatchedEquivocation
+                        .fail()
+                        .spot(here!());
+                }
+
+                equivocation
+                    .validate(discovery)
+                    .pot(SynchronizeError::InvalidEquivocation, here!())
+            })
+            .collect::<Result<(), Top<SynchronizeError>>>()?;
+
+        // Adopt `equivocations`
+
+        let mut database = database
+            .lock()
+            .pot(SynchronizeError::DatabaseVoid, here!())?;
+
+        let persistent = database.journal.is_persistent();
+
+        let updates = buckets::apply_sparse(
+            &mut database.prepare.states,
+            Split::with_key(equivocations, |(id, _)| *id),
+            |states, (id, equivocation)| {
+                // `State::Equivocated` is absorbing and must not be updated,
+                // while a `Consistent` state no longer references its `Prepare`s
+                let released = match states.get(&id) {
+                    Some(State::Equivocated(_)) => return None,
+                    Some(State::Consistent(pipeline)) => pipeline
+                        .handles()
+                        .filter_map(PrepareHandle::batched)
+                        .collect::<Vec<_>>(),
+                    None => Vec::new(),
+                };
+
+                let state = State::Equivocated(equivocation);
+                states.insert(id, state.clone());
+
+                Some((id, state, released))
+            },
+        );
+
+        for (id, state, released) in updates {
+            for (root, index) in released {
+                if let Some(holder) = database.prepare.batches.get_mut(&root) {
+                    holder.unref(index);
+                }
+            }
+
+            if persistent {
+                database.journal.record(Record::PrepareState { id, state });
+            }
+
+            database.prepare.stale.insert(id);
+        }
+
+        Ok(())
+    }
+}
+
+async fn end(mut session: Session) -> Result<(), Top<SynchronizeError>> {
+    session
+        .send(&AdvertiseRequest::End)
+        .await
+        .pot(SynchronizeError::ConnectionError, here!())?;
+
+    session.end();
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{
+        account::Entry,
+        crypto::Identify,
+        prepare::{Equivocation, Prepare, WitnessedBatch},
+        processing::{test::System, ProcessorSettings},
+        signup::{IdRequest, SignupSettings},
+    };
+
+    use std::time::Duration;
+
+    use talk::{
+        crypto::{primitives::hash, KeyChain},
+        link::context::ConnectDispatcher,
+        net::test::System as NetSystem,
+    };
+
+    use zebra::vector::Vector;
+
+    #[tokio::test]
+    async fn equivocation() {
+        let mut settings = ProcessorSettings::default();
+        settings.advertise.interval = Duration::from_millis(100);
+
+        let System {
+            view,
+            discovery_server: _discovery_server,
+            discovery_client: _discovery_client,
+            brokers,
+            processors,
+        } = System::setup_with_settings(4, 1, settings).await;
+
+        let client = KeyChain::random();
+
+        let request = IdRequest::new(
+            &client,
+            &view,
+            processors[0].0.keycard().identity(),
+            SignupSettings::default().work_difficulty,
+        );
+
+        let assignment = brokers[0].signup(vec![request]).await.remove(0).unwrap();
+        let id = assignment.id();
+
+        let prepare =
+            |value: u64| Prepare::new(Entry { id, height: 1 }, hash::hash(&value).unwrap());
+
+        // Only the first member observes both `Prepare`s at height 1
+
+        let witness = brokers[0]
+            .witness(&[(client.clone(), assignment.clone(), prepare(1))])
+            .await;
+
+        let (_, shard) = brokers[0]
+            .prepare(
+                processors[0].0.keycard().identity(),
+                &[prepare(1)],
+                &witness,
+                &[None],
+            )
+            .await;
+
+        assert!(shard.exceptions().is_empty());
+
+        let witness = brokers[0]
+            .witness(&[(client.clone(), assignment.clone(), prepare(2))])
+            .await;
+
+        let (_, shard) = brokers[0]
+            .prepare(
+                processors[0].0.keycard().identity(),
+                &[prepare(2)],
+                &witness,
+                &[None],
+            )
+            .await;
+
+        assert!(shard.exceptions().contains(&id));
+
+        // Every other member synchronizes with the first member at least once
+        time::sleep(Duration::from_secs(1)).await;
+
+        // Every other member adopted the `Equivocation`, and excepts a
+        // `Prepare` it never observed a conflict for
+
+        let witness = brokers[0]
+            .witness(&[(client.clone(), assignment.clone(), prepare(3))])
+            .await;
+
+        for (keychain, _) in processors.iter().skip(1) {
+            let (_, shard) = brokers[0]
+                .prepare(
+                    keychain.keycard().identity(),
+                    &[prepare(3)],
+                    &witness,
+                    &[None],
+                )
+                .await;
+
+            assert!(shard.exceptions().contains(&id));
+        }
+    }
+
+    #[tokio::test]
+    async fn invalid_equivocation() {
+        let System {
+            view,
+            discovery_server: _discovery_server,
+            discovery_client,
+            brokers,
+            processors,
+        } = System::setup(4, 1).await;
+
+        let client = KeyChain::random();
+
+        let request = IdRequest::new(
+            &client,
+            &view,
+            processors[0].0.keycard().identity(),
+            SignupSettings::default().work_difficulty,
+        );
+
+        let assignment = brokers[0].signup(vec![request]).await.remove(0).unwrap();
+        let id = assignment.id();
+
+        let prepares = vec![Prepare::new(
+            Entry { id, height: 1 },
+            hash::hash(&0u64).unwrap(),
+        )];
+
+        let witness = brokers[0]
+            .witness(&[(client, assignment, prepares[0].clone())])
+            .await;
+
+        // Two copies of the same `Extract` do not prove an equivocation
+
+        let batch = WitnessedBatch::new(view.identifier(), Vector::new(prepares).unwrap(), witness);
+
+        let equivocation = Equivocation::new(batch.extract(0), batch.extract(0));
+
+        // The peer stands in for a member of `view` advertising `id` as
+        // equivocated, and is reached through a separate network
+
+        let mut forged = Database::new();
+
+        buckets::apply_sparse(
+            &mut forged.prepare.states,
+            Split::with_key(vec![(id, State::Equivocated(equivocation))], |(id, _)| *id),
+            |states, (id, state)| states.insert(id, state).map(|_| ()),
+        );
+
+        forged.prepare.stale.insert(id);
+
+        let peer = processors[0].0.clone();
+        let local = KeyChain::random();
+
+        let NetSystem {
+            mut connectors,
+            mut listeners,
+            ..
+        } = NetSystem::setup_with_keychains(vec![peer.clone(), local]).await;
+
+        let _processor = Processor::new(
+            peer.clone(),
+            discovery_client.clone(),
+            view.clone(),
+            forged,
+            connectors.remove(0),
+            listeners.remove(0),
+            Default::default(),
+        );
+
+        let dispatcher = ConnectDispatcher::new(connectors.remove(0));
+        let context = format!("{:?}::processor::advertise", view.identifier());
+        let connector = SessionConnector::new(dispatcher.register(context));
+
+        let database = Voidable::new(Database::new());
+
+        let error = Processor::synchronize(
+            discovery_client.as_ref(),
+            &database,
+            &connector,
+            peer.keycard().identity(),
+        )
+        .await
+        .unwrap_err();
+
+        assert!(matches!(error.top(), SynchronizeError::InvalidEquivocation));
+
+        // The invalid `Equivocation` is not adopted
+        assert!(database.lock().unwrap().prepare.stale.is_empty());
+    }
+}
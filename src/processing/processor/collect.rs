@@ -0,0 +1,395 @@
+use buckets::{Buckets, Split};
+
+use crate::{
+    account::{Entry, Id},
+    database::{
+        commit::{BatchHolder as CommitBatchHolder, PayloadHandle},
+        prepare::{BatchHolder as PrepareBatchHolder, PrepareHandle, State},
+        storage::Record,
+        Database,
+    },
+    processing::{processor_settings::Collect, Processor},
+};
+
+use std::{collections::HashMap, mem, sync::Arc};
+
+use talk::{crypto::primitives::hash::Hash, sync::voidable::Voidable};
+
+use tokio::time;
+
+impl Processor {
+    pub(in crate::processing) async fn run_collect(
+        database: Arc<Voidable<Database>>,
+        settings: Collect,
+    ) {
+        loop {
+            time::sleep(settings.interval).await;
+
+            let mut guard = match database.lock() {
+                Ok(guard) => guard,
+                Err(_) => return, // `database` was voided by `Processor::shutdown`
+            };
+
+            Processor::collect(&mut guard);
+        }
+    }
+
+    pub(in crate::processing) fn collect(database: &mut Database) {
+        // Superseded `Payload`s and committed `Prepare`s are processed first,
+        // as they can leave further batches unreferenced
+
+        Processor::collect_payloads(database);
+        Processor::release_committed(database);
+
+        Processor::collect_prepare_batches(database);
+        Processor::collect_commit_batches(database);
+    }
+
+    fn collect_payloads(database: &mut Database) {
+        let superseded = mem::take(&mut database.commit.superseded);
+        let split = Split::with_key(superseded, |entry| entry.id);
+
+        fn fields(
+            database: &mut Database,
+        ) -> (
+            &mut Buckets<HashMap<Entry, PayloadHandle>>,
+            &HashMap<Hash, CommitBatchHolder>,
+        ) {
+            (&mut database.commit.payloads, &database.commit.batches)
+        }
+
+        let (payloads, batches) = fields(database);
+
+        // A superseded `Payload` can be dropped only once its batch has a `BatchCompletion`
+        // (before then, it might still be needed to satisfy dependencies): until then,
+        // its `Entry` is put back in `database.commit.superseded`
+        let outcomes =
+            buckets::apply_sparse_attached(payloads, batches, split, |payloads, batches, entry| {
+                let handle = payloads.get(&entry)?;
+
+                // No `BatchHolder` can be left dangling after garbage
+                // collection: the following always succeeds
+                let holder = batches.get(&handle.batch).unwrap();
+
+                if holder.completion().is_some() {
+                    Some(Ok((entry, payloads.remove(&entry).unwrap())))
+                } else {
+                    Some(Err(entry))
+                }
+            });
+
+        for outcome in outcomes {
+            match outcome {
+                Ok((entry, handle)) => {
+                    database
+                        .commit
+                        .batches
+                        .get_mut(&handle.batch)
+                        .unwrap()
+                        .unref(handle.index);
+
+                    database.journal.record(Record::PayloadCollect(entry));
+                }
+                Err(entry) => database.commit.superseded.push(entry),
+            }
+        }
+    }
+
+    fn release_committed(database: &mut Database) {
+        let committed = mem::take(&mut database.prepare.committed);
+        let split = Split::with_key(committed, |entry| entry.id);
+
+        // A committed `Prepare` no longer references its batch. Its state, however,
+        // is left untouched until its batch is collected
+        let released = buckets::apply_sparse(
+            &mut database.prepare.states,
+            split,
+            |states, entry| match states.get(&entry.id) {
+                Some(State::Consistent(pipeline)) => pipeline
+                    .get(entry.height)
+                    .and_then(|(_, handle)| handle.batched()),
+                _ => None,
+            },
+        );
+
+        for (batch, index) in released {
+            if let Some(holder) = database.prepare.batches.get_mut(&batch) {
+                holder.unref(index);
+            }
+        }
+    }
+
+    fn collect_prepare_batches(database: &mut Database) {
+        let roots = database
+            .prepare
+            .batches
+            .iter()
+            .filter(|(_, holder)| holder.is_unreferenced())
+            .map(|(root, _)| *root)
+            .collect::<Vec<_>>();
+
+        if roots.is_empty() {
+            return;
+        }
+
+        // Surviving states that still point to a collected batch are downgraded
+        // to `PrepareHandle::Standalone`, carrying their own `Extract` (along with
+        // their `CommitProof`, if committed)
+
+        let prepares = roots.iter().flat_map(|root| {
+            let root = *root;
+
+            database.prepare.batches[&root]
+                .batch()
+                .prepares()
+                .iter()
+                .enumerate()
+                .map(move |(index, prepare)| (prepare.id(), root, index))
+                .collect::<Vec<_>>()
+        });
+
+        let split = Split::with_key(prepares, |(id, _, _)| *id);
+
+        fn fields(
+            database: &mut Database,
+        ) -> (
+            &mut Buckets<HashMap<Id, State>>,
+            &HashMap<Hash, PrepareBatchHolder>,
+        ) {
+            (&mut database.prepare.states, &database.prepare.batches)
+        }
+
+        let persistent = database.journal.is_persistent();
+        let (states, batches) = fields(database);
+
+        let records = buckets::apply_sparse_attached(
+            states,
+            batches,
+            split,
+            |states, batches, (id, root, index)| {
+                let state = states.get_mut(&id)?;
+
+                match state {
+                    State::Consistent(pipeline) => {
+                        let handle = pipeline
+                            .handles_mut()
+                            .find(|handle| handle.batched() == Some((root, index)))?;
+
+                        let holder = &batches[&root];
+
+                        *handle = PrepareHandle::Standalone(
+                            holder.extract(index),
+                            holder.commit_proof(index),
+                        );
+                    }
+                    _ => return None,
+                }
+
+                if persistent {
+                    Some(Record::PrepareState {
+                        id,
+                        state: state.clone(),
+                    })
+                } else {
+                    None
+                }
+            },
+        );
+
+        database.journal.extend(records);
+
+        for root in roots {
+            database.prepare.batches.remove(&root);
+            database.journal.record(Record::PrepareCollect(root));
+        }
+    }
+
+    fn collect_commit_batches(database: &mut Database) {
+        let roots = database
+            .commit
+            .batches
+            .iter()
+            .filter(|(_, holder)| holder.is_unreferenced())
+            .map(|(root, _)| *root)
+            .collect::<Vec<_>>();
+
+        for root in roots {
+            database.commit.batches.remove(&root);
+            database.journal.record(Record::CommitCollect(root));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bit_vec::BitVec;
+
+    use crate::{
+        account::Operation,
+        commit::{Payload, WitnessedBatch as CommitWitnessedBatch},
+        crypto::Certificate,
+        database::prepare::Pipeline,
+        prepare::{Prepare, ReductionStatement, WitnessedBatch as PrepareWitnessedBatch},
+    };
+
+    use talk::crypto::{primitives::hash, KeyChain};
+
+    use zebra::vector::Vector;
+
+    // Garbage collection does not inspect witnesses: any `Certificate` will do
+    fn certificate() -> Certificate {
+        let statement = ReductionStatement::new(hash::hash(&0u32).unwrap());
+        let signature = KeyChain::random().multisign(&statement).unwrap();
+
+        Certificate::new(BitVec::new(), signature)
+    }
+
+    fn prepare_batch(entries: &[Entry]) -> PrepareWitnessedBatch {
+        let prepares = entries
+            .iter()
+            .map(|entry| Prepare::new(*entry, hash::hash(entry).unwrap()))
+            .collect::<Vec<_>>();
+
+        PrepareWitnessedBatch::new(
+            hash::hash(&0u32).unwrap(),
+            Vector::new(prepares).unwrap(),
+            certificate(),
+        )
+    }
+
+    fn commit_batch(entries: &[Entry]) -> CommitWitnessedBatch {
+        let payloads = entries
+            .iter()
+            .map(|entry| Payload::new(*entry, Operation::withdraw(entry.id, 0, 0)))
+            .collect::<Vec<_>>();
+
+        CommitWitnessedBatch::new(
+            hash::hash(&0u32).unwrap(),
+            Vector::new(payloads).unwrap(),
+            certificate(),
+        )
+    }
+
+    fn point(database: &mut Database, batch: &PrepareWitnessedBatch) {
+        for (index, prepare) in batch.prepares().iter().enumerate() {
+            let state = State::Consistent(Pipeline::new(
+                prepare.height(),
+                prepare.commitment(),
+                PrepareHandle::Batched {
+                    batch: batch.root(),
+                    index,
+                },
+                None,
+            ));
+
+            database
+                .prepare
+                .states
+                .apply(
+                    Split::with_key(vec![(prepare.id(), state)], |(id, _)| *id),
+                    |states, (id, state)| {
+                        states.insert(id, state);
+                    },
+                )
+                .join();
+        }
+    }
+
+    #[test]
+    fn prepare_batches() {
+        let mut database = Database::new();
+
+        let first = prepare_batch(&[Entry { id: 0, height: 1 }, Entry { id: 1, height: 1 }]);
+        let second = prepare_batch(&[Entry { id: 0, height: 2 }]);
+
+        let (first_root, second_root) = (first.root(), second.root());
+
+        point(&mut database, &first);
+        database
+            .prepare
+            .batches
+            .insert(first_root, PrepareBatchHolder::new(first));
+
+        point(&mut database, &second);
+        database
+            .prepare
+            .batches
+            .insert(second_root, PrepareBatchHolder::new(second));
+
+        // `0`'s `Prepare` in `first` is superseded by `second`
+        database
+            .prepare
+            .batches
+            .get_mut(&first_root)
+            .unwrap()
+            .unref(0);
+
+        Processor::collect(&mut database);
+        assert_eq!(database.prepare.batches.len(), 2);
+
+        // Committing `1`'s `Prepare` leaves `first` unreferenced
+        database.prepare.committed.push(Entry { id: 1, height: 1 });
+
+        Processor::collect(&mut database);
+        assert!(!database.prepare.batches.contains_key(&first_root));
+        assert!(database.prepare.batches.contains_key(&second_root));
+
+        // `1`'s state survives, downgraded to `Standalone`
+        let handles = database
+            .prepare
+            .states
+            .apply(
+                Split::with_key(vec![0, 1], |id| *id),
+                |states, id| match states.get(&id) {
+                    Some(State::Consistent(pipeline)) => {
+                        pipeline.handles().next().unwrap().batched()
+                    }
+                    _ => unreachable!(),
+                },
+            )
+            .join();
+
+        assert_eq!(handles, vec![Some((second_root, 0)), None]);
+    }
+
+    #[test]
+    fn commit_batches() {
+        let mut database = Database::new();
+
+        let first = commit_batch(&[Entry { id: 0, height: 1 }]);
+        let root = first.root();
+
+        database
+            .commit
+            .batches
+            .insert(root, CommitBatchHolder::new(first));
+
+        let entry = Entry { id: 0, height: 1 };
+
+        database
+            .commit
+            .payloads
+            .apply(
+                Split::with_key(vec![entry], |entry| entry.id),
+                move |payloads, entry| {
+                    payloads.insert(
+                        entry,
+                        PayloadHandle {
+                            batch: root,
+                            index: 0,
+                        },
+                    );
+                },
+            )
+            .join();
+
+        // Without a `BatchCompletion`, a superseded `Payload` is retained
+        database.commit.superseded.push(entry);
+
+        Processor::collect(&mut database);
+        assert!(database.commit.batches.contains_key(&root));
+        assert_eq!(database.commit.superseded, vec![entry]);
+    }
+}
@@ -4,6 +4,7 @@ use crate::{
     processing::{
         messages::CommitRequest,
         processor::commit::{errors::ServeCommitError, handlers},
+        processor_settings::Commit,
         Processor,
     },
     view::View,
@@ -26,6 +27,7 @@ impl Processor {
         view: View,
         database: Arc<Voidable<Database>>,
         listener: L,
+        settings: Commit,
     ) where
         L: Listener,
     {
@@ -39,9 +41,12 @@ impl Processor {
             let discovery = discovery.clone();
             let view = view.clone();
             let database = database.clone();
+            let settings = settings.clone();
 
             fuse.spawn(async move {
-                let _ = Processor::serve_commit(keychain, discovery, view, database, session).await;
+                let _ =
+                    Processor::serve_commit(keychain, discovery, view, database, session, settings)
+                        .await;
             });
         }
     }
@@ -52,6 +57,7 @@ impl Processor {
         view: View,
         database: Arc<Voidable<Database>>,
         mut session: Session,
+        settings: Commit,
     ) -> Result<(), Top<ServeCommitError>> {
         let request = session
             .receive::<CommitRequest>()
@@ -68,6 +74,7 @@ impl Processor {
                     database.as_ref(),
                     session,
                     payloads,
+                    &settings,
                 )
                 .await
             }
@@ -79,3 +86,205 @@ impl Processor {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        account::{Entry, Operation},
+        commit::{CompletionProof, Payload},
+        prepare::Continuity,
+        processing::{
+            test::{System, TestBroker},
+            ProcessorSettings,
+        },
+        signup::{IdAssignment, IdRequest, SignupSettings},
+        view::View,
+    };
+
+    use std::time::Duration;
+
+    use talk::crypto::{primitives::hash, Identity, KeyChain};
+
+    use tokio::time;
+
+    use zebra::vector::Vector;
+
+    async fn signup(
+        view: &View,
+        broker: &TestBroker,
+        allocator: Identity,
+        count: usize,
+    ) -> Vec<(KeyChain, IdAssignment)> {
+        let clients = (0..count).map(|_| KeyChain::random()).collect::<Vec<_>>();
+
+        let requests = clients
+            .iter()
+            .map(|client| {
+                IdRequest::new(
+                    client,
+                    view,
+                    allocator,
+                    SignupSettings::default().work_difficulty,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let assignments = broker
+            .signup(requests)
+            .await
+            .into_iter()
+            .map(Option::unwrap);
+
+        clients.into_iter().zip(assignments).collect()
+    }
+
+    fn support(
+        (client, assignment): &(KeyChain, IdAssignment),
+        height: u64,
+    ) -> (KeyChain, IdAssignment, Payload, Option<Continuity>) {
+        let payload = Payload::new(
+            Entry {
+                id: assignment.id(),
+                height,
+            },
+            Operation::support(hash::hash(&height).unwrap()),
+        );
+
+        (client.clone(), assignment.clone(), payload, None)
+    }
+
+    fn payloads(batch: Vec<(KeyChain, IdAssignment, Payload, Option<Continuity>)>) -> Vec<Payload> {
+        batch
+            .into_iter()
+            .map(|(_, _, payload, _)| payload)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn pipelined() {
+        let mut settings = ProcessorSettings::default();
+        settings.commit.account_settings.pipeline_width = 2;
+
+        let System {
+            view,
+            brokers,
+            processors,
+            ..
+        } = System::setup_with_settings(4, 1, settings).await;
+
+        let allocator = processors[0].0.keycard().identity();
+        let clients = signup(&view, &brokers[0], allocator, 2).await;
+
+        let (x, y) = (clients[0].1.id(), clients[1].1.id());
+
+        // The second batch is prepared while the first is not committed yet:
+        // `x`'s `Payload` at height 2 is pipelined on the first batch
+
+        let first = vec![support(&clients[0], 1)];
+
+        let mut second = vec![support(&clients[0], 2), support(&clients[1], 1)];
+        second.sort_by_key(|(_, _, payload, _)| payload.id());
+
+        let first_proofs = brokers[0].commit_proofs(first.as_slice()).await;
+        let second_proofs = brokers[0].commit_proofs(second.as_slice()).await;
+
+        let first = payloads(first);
+        let second = payloads(second);
+
+        // The second batch is committed first: every member applies `y`'s
+        // `Payload` immediately, waiting for the first batch to apply `x`'s
+
+        let (completion, _) = tokio::join!(
+            brokers[0].commit(second.as_slice(), second_proofs.as_slice()),
+            async {
+                time::sleep(Duration::from_millis(500)).await;
+
+                for (keychain, _) in processors.iter() {
+                    let replica = keychain.keycard().identity();
+
+                    let receipts = brokers[0].history(replica, y, 0..u64::MAX).await;
+                    assert_eq!(receipts.len(), 1);
+
+                    let receipts = brokers[0].history(replica, x, 0..u64::MAX).await;
+                    assert!(receipts.is_empty());
+                }
+
+                brokers[0]
+                    .commit(first.as_slice(), first_proofs.as_slice())
+                    .await
+            }
+        );
+
+        assert!(!completion.defers(x) && completion.exception(x).is_none());
+        assert!(!completion.defers(y) && completion.exception(y).is_none());
+
+        for (keychain, _) in processors.iter() {
+            let replica = keychain.keycard().identity();
+
+            let heights = brokers[0]
+                .history(replica, x, 0..u64::MAX)
+                .await
+                .into_iter()
+                .map(|receipt| receipt.entry().height)
+                .collect::<Vec<_>>();
+
+            assert_eq!(heights, vec![1, 2]);
+        }
+    }
+
+    #[tokio::test]
+    async fn deferred() {
+        let mut settings = ProcessorSettings::default();
+        settings.commit.account_settings.pipeline_width = 2;
+        settings.commit.pipeline_timeout = Duration::from_millis(200);
+
+        let System {
+            view,
+            discovery_server: _discovery_server,
+            discovery_client,
+            brokers,
+            processors,
+        } = System::setup_with_settings(4, 1, settings).await;
+
+        let allocator = processors[0].0.keycard().identity();
+        let clients = signup(&view, &brokers[0], allocator, 2).await;
+
+        let (x, y) = (clients[0].1.id(), clients[1].1.id());
+
+        // `x`'s `Payload` at height 2 is pipelined on a batch that is never committed
+
+        brokers[0].commit_proofs(&[support(&clients[0], 1)]).await;
+
+        let mut batch = vec![support(&clients[0], 2), support(&clients[1], 1)];
+        batch.sort_by_key(|(_, _, payload, _)| payload.id());
+
+        let proofs = brokers[0].commit_proofs(batch.as_slice()).await;
+        let batch = payloads(batch);
+
+        // The batch is certified regardless: `x`'s `Payload` is deferred, `y`'s is applied
+
+        let completion = brokers[0].commit(batch.as_slice(), proofs.as_slice()).await;
+
+        assert!(completion.defers(x));
+        assert!(!completion.defers(y) && completion.exception(y).is_none());
+
+        let vector = Vector::new(batch.clone()).unwrap();
+
+        for (index, payload) in batch.iter().enumerate() {
+            let proof = CompletionProof::new(completion.clone(), vector.prove(index));
+            let result = proof.validate(discovery_client.as_ref(), payload);
+
+            assert_eq!(result.is_ok(), payload.id() == y);
+        }
+
+        for (keychain, _) in processors.iter() {
+            let replica = keychain.keycard().identity();
+
+            let receipts = brokers[0].history(replica, y, 0..u64::MAX).await;
+            assert_eq!(receipts.len(), 1);
+
+            let receipts = brokers[0].history(replica, x, 0..u64::MAX).await;
+            assert!(receipts.is_empty());
+        }
+    }
+}
@@ -10,6 +10,8 @@ pub(in crate::processing::processor::commit) enum ServeCommitError {
     MalformedBatch,
     #[doom(description("Database void"))]
     DatabaseVoid,
+    #[doom(description("Failed to persist database"))]
+    PersistFailed,
     #[doom(description("Invalid batch"))]
     InvalidBatch,
     #[doom(description("Malformed commit proofs"))]
@@ -22,8 +24,6 @@ pub(in crate::processing::processor::commit) enum ServeCommitError {
     MismatchedDependency,
     #[doom(description("Invalid dependency"))]
     InvalidDependency,
-    #[doom(description("Batch inapplicable"))]
-    BatchInapplicable,
     #[doom(description("`BatchCompletion` invalid"))]
     BatchCompletionInvalid,
 }
@@ -5,6 +5,8 @@ use crate::{
     processing::{
         messages::CommitResponse,
         processor::commit::{errors::ServeCommitError, steps},
+        processor_settings::Commit,
+        Processor,
     },
     view::View,
 };
@@ -22,6 +24,7 @@ pub(in crate::processing::processor::commit) async fn batch(
     database: &Voidable<Database>,
     mut session: Session,
     payloads: Vector<Payload>,
+    settings: &Commit,
 ) -> Result<(), Top<ServeCommitError>> {
     // Obtain a `WitnessedBatch`
 
@@ -36,7 +39,13 @@ pub(in crate::processing::processor::commit) async fn batch(
 
     // Apply `batch` to `database` to obtain a `BatchCompletionShard`
 
-    let shard = steps::apply_batch(keychain, view, database, batch, dependencies).await?;
+    let shard = steps::apply_batch(keychain, view, database, batch, dependencies, settings).await?;
+
+    // Persist the updates on which `shard` relies before sending `shard`
+
+    Processor::persist(database)
+        .await
+        .pot(ServeCommitError::PersistFailed, here!())?;
 
     // Send `shard` and end `session`
 
@@ -1,5 +1,7 @@
 use crate::{
-    commit::BatchCompletion, database::Database, discovery::Client,
+    commit::BatchCompletion,
+    database::{storage::Record, Database},
+    discovery::Client,
     processing::processor::commit::errors::ServeCommitError,
 };
 
@@ -27,7 +29,8 @@ pub(in crate::processing::processor::commit) async fn completion(
             .pot(ServeCommitError::DatabaseVoid, here!())?;
 
         if let Some(holder) = database.commit.batches.get_mut(&completion.root()) {
-            holder.attach(completion);
+            holder.attach(completion.clone());
+            database.journal.record(Record::Completion(completion));
         }
     }
 
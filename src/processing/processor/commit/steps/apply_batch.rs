@@ -1,22 +1,31 @@
 use buckets::{Buckets, Split};
 
 use crate::{
-    account::{Account, Entry, Id, Operation},
+    account::{Account, AccountSettings, Corruption, Entry, Id, Operation},
     commit::{BatchCompletionShard, Payload, WitnessedBatch},
     crypto::Identify,
     database::{
         commit::{BatchHolder, PayloadHandle},
-        Database,
+        storage::Record,
+        Database, Receipt,
     },
-    processing::processor::commit::errors::ServeCommitError,
+    processing::{processor::commit::errors::ServeCommitError, processor_settings::Commit},
     view::View,
 };
 
 use doomstack::{here, Doom, ResultExt, Top};
 
-use std::collections::HashMap;
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::Instant,
+};
+
+use talk::{
+    crypto::{primitives::hash::Hash, KeyChain},
+    sync::voidable::Voidable,
+};
 
-use talk::{crypto::KeyChain, sync::voidable::Voidable};
+use tokio::time;
 
 use zebra::database::TableTransaction;
 
@@ -26,64 +35,172 @@ pub(in crate::processing::processor::commit) async fn apply_batch(
     database: &Voidable<Database>,
     batch: WitnessedBatch,
     dependencies: Vec<Option<Operation>>,
+    settings: &Commit,
 ) -> Result<BatchCompletionShard, Top<ServeCommitError>> {
+    let account_settings = &settings.account_settings;
+
     let root = batch.root();
 
-    // Check if `batch` can be applied to `database` (i.e.,
-    // every `Entry` in `batch.payloads()` is applicable
-    // to the relevant element of `database.accounts`)
+    // Each `Payload` in `batch` is applied to `database` as soon as it is ready
+    // (i.e., its `Entry` is immediately applicable to the relevant element of
+    // `database.accounts`). Pipelined payloads (i.e., applicable once the heights
+    // preceding them are applied by other batches) are waited for, up to
+    // `settings.pipeline_timeout`. Payloads that are inapplicable, or still
+    // pipelined on timeout, are deferred: the rest of `batch` is certified
+    // regardless, and deferred payloads are left for their clients to resubmit
 
-    let entries = batch
+    let mut pending = batch
         .payloads()
         .iter()
-        .map(Payload::entry)
-        .collect::<Split<_>>();
+        .cloned()
+        .zip(dependencies)
+        .enumerate()
+        .collect::<Vec<_>>();
 
-    let inapplicable_ids = {
-        let mut database = database
-            .lock()
-            .pot(ServeCommitError::DatabaseVoid, here!())?;
+    let mut exceptions = Vec::new();
+    let mut deferred = Vec::new();
+    let mut stored = false;
+
+    let start = Instant::now();
+
+    loop {
+        let applications = Split::with_key(pending, |(_, (payload, _))| payload.id());
+
+        let readiness = {
+            let mut database = database
+                .lock()
+                .pot(ServeCommitError::DatabaseVoid, here!())?;
+
+            buckets::apply_sparse(
+                &mut database.accounts,
+                applications,
+                |accounts, application| {
+                    let (_, (payload, _)) = &application;
+
+                    // Fetch `payload.id()`'s `Account` (if no operation was previously
+                    // processed from `payload.id()`, initialize an empty `Account`)
+
+                    let account = accounts
+                        .entry(payload.id())
+                        .or_insert_with(|| Account::new(payload.id(), account_settings));
+
+                    // Flag whether `payload` is ready to be applied to `account`,
+                    // or at least applicable at all
+
+                    let ready = account.ready(payload.height());
+                    let applicable = account.applicable(payload.height(), account_settings);
+
+                    Some((application, ready, applicable))
+                },
+            )
+        };
+
+        let (ready, unready): (Vec<_>, Vec<_>) =
+            readiness.into_iter().partition(|(_, ready, _)| *ready);
+
+        if !ready.is_empty() {
+            let ready = ready
+                .into_iter()
+                .map(|(application, _, _)| application)
+                .collect::<Vec<_>>();
+
+            exceptions.extend(apply(
+                root,
+                &batch,
+                ready,
+                !stored,
+                database,
+                account_settings,
+            )?);
+            stored = true;
+        }
 
-        buckets::apply_sparse(&mut database.accounts, entries, |accounts, entry| {
-            // Fetch `entry.id`'s `Account` (if no operation was previously
-            // processed from `entry.id`, initialize an empty `Account`)
+        let (pipelined, inapplicable): (Vec<_>, Vec<_>) = unready
+            .into_iter()
+            .partition(|(_, _, applicable)| *applicable);
 
-            let account = accounts
-                .entry(entry.id)
-                .or_insert_with(|| Account::new(entry.id, &Default::default())); // TODO: Add settings
+        deferred.extend(
+            inapplicable
+                .into_iter()
+                .map(|((index, (payload, _)), _, _)| (index, payload.id())),
+        );
 
-            // If `entry.height` is not applicable to `account`, return `entry.id`
+        if pipelined.is_empty() {
+            break;
+        }
 
-            if account.applicable(entry.height) {
-                None
-            } else {
-                Some(entry.id)
-            }
-        })
-    };
+        if start.elapsed() >= settings.pipeline_timeout {
+            deferred.extend(
+                pipelined
+                    .into_iter()
+                    .map(|((index, (payload, _)), _, _)| (index, payload.id())),
+            );
+
+            break;
+        }
+
+        pending = pipelined
+            .into_iter()
+            .map(|(application, _, _)| application)
+            .collect();
 
-    // The whole batch must be applicable in order to be processed
-    if !inapplicable_ids.is_empty() {
-        return ServeCommitError::BatchInapplicable.fail().spot(here!());
+        time::sleep(settings.pipeline_interval).await;
     }
 
-    // Zip together in a `Split` an enumeration of `payloads` and `dependencies`
+    // Deferred payloads were never stored in `database.commit.payloads`:
+    // should `batch` be served again, they are referenced once applied
 
-    let applications = Split::with_key(
-        batch
-            .payloads()
-            .iter()
-            .cloned()
-            .zip(dependencies.iter().cloned())
-            .enumerate(),
-        |(_, (payload, _))| payload.id(),
+    if stored && !deferred.is_empty() {
+        let mut database = database
+            .lock()
+            .pot(ServeCommitError::DatabaseVoid, here!())?;
+
+        if let Some(holder) = database.commit.batches.get_mut(&root) {
+            for (index, _) in deferred.iter() {
+                holder.unref(*index);
+            }
+        }
+    }
+
+    // Sign and return a `BatchCompletionShard` with the appropriate `exceptions`
+
+    let shard = BatchCompletionShard::new(
+        keychain,
+        view.identifier(),
+        root,
+        exceptions,
+        deferred.into_iter().map(|(_, id)| id),
     );
 
+    Ok(shard)
+}
+
+/// Applies each `(index, (payload, dependency))` in `applications` (all ready) to
+/// `database`, storing `batch` in `database.commit.batches` (journaling it if `record`).
+/// Returns the exceptions raised by `applications`.
+fn apply(
+    root: Hash,
+    batch: &WitnessedBatch,
+    applications: Vec<(usize, (Payload, Option<Operation>))>,
+    record: bool,
+    database: &Voidable<Database>,
+    account_settings: &AccountSettings,
+) -> Result<Vec<(Id, Corruption)>, Top<ServeCommitError>> {
+    let indices = applications
+        .iter()
+        .map(|(index, _)| *index)
+        .collect::<Vec<_>>();
+
+    let applications = Split::with_key(applications, |(_, (payload, _))| payload.id());
+
     let flush = {
         let mut database = database
             .lock()
             .pot(ServeCommitError::DatabaseVoid, here!())?;
 
+        // Updated accounts and payloads are journaled only if `database` is persistent
+        let persistent = database.journal.is_persistent();
+
         // Apply each `(_, (payload, dependency))` in `applications` to `database.accounts`,
         // then store `payload` in `database.commit.payloads` as a `PayloadHandle`
 
@@ -100,61 +217,141 @@ pub(in crate::processing::processor::commit) async fn apply_batch(
 
         let flush = buckets::apply_attached(
             (accounts, payloads),
-            &root,
+            &(root, persistent, account_settings),
             applications,
-            |(accounts, payloads), root, (index, (payload, dependency))| {
+            |(accounts, payloads),
+             &(root, persistent, account_settings),
+             (index, (payload, dependency))| {
                 // Apply `(payload, dependency)` to `accounts`
 
                 // All missing accounts where created when checking applicability,
                 // so the following `unwrap` is guaranteed to succeed
                 let account = accounts.get_mut(&payload.id()).unwrap();
 
-                let exception = if account.apply(
-                    &payload,
-                    dependency.as_ref(),
-                    &Default::default(), // TODO: Add settings
-                ) {
+                // `Support`s and `Abandon`s (or a corruption) change the
+                // motions supported by `account`
+                let before = account.motions();
+
+                let (correct, outcome) =
+                    account.apply(&payload, dependency.as_ref(), account_settings);
+
+                // A corrupted `account` carries the evidence of its corruption (even
+                // if `payload` was previously applied, was applied after the corruption,
+                // or `account` was acquired)
+                let exception = if correct {
                     None
                 } else {
-                    Some(payload.id())
+                    let corruption = account.corruption().unwrap().clone();
+                    Some((payload.id(), corruption))
                 };
 
+                let after = account.motions();
+                let motions = Some((before, after)).filter(|(before, after)| before != after);
+
                 let id = payload.id();
                 let summary = account.summarize();
 
                 // Store (a reference to) `payload` in `payloads`
 
-                payloads.insert(
-                    payload.entry(),
-                    PayloadHandle {
-                        batch: *root,
-                        index,
-                    },
-                );
+                let handle = PayloadHandle { batch: root, index };
+
+                // A `Receipt` is issued only the first time `payload` is applied
+                let receipt = outcome.map(|outcome| Receipt::new(payload.clone(), outcome, root));
+
+                // The `Payload` at the previous height of `payload.id()` is now superseded
+                let superseded = payload
+                    .height()
+                    .checked_sub(1)
+                    .map(|height| Entry { id, height })
+                    .filter(|entry| payloads.contains_key(entry));
+
+                let records = if persistent {
+                    let mut records = vec![
+                        Record::Account {
+                            id,
+                            account: account.clone(),
+                        },
+                        Record::Payload {
+                            entry: payload.entry(),
+                            handle: handle.clone(),
+                        },
+                    ];
+
+                    records.extend(receipt.clone().map(Record::Receipt));
+                    records
+                } else {
+                    Vec::new()
+                };
 
-                ((id, summary), exception)
+                // If `payload` was previously stored from another batch, the
+                // old `PayloadHandle` no longer references its batch
+                let released = payloads
+                    .insert(payload.entry(), handle.clone())
+                    .filter(|old| *old != handle);
+
+                (
+                    (id, summary, records),
+                    exception,
+                    (released, superseded),
+                    motions,
+                    receipt,
+                )
             },
         );
 
-        // Store `batch` in `database.commit.batches`
+        // Store `batch` in `database.commit.batches` (if `batch` was already
+        // applied, its `BatchHolder` and `BatchCompletion` are preserved)
+
+        if persistent && record {
+            database.journal.record(Record::CommitBatch(batch.clone()));
+        }
 
         database
+            .prepare
+            .committed
+            .extend(indices.iter().map(|index| batch.payloads()[*index].entry()));
+
+        let holder = database
             .commit
             .batches
-            .insert(root, BatchHolder::new(batch));
+            .entry(root)
+            .or_insert_with(|| BatchHolder::new(batch.clone()));
+
+        // Every applied `Payload` is now referenced by its `PayloadHandle`
+        for index in indices {
+            holder.reference(index);
+        }
 
         flush
     }
     .join();
 
     let mut transaction = TableTransaction::new();
+    let mut journal = Vec::new();
+    let mut released = Vec::new();
+    let mut superseded = Vec::new();
+    let mut motions = Vec::new();
+    let mut receipts = Vec::new();
 
     let exceptions = flush
         .into_iter()
-        .filter_map(|((id, summary), exception)| {
-            transaction.set(id, summary).unwrap();
-            exception
-        })
+        .filter_map(
+            |(
+                (id, summary, records),
+                exception,
+                (released_handle, superseded_entry),
+                motion_update,
+                receipt,
+            )| {
+                transaction.set(id, summary).unwrap();
+                journal.extend(records);
+                released.extend(released_handle);
+                superseded.extend(superseded_entry);
+                motions.extend(motion_update);
+                receipts.extend(receipt);
+                exception
+            },
+        )
         .collect::<Vec<_>>();
 
     {
@@ -163,11 +360,40 @@ pub(in crate::processing::processor::commit) async fn apply_batch(
             .pot(ServeCommitError::DatabaseVoid, here!())?;
 
         database.imminent.execute(transaction);
-    }
+        database.journal.extend(journal);
 
-    // Sign and return a `BatchCompletionShard` with the appropriate `exceptions`
+        // Unreferenced `BatchHolder`s and superseded `Payload`s
+        // are left to garbage collection
 
-    let shard = BatchCompletionShard::new(keychain, view.identifier(), root, exceptions);
+        for handle in released {
+            if let Some(holder) = database.commit.batches.get_mut(&handle.batch) {
+                holder.unref(handle.index);
+            }
+        }
 
-    Ok(shard)
+        database.commit.superseded.extend(superseded);
+
+        for (before, after) in motions {
+            database.motions.update(&before, &after);
+        }
+
+        // `Receipt`s are appended to the history of their `Account`
+
+        database
+            .history
+            .apply(
+                Split::with_key(receipts, |receipt| receipt.entry().id),
+                |history, receipt| {
+                    let entry = receipt.entry();
+
+                    history
+                        .entry(entry.id)
+                        .or_insert_with(BTreeMap::new)
+                        .insert(entry.height, receipt);
+                },
+            )
+            .join();
+    }
+
+    Ok(exceptions)
 }
@@ -1,8 +1,8 @@
 use buckets::{Buckets, Split};
 
 use crate::{
-    account::{Entry, Operation},
-    commit::{Payload, WitnessedBatch},
+    account::{Entry, Id, Operation},
+    commit::WitnessedBatch,
     database::{
         commit::{BatchHolder, PayloadHandle},
         Database,
@@ -71,7 +71,7 @@ pub(in crate::processing::processor::commit) async fn fetch_dependencies(
                 // An `Operation` is available for `dependency` in `database` only if:
                 //  - An entry exists for `dependency` in `payloads`;
                 //  - Such entry is member of a batch to which a `BatchCompletion` was attached
-                //    that does not except `dependency.id`
+                //    that neither excepts nor defers `dependency.id`
                 match payloads.get(&dependency) {
                     Some(handle) => {
                         // No `BatchHolder` can be left dangling after garbage
@@ -84,7 +84,9 @@ pub(in crate::processing::processor::commit) async fn fetch_dependencies(
                             // This means that the broker is Byzantine, and will fail
                             // to exhibit a `Completion` for `dependency` when asked
                             // to do so.
-                            if !completion.excepts(dependency.id) {
+                            if !completion.excepts(dependency.id)
+                                && !completion.defers(dependency.id)
+                            {
                                 return Ok(holder.batch().payloads()[handle.index]
                                     .operation()
                                     .clone());
@@ -100,9 +102,13 @@ pub(in crate::processing::processor::commit) async fn fetch_dependencies(
     }
     .join();
 
-    // Extract `Option` dependencies from `batch.payloads()`
+    // For each element of `batch.payloads()` with a dependency, extract the `Id` of
+    // its depositor (on which the dependency is eventually reduced: see `reduce`)
 
-    let dependencies = batch.payloads().iter().map(Payload::dependency);
+    let dependencies = batch
+        .payloads()
+        .iter()
+        .map(|payload| payload.dependency().map(|_| payload.id()));
 
     // Extract `Id`s and corresponding `Entry` dependencies missing from `database`
 
@@ -118,11 +124,11 @@ pub(in crate::processing::processor::commit) async fn fetch_dependencies(
         // each `Some` element of `dependencies`
         let operations = dependencies
             .map(|dependency| {
-                dependency.map(|_| {
+                dependency.map(|depositor| {
                     // The following `unwrap`s cannot fail:
                     // - There are as many `database_operations` as there are `Some` `dependencies`
                     // - All elements of `database_operations` are guaranteed to be `Ok`
-                    database_operations.next().unwrap().unwrap()
+                    reduce(database_operations.next().unwrap().unwrap(), depositor)
                 })
             })
             .collect::<Vec<_>>();
@@ -186,23 +192,38 @@ pub(in crate::processing::processor::commit) async fn fetch_dependencies(
 
     let operations = dependencies
         .map(|dependency| {
-            dependency.map(|_| {
-                // The next element of `database_operations` is `Some`
-                // if and only if the ignored argument `_` of this closure
+            dependency.map(|depositor| {
+                // The next element of `database_operations` is `Ok`
+                // if and only if the dependency of `depositor`'s payload
                 // could be found in `database`.
-                // If the next element of `database_operations` is `None`,
-                // then `_` was satisfied by querying `session`, and the
-                // relevant operation can be extracted from the next element
-                // of `completions`.
-                match database_operations.next().unwrap() {
+                // If the next element of `database_operations` is `Err`,
+                // then the dependency was satisfied by querying `session`,
+                // and the relevant operation can be extracted from the next
+                // element of `completions`.
+                let operation = match database_operations.next().unwrap() {
                     Ok(operation) => operation,
                     // Noting that `session_operations` is as long as
                     // `missing`, the following cannot fail
                     Err(_) => session_operations.next().unwrap(),
-                }
+                };
+
+                reduce(operation, depositor)
             })
         })
         .collect::<Vec<_>>();
 
     Ok(operations)
 }
+
+// A `Deposit` refers to a single leg of a `Transfer`: only the leg paying `depositor`
+// is retained, and `CorrectState::apply` checks its slot as it would for a `Withdraw`
+// (a `Transfer` without such a leg is left to `CorrectState::apply` to reject)
+fn reduce(operation: Operation, depositor: Id) -> Operation {
+    if let Operation::Transfer(transfer) = &operation {
+        if let Some(leg) = transfer.leg(depositor) {
+            return Operation::Withdraw(leg.clone());
+        }
+    }
+
+    operation
+}
@@ -79,10 +79,10 @@ pub(in crate::processing::processor::commit) async fn validate_batch(
         buckets::apply_sparse_attached(states, batches, prepares, |states, batches, prepare| {
             // Check if:
             // - A `Consistent` entry exists in `states` for `prepare.id()`;
-            // - The height and commitment of such entry match `prepare.id()`
-            //   and `prepare.commitment()`, meaning that `prepare` was
-            //   the last `Prepare` seen by the local replica for `prepare.id()`,
-            //   and `prepare` was not previously committed;
+            // - The pipeline of such entry holds a `Prepare` at `prepare.height()`
+            //   whose commitment matches `prepare.commitment()`, meaning that
+            //   `prepare` was seen by the local replica for `prepare.id()`, and
+            //   was not superseded since;
             // - Such entry belongs to a `prepare::WitnessedBatch` for which
             //   a `BatchCommit` was obtained that does not except `prepare.id()`.
             // If so, no additional information is necessary to verify that
@@ -93,13 +93,12 @@ pub(in crate::processing::processor::commit) async fn validate_batch(
                 // State relevant to `prepare.id()` must exist
                 Some(state) => match state {
                     // `state` must be consistent
-                    State::Consistent {
-                        height,
-                        commitment,
-                        handle,
-                    } => {
-                        // `state`'s `height` and `commitment` must match `prepare`'s
-                        if *height == prepare.height() && *commitment == prepare.commitment() {
+                    State::Consistent(pipeline) => {
+                        // `pipeline` must hold a `Prepare` matching `prepare`'s height and commitment
+                        if let Some((_, handle)) = pipeline
+                            .get(prepare.height())
+                            .filter(|(commitment, _)| *commitment == prepare.commitment())
+                        {
                             match handle {
                                 // `handle` must be `Batched` (committed batches are garbage
                                 // collected along with their `BatchCommit`s)
@@ -118,7 +117,7 @@ pub(in crate::processing::processor::commit) async fn validate_batch(
                                         }
                                     }
                                 }
-                                PrepareHandle::Standalone(_) => (),
+                                PrepareHandle::Standalone(..) => (),
                             }
                         }
                     }
@@ -8,7 +8,7 @@ use std::sync::Arc;
 use talk::{
     crypto::KeyChain,
     link::context::{ConnectDispatcher, ListenDispatcher},
-    net::{Connector, Listener},
+    net::{Connector, Listener, SessionConnector},
     sync::{fuse::Fuse, voidable::Voidable},
 };
 
@@ -31,11 +31,34 @@ impl Processor {
         C: Connector,
         L: Listener,
     {
-        let database = Arc::new(Voidable::new(database));
-
-        let _connect_dispatcher = ConnectDispatcher::new(connector);
+        let connect_dispatcher = ConnectDispatcher::new(connector);
         let listen_dispatcher =
-            ListenDispatcher::new(listener, settings.listen_dispatcher_settings);
+            ListenDispatcher::new(listener, settings.listen_dispatcher_settings.clone());
+
+        Processor::with_dispatcher(
+            keychain,
+            discovery,
+            view,
+            database,
+            &connect_dispatcher,
+            &listen_dispatcher,
+            settings,
+        )
+    }
+
+    /// Builds a `Processor` whose contexts are registered to (possibly shared)
+    /// `connect_dispatcher` and `listen_dispatcher`. Contexts are scoped by `view`,
+    /// hence several `Processor`s (for different `View`s) can share the same dispatchers.
+    pub fn with_dispatcher(
+        keychain: KeyChain,
+        discovery: Arc<Client>,
+        view: View,
+        database: Database,
+        connect_dispatcher: &ConnectDispatcher,
+        listen_dispatcher: &ListenDispatcher,
+        settings: ProcessorSettings,
+    ) -> Self {
+        let database = Arc::new(Voidable::new(database));
 
         let fuse = Fuse::new();
 
@@ -71,8 +94,19 @@ impl Processor {
             let prepare_context = format!("{:?}::processor::prepare", view.identifier());
             let prepare_listener = listen_dispatcher.register(prepare_context);
 
+            // `Prepare`s are pipelined according to the same `AccountSettings` as commits
+            let account_settings = settings.commit.account_settings.clone();
+
             fuse.spawn(async move {
-                Processor::run_prepare(keychain, discovery, view, database, prepare_listener).await;
+                Processor::run_prepare(
+                    keychain,
+                    discovery,
+                    view,
+                    database,
+                    prepare_listener,
+                    account_settings,
+                )
+                .await;
             });
         }
 
@@ -84,9 +118,109 @@ impl Processor {
 
             let commit_context = format!("{:?}::processor::commit", view.identifier());
             let commit_listener = listen_dispatcher.register(commit_context);
+            let commit_settings = settings.commit.clone();
+
+            fuse.spawn(async move {
+                Processor::run_commit(
+                    keychain,
+                    discovery,
+                    view,
+                    database,
+                    commit_listener,
+                    commit_settings,
+                )
+                .await;
+            });
+        }
+
+        {
+            let keychain = keychain.clone();
+            let view = view.clone();
+            let database = database.clone();
+
+            let query_context = format!("{:?}::processor::query", view.identifier());
+            let query_listener = listen_dispatcher.register(query_context);
+            let query_settings = settings.query;
+
+            // Motions pass according to the threshold under which commits tally them
+            let pass_threshold = settings.commit.pass_threshold;
+
+            fuse.spawn(async move {
+                Processor::run_query(
+                    keychain,
+                    view,
+                    database,
+                    query_listener,
+                    query_settings,
+                    pass_threshold,
+                )
+                .await;
+            });
+        }
+
+        {
+            let view = view.clone();
+            let database = database.clone();
+
+            let transfer_context = format!("{:?}::processor::transfer", view.identifier());
+            let transfer_listener = listen_dispatcher.register(transfer_context);
+
+            fuse.spawn(async move {
+                Processor::run_transfer(database, transfer_listener).await;
+            });
+        }
+
+        {
+            let discovery = discovery.clone();
+            let view = view.clone();
+            let database = database.clone();
+
+            // Members of `view` synchronize advertisements through each other's
+            // `advertise` context
+            let advertise_context = format!("{:?}::processor::advertise", view.identifier());
+            let advertise_connector =
+                SessionConnector::new(connect_dispatcher.register(advertise_context.clone()));
+            let advertise_listener = listen_dispatcher.register(advertise_context);
+            let advertise_settings = settings.advertise;
+
+            let identity = keychain.keycard().identity();
+
+            {
+                let database = database.clone();
+
+                fuse.spawn(async move {
+                    Processor::run_advertise(database, advertise_listener).await;
+                });
+            }
+
+            fuse.spawn(async move {
+                Processor::run_synchronize(
+                    identity,
+                    discovery,
+                    view,
+                    database,
+                    advertise_connector,
+                    advertise_settings,
+                )
+                .await;
+            });
+        }
+
+        {
+            let database = database.clone();
+            let collect_settings = settings.collect;
+
+            fuse.spawn(async move {
+                Processor::run_collect(database, collect_settings).await;
+            });
+        }
+
+        {
+            let database = database.clone();
+            let persist_settings = settings.persist;
 
             fuse.spawn(async move {
-                Processor::run_commit(keychain, discovery, view, database, commit_listener).await;
+                Processor::run_persist(database, persist_settings).await;
             });
         }
 
@@ -97,10 +231,17 @@ impl Processor {
     }
 
     pub fn shutdown(self) -> Database {
+        // `Record`s not yet flushed are retained in the returned `Database`'s
+        // journal, to be flushed by the next `Processor` (or by the caller)
         self.database.void()
     }
 }
 
+mod advertise;
+mod collect;
 mod commit;
+mod persist;
 mod prepare;
+mod query;
 mod signup;
+mod transfer;
@@ -0,0 +1,82 @@
+use crate::{
+    database::{storage::Journal, Database},
+    processing::{processor_settings::Persist, Processor},
+};
+
+use doomstack::{here, Doom, ResultExt, Top};
+
+use std::sync::Arc;
+
+use talk::sync::voidable::Voidable;
+
+use tokio::time;
+
+#[derive(Doom)]
+pub(in crate::processing) enum PersistError {
+    #[doom(description("Database void"))]
+    DatabaseVoid,
+    #[doom(description("Failed to append `Record`s to storage"))]
+    AppendFailed,
+}
+
+impl Processor {
+    pub(in crate::processing) async fn run_persist(
+        database: Arc<Voidable<Database>>,
+        settings: Persist,
+    ) {
+        // Handlers persist the `Record`s they depend upon before responding:
+        // periodic flushes cover the `Record`s of all other updates
+        // (e.g., garbage collection or state transfer)
+        loop {
+            time::sleep(settings.flush_interval).await;
+
+            if let Err(error) = Processor::persist(database.as_ref()).await {
+                if let PersistError::DatabaseVoid = error.top() {
+                    return; // `database` was voided by `Processor::shutdown`
+                }
+            }
+        }
+    }
+
+    /// Durably appends all `Record`s pending in `database`'s journal. Replicas
+    /// must await this before sending any signature that depends on those
+    /// `Record`s: a replica that signs then crashes before its `Record`s reach
+    /// `Storage` might otherwise sign conflicting statements upon restart.
+    pub(in crate::processing) async fn persist(
+        database: &Voidable<Database>,
+    ) -> Result<(), Top<PersistError>> {
+        let storage = database
+            .lock()
+            .pot(PersistError::DatabaseVoid, here!())?
+            .journal
+            .storage();
+
+        let storage = match storage {
+            Some(storage) => storage.lock_owned().await,
+            None => return Ok(()),
+        };
+
+        // `Record`s are taken only once `storage` is locked: any `Record` journaled
+        // before this call was either already appended, or is taken below
+        let records = database
+            .lock()
+            .pot(PersistError::DatabaseVoid, here!())?
+            .journal
+            .take();
+
+        let (storage, records, result) = Journal::append(storage, records).await;
+
+        // If `records` could not be appended, put them back for the next flush (while
+        // `storage` is still locked). If `database` was voided in the meantime, `records`
+        // were already taken from its journal: they are lost, but were never acknowledged.
+        if result.is_err() {
+            if let Ok(mut database) = database.lock() {
+                database.journal.restore(records);
+            }
+        }
+
+        drop(storage);
+
+        result.pot(PersistError::AppendFailed, here!())
+    }
+}
@@ -10,18 +10,38 @@ pub(in crate::processing::processor::prepare) enum ServePrepareError {
     MalformedBatch,
     #[doom(description("Database void"))]
     DatabaseVoid,
+    #[doom(description("Failed to persist database"))]
+    PersistFailed,
     #[doom(description("Malformed id assignments"))]
     MalformedIdAssignments,
     #[doom(description("Mismatched id assignment"))]
     MismatchedIdAssignment,
     #[doom(description("Invalid id assignment"))]
     InvalidIdAssignment,
+    #[doom(description("Mismatched key rotation"))]
+    MismatchedKeyRotation,
+    #[doom(description("Invalid key rotation"))]
+    InvalidKeyRotation,
+    #[doom(description("Malformed rotations"))]
+    MalformedRotations,
+    #[doom(description("Invalid account proof"))]
+    InvalidAccountProof,
+    #[doom(description("Stale account proof"))]
+    StaleAccountProof,
     #[doom(description("Invalid batch"))]
     InvalidBatch,
     #[doom(description("Invalid witness"))]
     InvalidWitness,
+    #[doom(description("Malformed continuities"))]
+    MalformedContinuities,
+    #[doom(description("Mismatched continuity"))]
+    MismatchedContinuity,
+    #[doom(description("Invalid continuity"))]
+    InvalidContinuity,
     #[doom(description("Foreign commit"))]
     ForeignCommit,
     #[doom(description("Invalid commit"))]
     InvalidCommit,
+    #[doom(description("Stale prepare without evidence"))]
+    StaleUnproven,
 }
@@ -1,10 +1,12 @@
 use crate::{
+    account::AccountSettings,
     database::Database,
     discovery::Client,
     prepare::Prepare,
     processing::{
         messages::PrepareResponse,
         processor::prepare::{errors::ServePrepareError, steps},
+        Processor,
     },
     view::View,
 };
@@ -22,15 +24,53 @@ pub(in crate::processing::processor::prepare) async fn batch(
     database: &Voidable<Database>,
     mut session: Session,
     prepares: Vector<Prepare>,
+    settings: &AccountSettings,
 ) -> Result<(), Top<ServePrepareError>> {
     // Obtain a `WitnessedBatch`
 
-    let batch =
-        steps::witnessed_batch(keychain, discovery, view, database, &mut session, prepares).await?;
+    let batch = steps::witnessed_batch(
+        keychain,
+        discovery,
+        view,
+        database,
+        &mut session,
+        prepares,
+        settings.pipeline_width,
+    )
+    .await?;
+
+    // Obtain a proof of continuity for every `Prepare` in `batch` whose client is
+    // not known to have reached the previous height, and a proof of completion for
+    // every `Prepare` beyond the pipeline window (query `session` if necessary)
+
+    let (continuous, premature) = steps::fetch_continuities(
+        discovery,
+        database,
+        &mut session,
+        &batch,
+        settings.pipeline_width,
+    )
+    .await?;
 
     // Apply `batch` to `database` to obtain a `BatchCommitShard`
 
-    let shard = steps::apply_batch(keychain, view, database, batch).await?;
+    let shard = steps::apply_batch(
+        keychain,
+        view,
+        database,
+        batch,
+        continuous,
+        premature,
+        settings.pipeline_width,
+    )
+    .await?;
+
+    // Persist the updates on which `shard` relies (in particular, the
+    // prepare states it signs for) before sending `shard`
+
+    Processor::persist(database)
+        .await
+        .pot(ServePrepareError::PersistFailed, here!())?;
 
     // Send `shard` and end `session`
 
@@ -1,5 +1,7 @@
 use crate::{
-    database::Database, discovery::Client, prepare::BatchCommit,
+    database::{storage::Record, Database},
+    discovery::Client,
+    prepare::BatchCommit,
     processing::processor::prepare::errors::ServePrepareError,
 };
 
@@ -27,7 +29,8 @@ pub(in crate::processing::processor::prepare) async fn commit(
             .pot(ServePrepareError::DatabaseVoid, here!())?;
 
         if let Some(holder) = database.prepare.batches.get_mut(&commit.root()) {
-            holder.attach(commit);
+            holder.attach(commit.clone());
+            database.journal.record(Record::PrepareCommit(commit));
         }
     }
 
@@ -1,4 +1,5 @@
 use crate::{
+    account::AccountSettings,
     database::Database,
     discovery::Client,
     processing::{
@@ -26,6 +27,7 @@ impl Processor {
         view: View,
         database: Arc<Voidable<Database>>,
         listener: L,
+        settings: AccountSettings,
     ) where
         L: Listener,
     {
@@ -39,10 +41,13 @@ impl Processor {
             let discovery = discovery.clone();
             let view = view.clone();
             let database = database.clone();
+            let settings = settings.clone();
 
             fuse.spawn(async move {
-                let _ =
-                    Processor::serve_prepare(keychain, discovery, view, database, session).await;
+                let _ = Processor::serve_prepare(
+                    keychain, discovery, view, database, session, settings,
+                )
+                .await;
             });
         }
     }
@@ -53,6 +58,7 @@ impl Processor {
         view: View,
         database: Arc<Voidable<Database>>,
         mut session: Session,
+        settings: AccountSettings,
     ) -> Result<(), Top<ServePrepareError>> {
         let request = session
             .receive::<PrepareRequest>()
@@ -69,6 +75,7 @@ impl Processor {
                     database.as_ref(),
                     session,
                     prepares,
+                    &settings,
                 )
                 .await
             }
@@ -79,3 +86,628 @@ impl Processor {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        account::{Entry, Id, Operation},
+        commit::{Commit, Payload},
+        database::Database,
+        prepare::{Continuity, Prepare, WitnessStatement},
+        processing::{
+            test::{System, TestBroker},
+            Processor, ProcessorSettings,
+        },
+        signup::{IdAssignment, IdRequest, KeyRotation, SignupSettings},
+        view::View,
+    };
+
+    use talk::{
+        crypto::{primitives::hash, KeyChain},
+        net::test::System as NetSystem,
+    };
+
+    use zebra::vector::Vector;
+
+    async fn signup(
+        view: &View,
+        broker: &TestBroker,
+        allocator: &KeyChain,
+    ) -> (KeyChain, IdAssignment) {
+        let client = KeyChain::random();
+
+        let request = IdRequest::new(
+            &client,
+            view,
+            allocator.keycard().identity(),
+            SignupSettings::default().work_difficulty,
+        );
+
+        let assignment = broker.signup(vec![request]).await.remove(0).unwrap();
+
+        (client, assignment)
+    }
+
+    // Commits one `Support` for each of `heights` from `client`'s account,
+    // returning the `Continuity` of the highest
+    async fn settle(
+        broker: &TestBroker,
+        client: &KeyChain,
+        assignment: &IdAssignment,
+        heights: impl Iterator<Item = u64>,
+    ) -> Continuity {
+        let id = assignment.id();
+        let mut continuity = None;
+
+        for height in heights {
+            let payload = Payload::new(
+                Entry { id, height },
+                Operation::support(hash::hash(&height).unwrap()),
+            );
+
+            let completion = broker
+                .settle(&[(client.clone(), assignment.clone(), payload, continuity)])
+                .await
+                .remove(0);
+
+            continuity = Some(Continuity::Completion(completion));
+        }
+
+        continuity.unwrap()
+    }
+
+    #[tokio::test]
+    async fn rotation() {
+        let System {
+            view,
+            discovery_server: _discovery_server,
+            discovery_client,
+            brokers,
+            processors,
+        } = System::setup(4, 1).await;
+
+        let allocator = processors[0].0.keycard().identity();
+
+        let client = KeyChain::random();
+        let request = IdRequest::new(
+            &client,
+            &view,
+            allocator,
+            SignupSettings::default().work_difficulty,
+        );
+
+        let assignment = brokers[0].signup(vec![request]).await.remove(0).unwrap();
+        let id = assignment.id();
+
+        // `AccountProof` predating the rotation
+        let stale = brokers[0].account_proof(id).await;
+
+        // `client` rotates its `KeyCard` at height 1
+
+        let rotated = KeyChain::random();
+
+        let payload = Payload::new(
+            Entry { id, height: 1 },
+            Operation::rotate(rotated.keycard()),
+        );
+
+        let completion = brokers[0]
+            .settle(&[(client.clone(), assignment.clone(), payload, None)])
+            .await
+            .remove(0);
+
+        let rotation = KeyRotation::new(completion).unwrap();
+
+        // The `Prepare` at height 2 is signed by the rotated `KeyCard`
+
+        let prepare = Prepare::new(Entry { id, height: 2 }, hash::hash(&0u32).unwrap());
+
+        let root = Vector::new(vec![prepare.clone()]).unwrap().root();
+        let statement = WitnessStatement::new(root);
+
+        // Every member applied the rotation, and needs no `KeyRotation`
+
+        for (keychain, _) in processors.iter() {
+            let shard = brokers[0]
+                .witness_shard(
+                    keychain.keycard().identity(),
+                    &[(rotated.clone(), assignment.clone(), None, prepare.clone())],
+                    &[],
+                )
+                .await
+                .unwrap();
+
+            shard.verify([&keychain.keycard()], &statement).unwrap();
+        }
+
+        // The rotation is attested by the members' `AccountProof`
+
+        let proof = brokers[0].account_proof(id).await;
+        let summary = proof.validate(id).unwrap().unwrap();
+
+        assert_eq!(summary.rotation, Some((2, rotated.keycard().identity())));
+
+        // The joiner stands in for a member of `view` that did not apply the
+        // rotation, and is reached through a separate network
+        let joiner = processors[0].0.clone();
+        let broker = KeyChain::random();
+
+        let NetSystem {
+            mut connectors,
+            mut listeners,
+            ..
+        } = NetSystem::setup_with_keychains(vec![joiner.clone(), broker.clone()]).await;
+
+        let _processor = Processor::new(
+            joiner.clone(),
+            discovery_client.clone(),
+            view.clone(),
+            Database::new(),
+            connectors.remove(0),
+            listeners.remove(0),
+            Default::default(),
+        );
+
+        let broker = TestBroker::new(broker, view.clone(), connectors.remove(0));
+
+        // The joiner validates the `Prepare` against the provided `KeyRotation`,
+        // as attested by the provided `AccountProof`
+
+        let shard = broker
+            .witness_shard(
+                joiner.keycard().identity(),
+                &[(rotated, assignment.clone(), Some(rotation), prepare.clone())],
+                &[(id, proof.clone())],
+            )
+            .await
+            .unwrap();
+
+        shard.verify([&joiner.keycard()], &statement).unwrap();
+
+        // A `Prepare` signed by the original `KeyCard` is refused, whether the
+        // rotation is left out, or the `AccountProof` predates the rotation
+
+        let leaked = Prepare::new(Entry { id, height: 2 }, hash::hash(&1u32).unwrap());
+
+        for proof in vec![proof, stale] {
+            assert!(broker
+                .witness_shard(
+                    joiner.keycard().identity(),
+                    &[(client.clone(), assignment.clone(), None, leaked.clone())],
+                    &[(id, proof)],
+                )
+                .await
+                .is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn stale() {
+        let mut settings = ProcessorSettings::default();
+        settings.commit.account_settings.pipeline_width = 1;
+
+        let System {
+            view,
+            discovery_server: _discovery_server,
+            discovery_client,
+            brokers,
+            processors,
+        } = System::setup_with_settings(4, 1, settings).await;
+
+        let (client, assignment) = signup(&view, &brokers[0], &processors[0].0).await;
+        let id = assignment.id();
+
+        // Once the `Prepare` at height 2 is committed, the `Prepare` at height 3
+        // evicts the one at height 1 from every pipeline
+        settle(&brokers[0], &client, &assignment, 1..=3).await;
+
+        let prepares = vec![Prepare::new(
+            Entry { id, height: 1 },
+            hash::hash(&0u64).unwrap(),
+        )];
+
+        let witness = brokers[0]
+            .witness(&[(client, assignment, prepares[0].clone())])
+            .await;
+
+        let root = Vector::new(prepares.clone()).unwrap().root();
+
+        // Every member excepts the `Prepare` at height 1, proving that
+        // a higher `Prepare` was committed
+
+        for (keychain, _) in processors.iter() {
+            let (queried, shard) = brokers[0]
+                .prepare(keychain.keycard().identity(), &prepares, &witness, &[None])
+                .await;
+
+            assert!(queried.is_empty());
+            assert!(shard.exceptions().contains(&id));
+
+            shard
+                .validate(
+                    discovery_client.as_ref(),
+                    &view,
+                    root,
+                    &prepares,
+                    &[None],
+                    &queried,
+                    &keychain.keycard(),
+                )
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn overtaken() {
+        let System {
+            view,
+            discovery_server: _discovery_server,
+            discovery_client,
+            brokers,
+            processors,
+        } = System::setup(4, 1).await;
+
+        let (client, assignment) = signup(&view, &brokers[0], &processors[0].0).await;
+        let id = assignment.id();
+
+        let continuity = settle(&brokers[0], &client, &assignment, 1..=2).await;
+
+        // The joiner stands in for a member of `view` that did not observe heights
+        // 1 and 2, and is reached through a separate network
+        let joiner = processors[0].0.clone();
+        let broker = KeyChain::random();
+
+        let NetSystem {
+            mut connectors,
+            mut listeners,
+            ..
+        } = NetSystem::setup_with_keychains(vec![joiner.clone(), broker.clone()]).await;
+
+        let _processor = Processor::new(
+            joiner.clone(),
+            discovery_client.clone(),
+            view.clone(),
+            Database::new(),
+            connectors.remove(0),
+            listeners.remove(0),
+            Default::default(),
+        );
+
+        let broker = TestBroker::new(broker, view.clone(), connectors.remove(0));
+
+        // The joiner initializes its pipeline at height 3 along with the
+        // `Continuity` of height 2
+
+        let prepares = vec![Prepare::new(
+            Entry { id, height: 3 },
+            hash::hash(&0u64).unwrap(),
+        )];
+
+        let witness = brokers[0]
+            .witness(&[(client.clone(), assignment.clone(), prepares[0].clone())])
+            .await;
+
+        let (queried, shard) = broker
+            .prepare(
+                joiner.keycard().identity(),
+                &prepares,
+                &witness,
+                &[Some(continuity.clone())],
+            )
+            .await;
+
+        assert_eq!(queried, vec![id]);
+        assert!(shard.exceptions().is_empty());
+
+        // A `Prepare` conflicting with the one committed at height 2 is excepted,
+        // with the `Continuity` as evidence
+
+        let prepares = vec![Prepare::new(
+            Entry { id, height: 2 },
+            hash::hash(&0u64).unwrap(),
+        )];
+
+        let witness = brokers[0]
+            .witness(&[(client.clone(), assignment.clone(), prepares[0].clone())])
+            .await;
+
+        let root = Vector::new(prepares.clone()).unwrap().root();
+
+        let (queried, shard) = broker
+            .prepare(joiner.keycard().identity(), &prepares, &witness, &[None])
+            .await;
+
+        assert!(shard.exceptions().contains(&id));
+
+        shard
+            .validate(
+                discovery_client.as_ref(),
+                &view,
+                root,
+                &prepares,
+                &[None],
+                &queried,
+                &joiner.keycard(),
+            )
+            .unwrap();
+
+        // The `Prepare` committed at height 2 needs no exception
+
+        let prepares = vec![continuity.prepare()];
+
+        let witness = brokers[0]
+            .witness(&[(client, assignment, prepares[0].clone())])
+            .await;
+
+        let (_, shard) = broker
+            .prepare(joiner.keycard().identity(), &prepares, &witness, &[None])
+            .await;
+
+        assert!(shard.exceptions().is_empty());
+    }
+
+    #[tokio::test]
+    async fn discontinuous() {
+        let System {
+            view,
+            discovery_server: _discovery_server,
+            discovery_client,
+            brokers,
+            processors,
+        } = System::setup(4, 1).await;
+
+        let (client, assignment) = signup(&view, &brokers[0], &processors[0].0).await;
+        let id = assignment.id();
+
+        settle(&brokers[0], &client, &assignment, 1..=1).await;
+
+        // The joiner stands in for a member of `view` that did not observe
+        // height 1, and is reached through a separate network
+        let joiner = processors[0].0.clone();
+        let broker = KeyChain::random();
+
+        let NetSystem {
+            mut connectors,
+            mut listeners,
+            ..
+        } = NetSystem::setup_with_keychains(vec![joiner.clone(), broker.clone()]).await;
+
+        let _processor = Processor::new(
+            joiner.clone(),
+            discovery_client.clone(),
+            view.clone(),
+            Database::new(),
+            connectors.remove(0),
+            listeners.remove(0),
+            Default::default(),
+        );
+
+        let broker = TestBroker::new(broker, view.clone(), connectors.remove(0));
+
+        // The `Continuity` of height 1 is withheld from the joiner
+
+        let prepares = vec![Prepare::new(
+            Entry { id, height: 2 },
+            hash::hash(&0u64).unwrap(),
+        )];
+
+        let witness = brokers[0]
+            .witness(&[(client, assignment, prepares[0].clone())])
+            .await;
+
+        let root = Vector::new(prepares.clone()).unwrap().root();
+
+        let (queried, shard) = broker
+            .prepare(joiner.keycard().identity(), &prepares, &witness, &[None])
+            .await;
+
+        assert_eq!(queried, vec![id]);
+        assert!(shard.exceptions().contains(&id));
+
+        let validate = |queried: &[Id]| {
+            shard.validate(
+                discovery_client.as_ref(),
+                &view,
+                root,
+                &prepares,
+                &[None],
+                queried,
+                &joiner.keycard(),
+            )
+        };
+
+        validate(&queried).unwrap();
+
+        // The exception is unjustified unless the `Continuity` was queried
+        assert!(validate(&[]).is_err());
+    }
+
+    #[tokio::test]
+    async fn premature() {
+        let System {
+            view,
+            discovery_server: _discovery_server,
+            discovery_client,
+            brokers,
+            processors,
+        } = System::setup(4, 1).await;
+
+        let (client, assignment) = signup(&view, &brokers[0], &processors[0].0).await;
+        let id = assignment.id();
+
+        let completion = settle(&brokers[0], &client, &assignment, 1..=1).await;
+
+        // Height 2 is committed, then completed
+
+        let payload = Payload::new(
+            Entry { id, height: 2 },
+            Operation::support(hash::hash(&2u64).unwrap()),
+        );
+
+        let proof = brokers[0]
+            .commit_proofs(&[(
+                client.clone(),
+                assignment.clone(),
+                payload.clone(),
+                Some(completion.clone()),
+            )])
+            .await
+            .remove(0);
+
+        brokers[0]
+            .commit(&[payload.clone()], &[proof.clone()])
+            .await;
+
+        let commit = Continuity::Commit(Commit::new(proof, payload));
+
+        // The joiner stands in for a member of `view` that did not observe heights
+        // 1 and 2, and is reached through a separate network (the default
+        // `pipeline_width` is 1)
+        let joiner = processors[0].0.clone();
+        let broker = KeyChain::random();
+
+        let NetSystem {
+            mut connectors,
+            mut listeners,
+            ..
+        } = NetSystem::setup_with_keychains(vec![joiner.clone(), broker.clone()]).await;
+
+        let _processor = Processor::new(
+            joiner.clone(),
+            discovery_client.clone(),
+            view.clone(),
+            Database::new(),
+            connectors.remove(0),
+            listeners.remove(0),
+            Default::default(),
+        );
+
+        let broker = TestBroker::new(broker, view.clone(), connectors.remove(0));
+
+        // Height 3 is continuous, but beyond the joiner's window
+
+        let prepares = vec![Prepare::new(
+            Entry { id, height: 3 },
+            hash::hash(&0u64).unwrap(),
+        )];
+
+        let continuities = vec![Some(commit)];
+
+        let witness = brokers[0]
+            .witness(&[(client, assignment, prepares[0].clone())])
+            .await;
+
+        let root = Vector::new(prepares.clone()).unwrap().root();
+
+        let (queried, shard) = broker
+            .prepare(
+                joiner.keycard().identity(),
+                &prepares,
+                &witness,
+                &continuities,
+            )
+            .await;
+
+        assert_eq!(queried, vec![id]);
+        assert!(shard.exceptions().contains(&id));
+
+        let validate = |continuities: &[Option<Continuity>], queried: &[Id]| {
+            shard.validate(
+                discovery_client.as_ref(),
+                &view,
+                root,
+                &prepares,
+                continuities,
+                queried,
+                &joiner.keycard(),
+            )
+        };
+
+        validate(&continuities, &queried).unwrap();
+
+        // The exception is unjustified unless the `Continuity` was queried,
+        // and is not a `Completion`
+        assert!(validate(&continuities, &[]).is_err());
+        assert!(validate(&[Some(completion)], &queried).is_err());
+    }
+
+    #[tokio::test]
+    async fn missing_continuities() {
+        let System {
+            view,
+            discovery_server: _discovery_server,
+            discovery_client,
+            brokers,
+            processors,
+        } = System::setup(4, 1).await;
+
+        let (client, assignment) = signup(&view, &brokers[0], &processors[0].0).await;
+        let id = assignment.id();
+
+        let continuity = settle(&brokers[0], &client, &assignment, 1..=1).await;
+
+        let prepares = vec![Prepare::new(
+            Entry { id, height: 2 },
+            hash::hash(&0u64).unwrap(),
+        )];
+
+        let continuities = vec![Some(continuity)];
+
+        let witness = brokers[0]
+            .witness(&[(client, assignment, prepares[0].clone())])
+            .await;
+
+        // Every member committed height 1, and needs no `Continuity`
+
+        for (keychain, _) in processors.iter() {
+            let (queried, shard) = brokers[0]
+                .prepare(
+                    keychain.keycard().identity(),
+                    &prepares,
+                    &witness,
+                    &continuities,
+                )
+                .await;
+
+            assert!(queried.is_empty());
+            assert!(shard.exceptions().is_empty());
+        }
+
+        // The joiner stands in for a member of `view` that did not commit
+        // height 1, and is reached through a separate network
+        let joiner = processors[0].0.clone();
+        let broker = KeyChain::random();
+
+        let NetSystem {
+            mut connectors,
+            mut listeners,
+            ..
+        } = NetSystem::setup_with_keychains(vec![joiner.clone(), broker.clone()]).await;
+
+        let _processor = Processor::new(
+            joiner.clone(),
+            discovery_client.clone(),
+            view.clone(),
+            Database::new(),
+            connectors.remove(0),
+            listeners.remove(0),
+            Default::default(),
+        );
+
+        let broker = TestBroker::new(broker, view.clone(), connectors.remove(0));
+
+        // The joiner queries the `Continuity` of height 2, and validates it
+
+        let (queried, shard) = broker
+            .prepare(
+                joiner.keycard().identity(),
+                &prepares,
+                &witness,
+                &continuities,
+            )
+            .await;
+
+        assert_eq!(queried, vec![id]);
+        assert!(shard.exceptions().is_empty());
+    }
+}
@@ -4,15 +4,16 @@ use crate::{
     account::Id,
     crypto::Identify,
     database::{
-        prepare::{BatchHolder, PrepareHandle, State},
+        prepare::{BatchHolder, Pipeline, PrepareHandle, State},
+        storage::Record,
         Database,
     },
-    prepare::{BatchCommitShard, Equivocation, WitnessedBatch},
+    prepare::{BatchCommitShard, Continuity, Equivocation, Exception, Prepare, WitnessedBatch},
     processing::processor::prepare::errors::ServePrepareError,
     view::View,
 };
 
-use doomstack::{here, ResultExt, Top};
+use doomstack::{here, Doom, ResultExt, Top};
 
 use std::collections::{HashMap, HashSet};
 
@@ -26,6 +27,9 @@ pub(in crate::processing::processor::prepare) async fn apply_batch(
     view: &View,
     database: &Voidable<Database>,
     batch: WitnessedBatch,
+    continuous: HashMap<Id, Option<Continuity>>,
+    premature: HashSet<Id>,
+    pipeline_width: u64,
 ) -> Result<BatchCommitShard, Top<ServePrepareError>> {
     // Prepare `Split` to feed `database`'s `Buckets`
 
@@ -41,6 +45,9 @@ pub(in crate::processing::processor::prepare) async fn apply_batch(
         .lock()
         .pot(ServePrepareError::DatabaseVoid, here!())?;
 
+    // Updated states are journaled only if `database` is persistent
+    let persistent = database.journal.is_persistent();
+
     // This function extracts the appropriate (mutable and immutable) references to
     // `database`'s fields from a mutable reference to `database`. It is unclear
     // whether or not a more compact syntax exists to achieve the same.
@@ -48,131 +55,284 @@ pub(in crate::processing::processor::prepare) async fn apply_batch(
         database: &mut Database,
     ) -> (
         &mut Buckets<HashMap<Id, State>>,
-        &mut Buckets<HashSet<Id>>,
         &HashMap<Hash, BatchHolder>,
     ) {
-        (
-            &mut database.prepare.states,
-            &mut database.prepare.stale,
-            &database.prepare.batches,
-        )
+        (&mut database.prepare.states, &database.prepare.batches)
     }
 
-    let (states, stale, batches) = fields(&mut database);
+    let (states, batches) = fields(&mut database);
+
+    // Gather the evidence of staleness of every `Prepare` in `batch` below its
+    // `Pipeline` before updating `states` (see `staleness`). Evidence is always
+    // available by construction (see `Pipeline`): should it not be, `batch` is
+    // refused before `database` is altered
+    let stale = buckets::apply_sparse_attached(
+        states,
+        batches,
+        Split::with_key(batch.prepares().iter().cloned(), Prepare::id),
+        |states, batches, prepare| match states.get(&prepare.id()) {
+            Some(State::Consistent(pipeline)) if prepare.height() < pipeline.lowest().0 => {
+                let evidence = staleness(pipeline, &prepare, batches);
+                Some(evidence.map(|exception| (prepare.id(), exception)))
+            }
+            _ => None,
+        },
+    )
+    .into_iter()
+    .collect::<Result<HashMap<_, _>, _>>()?;
+
+    let (states, batches) = fields(&mut database);
 
-    // The following applies each enumerated `Prepare` in `split` to `states` and
-    // `stales`, while attaching immutable references to `batches` and `batch`
-    let exceptions = buckets::apply_sparse_attached(
-        (states, stale),
-        &(batches, &batch),
+    // The following applies each enumerated `Prepare` in `split` to `states`,
+    // while attaching immutable references to `batches`, `batch`, `continuous`,
+    // `premature` and `stale` (along with `pipeline_width`).
+    // Alongside each update, the `Prepare`s that are no longer referenced by
+    // any state are returned (see `BatchHolder::unref`)
+    let updates = buckets::apply_attached(
+        states,
+        &(
+            batches,
+            &batch,
+            &continuous,
+            &premature,
+            &stale,
+            pipeline_width,
+        ),
         split,
-        |(states, stale), &(batches, batch), (index, prepare)| {
+        |states,
+         &(batches, batch, continuous, premature, stale, pipeline_width),
+         (index, prepare)| {
             // Build `PrepareHandle` relevant to `prepare`
             let handle = PrepareHandle::Batched {
                 batch: batch.root(),
                 index,
             };
 
+            let mut released = Vec::new();
+
             let state = match states.get(&prepare.id()) {
                 Some(state) => match state {
-                    State::Consistent {
-                        height: state_height,
-                        commitment: state_commitment,
-                        handle: state_handle,
-                    } => {
-                        if prepare.height() == *state_height {
+                    State::Consistent(pipeline) => {
+                        if let Some((state_commitment, state_handle)) =
+                            pipeline.get(prepare.height())
+                        {
                             // A `Prepare` for this `prepare.height()` was previously received.
 
-                            if prepare.commitment() == *state_commitment {
+                            if prepare.commitment() == state_commitment {
                                 // `prepare` does not collide with the previously observed `Prepare`:
-                                // `prepare` is valid, and no further update is required
-                                return None;
+                                // `prepare` is valid, and no further update is required. Unless
+                                // `batch` was already applied, `state` does not reference `batch`
+                                if state_handle.batched() != Some((batch.root(), index)) {
+                                    released.push((batch.root(), index));
+                                }
+
+                                return (None, released);
                             } else {
                                 // `prepare` collides with a previously observed `Prepare`:
                                 // retrieve `Extract` to prove `Equivocation`
-                                let state_extract = match state_handle {
-                                    PrepareHandle::Batched { batch, index } => {
-                                        // `batch` is still in `database`, obtain `Extract` from there
-                                        batches.get(batch).unwrap().extract(*index)
-                                    }
-
-                                    // The batch was garbage collected, leaving a ready-made `Extract` behind
-                                    PrepareHandle::Standalone(extract) => extract.clone(),
-                                };
+                                let state_extract = state_handle.extract(batches);
 
                                 // Obtain conflicting `Extract` from `batch`, build `Equivocation`
                                 let extract = batch.extract(index);
                                 let equivocation = Equivocation::new(extract, state_extract);
 
-                                // State must be updated to reflect the equivocation
+                                // State must be updated to reflect the equivocation: neither
+                                // `handle` nor any handle in `pipeline` is referenced any longer
+                                released
+                                    .extend(pipeline.handles().filter_map(PrepareHandle::batched));
+                                released.push((batch.root(), index));
+
                                 State::Equivocated(equivocation)
                             }
+                        } else if let Some(exception) = stale.get(&prepare.id()) {
+                            // `prepare` is stale: `Prepare`s were previously witnessed beyond
+                            // `prepare.height()` (possibly evicting it from `pipeline`). `state`
+                            // is left untouched, and the evidence gathered above is provided
+                            // to prove misbehaviour / delay (unless `prepare` itself was
+                            // committed at its height, in which case no exception is needed)
+                            released.push((batch.root(), index));
+
+                            return (Some((exception.clone(), None)), released);
+                        } else if !continuous.contains_key(&prepare.id()) {
+                            // (*) `prepare` skips a height: no proof is available that the
+                            // client reached `prepare.height() - 1` (see `fetch_continuities`).
+                            // `state` is left untouched
+                            released.push((batch.root(), index));
+
+                            let exception = Exception::Discontinuous(prepare.id());
+                            return (Some((Some(exception), None)), released);
+                        } else if premature.contains(&prepare.id()) {
+                            // (**) `prepare` is beyond the pipeline window: no proof is
+                            // available that the client committed `prepare.height() -
+                            // pipeline_width` (see `fetch_continuities`). `state` is left
+                            // untouched
+                            released.push((batch.root(), index));
+
+                            let exception = Exception::Premature(prepare.id());
+                            return (Some((Some(exception), None)), released);
                         } else {
-                            // No `Prepare` was previously observed for this height: initialize
-                            // the state to `Consistent`.
-
-                            // (*) Remark: currently, no further check is performed on `prepare.height()`.
-                            // In the future, a proof will be optionally provided by the broker to
-                            // prove that the client successfully reached `prepare.height() - 1`.
-                            //
-                            // As a result, the following should apply:
-                            //  - If `prepare.height()` is greater than both the highest observed
-                            //    `Commit` for `prepare.id()` AND `state_height`, then the `state`
-                            //    should be updated as done below.
-                            //  - Otherwise, a higher `Commit` should be provided to the broker
-                            //    as evidence of misbehaviour / delay, and `prepare.id()` should
-                            //    be represented in `exceptions`.
-
-                            State::Consistent {
-                                height: prepare.height(),
-                                commitment: prepare.commitment(),
+                            // No `Prepare` was previously observed for this height, and the
+                            // client reached `prepare.height() - 1`: add `prepare` to `pipeline`.
+                            // The lowest `Prepare`s in `pipeline` beyond `pipeline_width` are
+                            // evicted, if superseded by a committed `Prepare`
+                            let mut pipeline = pipeline.clone();
+
+                            let evicted = pipeline.push(
+                                prepare.height(),
+                                prepare.commitment(),
                                 handle,
-                            }
+                                pipeline_width,
+                                |handle| handle.committed(batches),
+                            );
+
+                            released.extend(evicted.iter().filter_map(PrepareHandle::batched));
+
+                            State::Consistent(pipeline)
                         }
                     }
 
                     // `State::Equivocated` is absorbing and must not be updated
-                    equivocated => equivocated.clone(),
-                },
-                None => State::Consistent {
-                    // No `Prepare` was previously observed for this height: initialize
-                    // the state to `Consistent`
-
-                    // Remark: see above (*)
-                    height: prepare.height(),
-                    commitment: prepare.commitment(),
-                    handle,
+                    equivocated => {
+                        released.push((batch.root(), index));
+                        equivocated.clone()
+                    }
                 },
+                None => {
+                    if !continuous.contains_key(&prepare.id()) {
+                        // Remark: see above (*)
+                        released.push((batch.root(), index));
+
+                        let exception = Exception::Discontinuous(prepare.id());
+                        return (Some((Some(exception), None)), released);
+                    }
+
+                    if premature.contains(&prepare.id()) {
+                        // Remark: see above (**)
+                        released.push((batch.root(), index));
+
+                        let exception = Exception::Premature(prepare.id());
+                        return (Some((Some(exception), None)), released);
+                    }
+
+                    // No `Prepare` was previously observed for this `Id`: initialize
+                    // the state to `Consistent`, along with the `Continuity` of the
+                    // height below (if any, see `Pipeline`)
+                    let floor = continuous.get(&prepare.id()).cloned().flatten();
+
+                    State::Consistent(Pipeline::new(
+                        prepare.height(),
+                        prepare.commitment(),
+                        handle,
+                        floor,
+                    ))
+                }
             };
 
             // Extract, if available, the appropriate `Equivocation` from `state`
             let exception = if let State::Equivocated(equivocation) = &state {
-                Some(equivocation.clone())
+                Some(Exception::Equivocation(equivocation.clone()))
+            } else {
+                None
+            };
+
+            let record = if persistent {
+                Some(Record::PrepareState {
+                    id: prepare.id(),
+                    state: state.clone(),
+                })
             } else {
                 None
             };
 
-            // Update `states`, flag new state in `stale` to allow efficient
-            // flushing to `advertisements` (performed immediately before
-            // state transfer)
             states.insert(prepare.id(), state);
-            stale.insert(prepare.id());
 
             // If `exception` is `Some`, it is collected in `exceptions`
-            exception
+            (Some((exception, record)), released)
         },
-    );
+    )
+    .join();
+
+    let (updates, released): (Vec<_>, Vec<_>) = updates.into_iter().unzip();
+    let (exceptions, records): (Vec<_>, Vec<_>) = updates.into_iter().flatten().unzip();
+
+    database.journal.extend(records.into_iter().flatten());
+
+    // Flag updated states as `stale` to allow efficient flushing to
+    // `prepare.advertisements` (see `Prepare::flush_advertisements`).
+    // States left untouched by `batch` are flushed redundantly, to no effect
+    database
+        .prepare
+        .stale
+        .extend(batch.prepares().iter().map(|prepare| prepare.id()));
 
     // Store `batch` in `batches`
 
+    if persistent {
+        database.journal.record(Record::PrepareBatch(batch.clone()));
+    }
+
+    // If `batch` was already applied, its `BatchHolder` (along with its
+    // `BatchCommit`, if any) is preserved
+
     let root = batch.root();
-    let holder = BatchHolder::new(batch);
 
-    database.prepare.batches.insert(root, holder);
+    database
+        .prepare
+        .batches
+        .entry(root)
+        .or_insert_with(|| BatchHolder::new(batch));
+
+    // Unreferenced `BatchHolder`s are left to garbage collection
+
+    for (released_root, index) in released.into_iter().flatten() {
+        if let Some(holder) = database.prepare.batches.get_mut(&released_root) {
+            holder.unref(index);
+        }
+    }
 
     // Use `exceptions` to return an appropriate `BatchCommitShard`
 
-    let shard = BatchCommitShard::new(&keychain, view.identifier(), root, exceptions);
+    let shard = BatchCommitShard::new(
+        &keychain,
+        view.identifier(),
+        root,
+        exceptions.into_iter().flatten(),
+    );
 
     Ok(shard)
 }
+
+// Returns the evidence that `prepare`, below `pipeline`, is stale (`None` if `prepare`
+// itself is committed at its height: no exception is then needed). Unless no height
+// was ever evicted from `pipeline`, a `Prepare` in `pipeline` is committed (see
+// `Pipeline::push`) and provides evidence, otherwise `pipeline`'s floor does
+fn staleness(
+    pipeline: &Pipeline,
+    prepare: &Prepare,
+    batches: &HashMap<Hash, BatchHolder>,
+) -> Result<Option<Exception>, Top<ServePrepareError>> {
+    let committed = pipeline.handles().find_map(|handle| {
+        let proof = handle.commit_proof(batches)?;
+        let committed = handle.extract(batches).prepare().clone();
+
+        Some(Exception::Stale(committed, proof))
+    });
+
+    if committed.is_some() {
+        return Ok(committed);
+    }
+
+    // `floor` is at `pipeline.lowest().0 - 1`, hence at or above `prepare.height()`
+    let floor = pipeline
+        .floor()
+        .ok_or(ServePrepareError::StaleUnproven.into_top())
+        .spot(here!())?;
+
+    let committed = floor.prepare();
+
+    if committed.height() == prepare.height() && committed.commitment() == prepare.commitment() {
+        Ok(None)
+    } else {
+        Ok(Some(Exception::Overtaken(floor.clone())))
+    }
+}
@@ -0,0 +1,180 @@
+use buckets::Split;
+
+use crate::{
+    account::Id,
+    database::{prepare::State, Database},
+    discovery::Client,
+    prepare::{Continuity, WitnessedBatch},
+    processing::{
+        messages::{PrepareRequest, PrepareResponse},
+        processor::prepare::errors::ServePrepareError,
+    },
+};
+
+use doomstack::{here, Doom, ResultExt, Top};
+
+use rayon::prelude::*;
+
+use std::collections::{HashMap, HashSet};
+
+use talk::{net::Session, sync::voidable::Voidable};
+
+// Returns the `Id`s of all `Prepare`s in `batch` whose client is known (either from
+// `database`, or by a `Continuity` obtained from `session`) to have reached the
+// height below its `Prepare` (each along with its `Continuity`, if obtained from
+// `session`), along with the `Id`s of all `Prepare`s in `batch` that are beyond the
+// pipeline window (i.e., `pipeline_width` heights above the height up to which
+// their client is known to have committed)
+pub(in crate::processing::processor::prepare) async fn fetch_continuities(
+    discovery: &Client,
+    database: &Voidable<Database>,
+    session: &mut Session,
+    batch: &WitnessedBatch,
+    pipeline_width: u64,
+) -> Result<(HashMap<Id, Option<Continuity>>, HashSet<Id>), Top<ServePrepareError>> {
+    // A client is known to have reached `height - 1` if its `Account` was committed
+    // up to `height - 1`, or its `Prepare` at `height - 1` was witnessed (prepares are
+    // pipelined). A `Prepare` is within the window if its `Account` was committed up
+    // to `height - pipeline_width`. Collect the `Id`s of all `Prepare`s in `batch` for
+    // which either cannot be established from `database`. Because a `Pipeline` above
+    // height 1 is initialized only with the `Continuity` of the height below it (see
+    // `Pipeline::new`), this includes all `Id`s above height 1 without a state
+
+    let entries = Split::with_key(
+        batch
+            .prepares()
+            .iter()
+            .map(|prepare| (prepare.id(), prepare.height())),
+        |(id, _)| *id,
+    );
+
+    let mut missing = {
+        let mut database = database
+            .lock()
+            .pot(ServePrepareError::DatabaseVoid, here!())?;
+
+        // Each element of `heights` specifies whether or not its `Account` was committed
+        // up to `height - 1`, and whether or not `height` is beyond the window
+        let heights =
+            buckets::apply_sparse(&mut database.accounts, entries, |accounts, (id, height)| {
+                let committed = accounts.get(&id).map_or(0, |account| account.height());
+                let beyond = height > committed + pipeline_width.max(1);
+
+                if height > 1 {
+                    Some((id, height, height <= committed + 1, beyond))
+                } else {
+                    None
+                }
+            });
+
+        // Every element of `heights` has `height > 1`. Each element of `missing`
+        // specifies whether or not its client is known to have reached `height - 1`
+        buckets::apply_sparse(
+            &mut database.prepare.states,
+            Split::with_key(heights, |(id, _, _, _)| *id),
+            |states, (id, height, committed, beyond)| {
+                let continuous = match states.get(&id) {
+                    Some(State::Consistent(pipeline)) => {
+                        committed || pipeline.get(height - 1).is_some()
+                    }
+                    Some(State::Equivocated(_)) => committed,
+                    None => false,
+                };
+
+                if continuous && !beyond {
+                    None
+                } else {
+                    Some((id, height, continuous, beyond))
+                }
+            },
+        )
+    };
+
+    let mut continuous = batch
+        .prepares()
+        .iter()
+        .map(|prepare| (prepare.id(), None))
+        .collect::<HashMap<_, _>>();
+
+    let mut premature = HashSet::new();
+
+    for (id, _, known, beyond) in missing.iter() {
+        if !known {
+            continuous.remove(id);
+        }
+
+        if *beyond {
+            premature.insert(*id);
+        }
+    }
+
+    if missing.is_empty() {
+        return Ok((continuous, premature));
+    }
+
+    // Query `session` for the `Continuity` of each element of `missing`
+    // (`missing` is sorted by `Id` to match the order of `batch.prepares()`)
+
+    missing.sort_unstable();
+
+    let missing_ids = missing.iter().map(|(id, _, _, _)| *id).collect::<Vec<_>>();
+
+    session
+        .send(&PrepareResponse::MissingContinuities(missing_ids))
+        .await
+        .pot(ServePrepareError::ConnectionError, here!())?;
+
+    let request = session
+        .receive::<PrepareRequest>()
+        .await
+        .pot(ServePrepareError::ConnectionError, here!())?;
+
+    let continuities = match request {
+        PrepareRequest::Continuities(continuities) => continuities,
+        _ => {
+            return ServePrepareError::UnexpectedRequest.fail().spot(here!());
+        }
+    };
+
+    // This check is necessary to ensure that the subsequent `zip` will
+    // iterate fully over both `missing` and `continuities`
+    if continuities.len() != missing.len() {
+        return ServePrepareError::MalformedContinuities
+            .fail()
+            .spot(here!());
+    }
+
+    // Each `Some` element of `continuities` must be valid and prove that the
+    // client of the corresponding element of `missing` reached `height - 1`
+    // (`None` elements leave the corresponding `Prepare` discontinuous). A
+    // `Completion` also proves that `height` is within the window
+    let proven = missing
+        .par_iter()
+        .zip(continuities.par_iter())
+        .filter_map(|((id, height, _, _), continuity)| {
+            continuity.as_ref().map(|continuity| {
+                let entry = continuity.entry();
+
+                if entry.id != *id || entry.height + 1 != *height {
+                    ServePrepareError::MismatchedContinuity.fail().spot(here!())
+                } else {
+                    continuity
+                        .validate(discovery)
+                        .pot(ServePrepareError::InvalidContinuity, here!())?;
+
+                    Ok((*id, continuity))
+                }
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for (id, continuity) in proven {
+        if let Continuity::Completion(_) = continuity {
+            premature.remove(&id);
+        }
+
+        continuous.insert(id, Some(continuity.clone()));
+    }
+
+    Ok((continuous, premature))
+}
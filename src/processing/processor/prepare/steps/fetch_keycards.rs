@@ -1,19 +1,23 @@
-use buckets::Split;
+use buckets::{Buckets, Split};
 
 use crate::{
-    database::Database,
+    account::{Account, Id},
+    database::{storage::Record, Database},
     discovery::Client,
-    prepare::{Prepare, SignedBatch},
+    prepare::SignedBatch,
     processing::{
         messages::{PrepareRequest, PrepareResponse},
         processor::prepare::errors::ServePrepareError,
     },
+    signup::IdAssignment,
 };
 
 use doomstack::{here, Doom, ResultExt, Top};
 
 use rayon::prelude::*;
 
+use std::collections::HashMap;
+
 use talk::{crypto::KeyCard, net::Session, sync::voidable::Voidable};
 
 pub(in crate::processing::processor::prepare) async fn fetch_keycards(
@@ -21,45 +25,71 @@ pub(in crate::processing::processor::prepare) async fn fetch_keycards(
     database: &Voidable<Database>,
     session: &mut Session,
     batch: &SignedBatch,
+    pipeline_width: u64,
 ) -> Result<Vec<KeyCard>, Top<ServePrepareError>> {
     // For each element of `batch.prepares()`, retrieve from `database`,
     // if available, the `KeyCard` corresponding to the relevant `Id`
+    // at the relevant height
 
-    let ids = Split::with_key(batch.prepares().iter().map(Prepare::id), |id| *id);
+    let entries = Split::with_key(
+        batch
+            .prepares()
+            .iter()
+            .map(|prepare| (prepare.id(), prepare.height())),
+        |(id, _)| *id,
+    );
 
     let database_keycards = {
         let mut database = database
             .lock()
             .pot(ServePrepareError::DatabaseVoid, here!())?;
 
-        // Map each `id` in `ids` into:
-        //  - `Ok(keycard)`, if some `keycard` is available for `id`
-        //    in `database.assignments`
-        //  - `Err(id)` otherwise, signifying that an `IdAssignment`
-        //    for `id` must be obtained before `batch` can be validated
-        database
-            .assignments
-            .apply(ids, |assignments, id| match assignments.get(&id) {
-                Some(assignment) => Ok(assignment.keycard().clone()),
-                None => Err(id),
-            })
-    }
-    .join();
+        fn fields(
+            database: &mut Database,
+        ) -> (
+            &mut Buckets<HashMap<Id, IdAssignment>>,
+            &mut Buckets<HashMap<Id, Account>>,
+        ) {
+            (&mut database.assignments, &mut database.accounts)
+        }
 
-    // Extract all unknown `Id`s from `database_keycards`'s `Err` elements
+        let (assignments, accounts) = fields(&mut database);
 
-    let unknown_ids = database_keycards
-        .iter()
-        .map(Result::as_ref)
-        .filter_map(Result::err)
-        .copied()
-        .collect::<Vec<_>>();
+        // Map each `(id, height)` in `entries` into:
+        //  - `Ok(keycard)`, if the `KeyCard` of `id` at `height` is known, i.e., `id`
+        //    is assigned in `database.assignments` (or rotated in `database.accounts`),
+        //    and the commits of `id` applied in `database.accounts` are within
+        //    `pipeline_width` of `height` (see `Account::rotation`)
+        //  - `Err((id, height, assignment, lagging))` otherwise, where `assignment` is
+        //    the `IdAssignment` of `id` (if available in `database.assignments`), and
+        //    `lagging` flags whether the commits of `id` applied in `database.accounts`
+        //    lag behind `height`, in which case the rotation in effect at `height` must
+        //    be proven before `batch` can be validated
+        buckets::apply_attached(
+            (assignments, accounts),
+            &pipeline_width,
+            entries,
+            |(assignments, accounts), &pipeline_width, (id, height)| {
+                let account = accounts.get(&id);
+
+                let lagging =
+                    account.map(Account::height).unwrap_or(0) + pipeline_width.max(1) < height;
 
-    // If `unknown_ids` is empty, no further communication is required
+                let rotation = account.and_then(|account| account.rotation(height));
+
+                match (rotation, assignments.get(&id)) {
+                    (Some((_, keycard)), _) if !lagging => Ok(keycard.clone()),
+                    (None, Some(assignment)) if !lagging => Ok(assignment.keycard().clone()),
+                    (_, assignment) => Err((id, height, assignment.cloned(), lagging)),
+                }
+            },
+        )
+    }
+    .join();
 
-    if unknown_ids.is_empty() {
-        // Because `unknown_ids` is empty, all `database_keycards` are `Ok`,
-        // and can be safely unwrapped
+    // If all elements of `database_keycards` are `Ok`, no further communication is required
+
+    if database_keycards.iter().all(Result::is_ok) {
         let keycards = database_keycards
             .into_iter()
             .map(|keycard| keycard.unwrap())
@@ -68,8 +98,93 @@ pub(in crate::processing::processor::prepare) async fn fetch_keycards(
         return Ok(keycards);
     }
 
-    // Query for `unknown_ids`
+    let mut unknown_entries = database_keycards
+        .iter()
+        .filter_map(|keycard| keycard.as_ref().err().cloned())
+        .collect::<Vec<_>>();
+
+    // Obtain (and store in `database`) the `IdAssignment`s missing from `database`
+
+    let unknown_ids = unknown_entries
+        .iter()
+        .filter(|(_, _, assignment, _)| assignment.is_none())
+        .map(|(id, _, _, _)| *id)
+        .collect::<Vec<_>>();
+
+    if !unknown_ids.is_empty() {
+        let mut assignments = fetch_assignments(discovery, database, session, unknown_ids)
+            .await?
+            .into_iter();
+
+        // `fetch_assignments` returns exactly one `IdAssignment` for each `None` assignment
+        for (_, _, assignment, _) in unknown_entries
+            .iter_mut()
+            .filter(|(_, _, assignment, _)| assignment.is_none())
+        {
+            *assignment = assignments.next();
+        }
+    }
+
+    // Obtain the rotated `KeyCard` in effect for each lagging element of `unknown_entries`
+    // (`None` if the relevant `Id` was never rotated)
 
+    let lagging_entries = unknown_entries
+        .iter()
+        .filter(|(_, _, _, lagging)| *lagging)
+        .map(|(id, height, _, _)| (*id, *height))
+        .collect::<Vec<_>>();
+
+    let mut rotations = if lagging_entries.is_empty() {
+        Vec::new()
+    } else {
+        prove_rotations(discovery, session, lagging_entries, pipeline_width).await?
+    }
+    .into_iter();
+
+    // Select the `KeyCard` relevant to each element of `unknown_entries`: the proven
+    // rotation (if the relevant `Id` lags), or the assigned `KeyCard` if no rotation
+    // is in effect
+
+    let mut missing_keycards = unknown_entries
+        .into_iter()
+        .map(|(_, _, assignment, lagging)| {
+            // `rotations` has one element for each lagging element of `unknown_entries`
+            let rotation = if lagging {
+                rotations.next().unwrap()
+            } else {
+                None
+            };
+
+            // All `None` assignments were filled by `fetch_assignments`
+            rotation.unwrap_or_else(|| assignment.unwrap().keycard().clone())
+        })
+        .collect::<Vec<_>>()
+        .into_iter(); // Elements will be extracted in order from `missing_keycards`
+
+    // Use `missing_keycards` to fill the gaps in `database_keycards`
+
+    let keycards = database_keycards
+        .into_iter()
+        .map(|keycard| match keycard {
+            Ok(keycard) => keycard,
+            // `missing_keycards` has one element for each `Err` element of `database_keycards`
+            Err(_) => missing_keycards.next().unwrap(),
+        })
+        .collect::<Vec<_>>();
+
+    // Each element of `keycards` now cointains the `KeyCard` relevant
+    // to the corresponding element of `batch.prepares()`
+
+    Ok(keycards)
+}
+
+// Queries `session` for the `IdAssignment`s of `unknown_ids`, storing them in `database`
+async fn fetch_assignments(
+    discovery: &Client,
+    database: &Voidable<Database>,
+    session: &mut Session,
+    unknown_ids: Vec<Id>,
+) -> Result<Vec<IdAssignment>, Top<ServePrepareError>> {
     session
         .send(&PrepareResponse::UnknownIds(unknown_ids.clone())) // TODO: Remove unnecessary `clone`
         .await
@@ -89,7 +204,7 @@ pub(in crate::processing::processor::prepare) async fn fetch_keycards(
         }
     };
 
-    // Validate `id_assignments` against `unknown_ids`
+    // Validate `assignments` against `unknown_ids`
 
     // This check is necessary to ensure that the subsequent `zip` will
     // iterate fully over both `unknown_ids` and `assignments`
@@ -117,44 +232,110 @@ pub(in crate::processing::processor::prepare) async fn fetch_keycards(
         })
         .collect::<Result<_, _>>()?;
 
-    // Store `assignments` in `database`, retain only the `KeyCard`s
-    // necessary to fill the gaps in `database_keycards`
-
-    let assignments = assignments.into_iter().collect::<Split<_>>();
+    // Store `assignments` in `database`
 
-    let mut missing_keycards = {
+    {
         let mut database = database
             .lock()
             .pot(ServePrepareError::DatabaseVoid, here!())?;
 
-        // The following collects all `KeyCard`s in a `Vec` to avoid
-        // lingering references to `database` (which needs to be
-        // unlocked in a timely fashion)
+        database.journal.extend(
+            assignments
+                .iter()
+                .map(|assignment| Record::Assignment(assignment.clone())),
+        );
+
+        let assignments = assignments.iter().cloned().collect::<Split<_>>();
+
         database
             .assignments
             .apply(assignments, |assignments, assignment| {
-                let keycard = assignment.keycard().clone();
                 assignments.insert(assignment.id(), assignment);
-                keycard
             })
     }
-    .join()
-    .into_iter(); // Elements will be extracted in order from `missing_keycards`
+    .join();
 
-    // Use `missing_keycards` to fill the gaps in `database_keycards`
+    Ok(assignments)
+}
 
-    let keycards = database_keycards
-        .into_iter()
-        .map(|keycard| match keycard {
-            Ok(keycard) => keycard,
-            // Because `missing_keycards.len() == id_assignments.len() == unknown_ids.len()`,
-            // `missing_keycards.next()` is guaranteed to be `Some`
-            Err(_) => missing_keycards.next().unwrap(),
-        })
+// Queries `session` for the rotation in effect for each `(id, height)` in `lagging_entries`,
+// returning the rotated `KeyCard` (`None` if `id` was never rotated). Rotations are proven
+// by an `AccountProof` of `id` within `pipeline_width` of `height` (see `Account::rotation`)
+// along with the `KeyRotation` it attests, if any: a `KeyRotation` is never trusted to be
+// the latest, nor its absence trusted to mean that `id` was never rotated
+async fn prove_rotations(
+    discovery: &Client,
+    session: &mut Session,
+    lagging_entries: Vec<(Id, u64)>,
+    pipeline_width: u64,
+) -> Result<Vec<Option<KeyCard>>, Top<ServePrepareError>> {
+    let lagging_ids = lagging_entries
+        .iter()
+        .map(|(id, _)| *id)
         .collect::<Vec<_>>();
 
-    // Each element of `keycards` now cointains the `KeyCard` relevant
-    // to the corresponding element of `batch.prepares()`
+    session
+        .send(&PrepareResponse::LaggingIds(lagging_ids))
+        .await
+        .pot(ServePrepareError::ConnectionError, here!())?;
 
-    Ok(keycards)
+    // Receive requested `AccountProof`s (and `KeyRotation`s)
+
+    let request = session
+        .receive::<PrepareRequest>()
+        .await
+        .pot(ServePrepareError::ConnectionError, here!())?;
+
+    let rotations = match request {
+        PrepareRequest::Rotations(rotations) => rotations,
+        _ => {
+            return ServePrepareError::UnexpectedRequest.fail().spot(here!());
+        }
+    };
+
+    // This check is necessary to ensure that the subsequent `zip` will
+    // iterate fully over both `lagging_entries` and `rotations`
+    if rotations.len() != lagging_entries.len() {
+        return ServePrepareError::MalformedRotations.fail().spot(here!());
+    }
+
+    // Check that each element of `rotations` proves the rotation in effect at the
+    // height of the corresponding element of `lagging_entries`
+    lagging_entries
+        .par_iter()
+        .zip(rotations.par_iter())
+        .map(|((id, height), (proof, rotation))| {
+            let summary = proof
+                .validate(*id)
+                .pot(ServePrepareError::InvalidAccountProof, here!())?;
+
+            let proven = summary.map(|summary| summary.height).unwrap_or(0);
+
+            if proven + pipeline_width.max(1) < *height {
+                return ServePrepareError::StaleAccountProof.fail().spot(here!());
+            }
+
+            // `rotation` must be the latest rotation attested by `proof`, and be in effect
+            // at `height` (a rotation attested past `height` leaves the `KeyCard` in effect
+            // at `height` unproven)
+            match (summary.and_then(|summary| summary.rotation), rotation) {
+                (None, None) => Ok(None),
+                (Some((since, identity)), Some(rotation))
+                    if rotation.id() == *id
+                        && rotation.height() == since
+                        && rotation.keycard().identity() == identity
+                        && since <= *height =>
+                {
+                    rotation
+                        .validate(discovery)
+                        .pot(ServePrepareError::InvalidKeyRotation, here!())?;
+
+                    Ok(Some(rotation.keycard().clone()))
+                }
+                _ => ServePrepareError::MismatchedKeyRotation
+                    .fail()
+                    .spot(here!()),
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()
 }
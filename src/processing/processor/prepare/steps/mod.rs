@@ -1,10 +1,12 @@
 mod apply_batch;
+mod fetch_continuities;
 mod fetch_keycards;
 mod trade_witnesses;
 mod validate_signed;
 mod witnessed_batch;
 
 pub(in crate::processing::processor::prepare) use apply_batch::apply_batch;
+pub(in crate::processing::processor::prepare) use fetch_continuities::fetch_continuities;
 pub(in crate::processing::processor::prepare) use fetch_keycards::fetch_keycards;
 pub(in crate::processing::processor::prepare) use trade_witnesses::trade_witnesses;
 pub(in crate::processing::processor::prepare) use validate_signed::validate_signed;
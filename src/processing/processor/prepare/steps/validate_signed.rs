@@ -21,6 +21,7 @@ pub(in crate::processing::processor::prepare) async fn validate_signed(
     database: &Voidable<Database>,
     session: &mut Session,
     batch: &SignedBatch,
+    pipeline_width: u64,
 ) -> Result<MultiSignature, Top<ServePrepareError>> {
     // Verify that `batch.prepares()` is strictly increasing by `Id`
     // (this ensures searchability and non-duplication of `Id`s)
@@ -34,9 +35,11 @@ pub(in crate::processing::processor::prepare) async fn validate_signed(
 
     // Retrieve the `KeyCard` relevant to each of the elements of `batch.prepares()`.
     // If any `KeyCard` is missing from `database`, query `session` for the necessary
-    // `IdAssignment`s (store in `database` all newly discovered `IdAssignments`).
+    // `IdAssignment`s (store in `database` all newly discovered `IdAssignments`), and
+    // for proofs of the rotations `database` lags behind.
 
-    let keycards = steps::fetch_keycards(discovery, database, session, batch).await?;
+    let keycards =
+        steps::fetch_keycards(discovery, database, session, batch, pipeline_width).await?;
 
     // Check all individual signatures in `batch` while collecting signers to
     // `batch`'s reduction statement
@@ -22,6 +22,7 @@ pub(in crate::processing::processor::prepare) async fn witnessed_batch(
     database: &Voidable<Database>,
     session: &mut Session,
     prepares: Vector<Prepare>,
+    pipeline_width: u64,
 ) -> Result<WitnessedBatch, Top<ServePrepareError>> {
     // Receive either:
     // - A witness, required to directly assemble a `WitnessedBatch`
@@ -46,8 +47,15 @@ pub(in crate::processing::processor::prepare) async fn witnessed_batch(
             let batch = SignedBatch::new(prepares, reduction_signature, individual_signatures);
 
             // Validate `batch` to obtain a witness shard
-            let witness_shard =
-                steps::validate_signed(keychain, discovery, database, session, &batch).await?;
+            let witness_shard = steps::validate_signed(
+                keychain,
+                discovery,
+                database,
+                session,
+                &batch,
+                pipeline_width,
+            )
+            .await?;
 
             // Trade `witness_shard` for a full witness (which aggregates the witness shards
             // of a plurality of replicas in `view`)
@@ -0,0 +1,11 @@
+use doomstack::Doom;
+
+#[derive(Doom)]
+pub(in crate::processing::processor::query) enum ServeQueryError {
+    #[doom(description("Connection error"))]
+    ConnectionError,
+    #[doom(description("Database void"))]
+    DatabaseVoid,
+    #[doom(description("Failed to export inclusion proof"))]
+    ExportFailed,
+}
@@ -0,0 +1,53 @@
+use buckets::Split;
+
+use crate::{
+    account::Id,
+    database::Database,
+    processing::{
+        messages::QueryResponse, processor::query::errors::ServeQueryError,
+        processor_settings::Query,
+    },
+};
+
+use doomstack::{here, ResultExt, Top};
+
+use std::ops::Range;
+
+use talk::sync::voidable::Voidable;
+
+pub(in crate::processing::processor::query) fn history(
+    database: &Voidable<Database>,
+    id: Id,
+    heights: Range<u64>,
+    settings: &Query,
+) -> Result<QueryResponse, Top<ServeQueryError>> {
+    // At most `settings.max_receipts` `Receipt`s are returned, by increasing
+    // height (further `Receipt`s can be queried from the last height returned)
+
+    // An empty (or reversed) `heights` selects no `Receipt`
+    // (`BTreeMap::range` panics if `heights.start > heights.end`)
+    if heights.is_empty() {
+        return Ok(QueryResponse::History(Vec::new()));
+    }
+
+    let receipts = database
+        .lock()
+        .pot(ServeQueryError::DatabaseVoid, here!())?
+        .history
+        .apply(Split::with_key([id], |id| *id), |history, id| {
+            history
+                .get(&id)
+                .map(|receipts| {
+                    receipts
+                        .range(heights.clone())
+                        .take(settings.max_receipts)
+                        .map(|(_, receipt)| receipt.clone())
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default()
+        })
+        .join()
+        .remove(0);
+
+    Ok(QueryResponse::History(receipts))
+}
@@ -0,0 +1,11 @@
+mod history;
+mod motion;
+mod ping;
+mod proof;
+mod summaries;
+
+pub(in crate::processing::processor::query) use history::history;
+pub(in crate::processing::processor::query) use motion::motion;
+pub(in crate::processing::processor::query) use ping::ping;
+pub(in crate::processing::processor::query) use proof::proof;
+pub(in crate::processing::processor::query) use summaries::summaries;
@@ -0,0 +1,37 @@
+use crate::{
+    crypto::Identify,
+    database::Database,
+    motion::PassShard,
+    processing::{messages::QueryResponse, processor::query::errors::ServeQueryError},
+    view::View,
+};
+
+use doomstack::{here, ResultExt, Top};
+
+use talk::{
+    crypto::{primitives::hash::Hash, KeyChain},
+    sync::voidable::Voidable,
+};
+
+pub(in crate::processing::processor::query) fn motion(
+    keychain: &KeyChain,
+    view: &View,
+    database: &Voidable<Database>,
+    motion: Hash,
+    pass_threshold: u64,
+) -> Result<QueryResponse, Top<ServeQueryError>> {
+    let tally = database
+        .lock()
+        .pot(ServeQueryError::DatabaseVoid, here!())?
+        .motions
+        .tally(&motion);
+
+    // Once `tally` reaches the threshold, attest that `motion` passed
+    let shard = if tally >= pass_threshold {
+        Some(PassShard::new(keychain, view.identifier(), motion))
+    } else {
+        None
+    };
+
+    Ok(QueryResponse::Motion { tally, shard })
+}
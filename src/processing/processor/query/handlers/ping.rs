@@ -0,0 +1,18 @@
+use crate::processing::{messages::QueryResponse, processor::query::errors::ServeQueryError};
+
+use doomstack::{here, ResultExt, Top};
+
+use talk::net::Session;
+
+pub(in crate::processing::processor::query) async fn ping(
+    mut session: Session,
+) -> Result<(), Top<ServeQueryError>> {
+    session
+        .send(&QueryResponse::Pong)
+        .await
+        .pot(ServeQueryError::ConnectionError, here!())?;
+
+    session.end();
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use buckets::Split;
+
+use crate::{
+    account::{Id, State},
+    crypto::Identify,
+    database::Database,
+    processing::{messages::QueryResponse, processor::query::errors::ServeQueryError},
+    query::AccountProofShard,
+    view::View,
+};
+
+use doomstack::{here, ResultExt, Top};
+
+use talk::{crypto::KeyChain, sync::voidable::Voidable};
+
+pub(in crate::processing::processor::query) fn proof(
+    keychain: &KeyChain,
+    view: &View,
+    database: &Voidable<Database>,
+    id: Id,
+) -> Result<QueryResponse, Top<ServeQueryError>> {
+    let (inclusion, account) = {
+        let mut database = database
+            .lock()
+            .pot(ServeQueryError::DatabaseVoid, here!())?;
+
+        // Both `inclusion` and `account` are obtained under the same lock
+
+        let inclusion = database
+            .imminent
+            .export([&id])
+            .pot(ServeQueryError::ExportFailed, here!())?;
+
+        let account = database
+            .accounts
+            .apply(Split::with_key([id], |id| *id), |accounts, id| {
+                accounts.get(&id).cloned()
+            })
+            .join()
+            .remove(0);
+
+        (inclusion, account)
+    };
+
+    // `database.imminent` is updated after `database.accounts` (see
+    // `commit::steps::apply_batch`): `account`'s `CorrectState` is provided
+    // only if it is the one summarized in `inclusion`
+    let state = account.and_then(|account| {
+        let summary = account.summarize();
+
+        let imminent = inclusion.get(&id).ok().flatten().copied();
+
+        match account.state() {
+            State::Correct(state) if imminent == Some(summary) => Some(state.clone()),
+            _ => None,
+        }
+    });
+
+    let shard = AccountProofShard::new(keychain, view.identifier(), inclusion, state);
+
+    Ok(QueryResponse::Proof(shard))
+}
@@ -0,0 +1,34 @@
+use buckets::Split;
+
+use crate::{
+    account::{Account, Id},
+    database::Database,
+    processing::{messages::QueryResponse, processor::query::errors::ServeQueryError},
+};
+
+use doomstack::{here, ResultExt, Top};
+
+use talk::sync::voidable::Voidable;
+
+pub(in crate::processing::processor::query) fn summaries(
+    database: &Voidable<Database>,
+    ids: Vec<Id>,
+) -> Result<QueryResponse, Top<ServeQueryError>> {
+    let ids = Split::with_key(ids, |id| *id);
+
+    // Map each `id` in `ids` into its `AccountSummary` (`None` if
+    // no operation was ever applied to `id`'s `Account`)
+
+    let summaries = {
+        let mut database = database
+            .lock()
+            .pot(ServeQueryError::DatabaseVoid, here!())?;
+
+        database.accounts.apply(ids, |accounts, id| {
+            accounts.get(&id).map(Account::summarize)
+        })
+    }
+    .join();
+
+    Ok(QueryResponse::Summaries(summaries))
+}
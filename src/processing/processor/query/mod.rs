@@ -0,0 +1,3 @@
+mod errors;
+mod handlers;
+mod query;
@@ -0,0 +1,233 @@
+use crate::{
+    database::Database,
+    processing::{
+        messages::QueryRequest,
+        processor::query::{errors::ServeQueryError, handlers},
+        processor_settings::Query,
+        Processor,
+    },
+    view::View,
+};
+
+use doomstack::{here, ResultExt, Top};
+
+use std::sync::Arc;
+
+use talk::{
+    crypto::KeyChain,
+    net::{Listener, Session, SessionListener},
+    sync::{fuse::Fuse, voidable::Voidable},
+};
+
+impl Processor {
+    pub(in crate::processing) async fn run_query<L>(
+        keychain: KeyChain,
+        view: View,
+        database: Arc<Voidable<Database>>,
+        listener: L,
+        settings: Query,
+        pass_threshold: u64,
+    ) where
+        L: Listener,
+    {
+        let mut listener = SessionListener::new(listener);
+        let fuse = Fuse::new();
+
+        loop {
+            let (_, session) = listener.accept().await;
+
+            let keychain = keychain.clone();
+            let view = view.clone();
+            let database = database.clone();
+            let settings = settings.clone();
+
+            fuse.spawn(async move {
+                let _ = Processor::serve_query(
+                    keychain,
+                    view,
+                    database,
+                    session,
+                    settings,
+                    pass_threshold,
+                )
+                .await;
+            });
+        }
+    }
+
+    async fn serve_query(
+        keychain: KeyChain,
+        view: View,
+        database: Arc<Voidable<Database>>,
+        mut session: Session,
+        settings: Query,
+        pass_threshold: u64,
+    ) -> Result<(), Top<ServeQueryError>> {
+        let request = session
+            .receive::<QueryRequest>()
+            .await
+            .pot(ServeQueryError::ConnectionError, here!())?;
+
+        let response = match request {
+            QueryRequest::Ping => return handlers::ping(session).await,
+            QueryRequest::Summaries(ids) => handlers::summaries(database.as_ref(), ids)?,
+            QueryRequest::Proof(id) => handlers::proof(&keychain, &view, database.as_ref(), id)?,
+            QueryRequest::Motion(motion) => {
+                handlers::motion(&keychain, &view, database.as_ref(), motion, pass_threshold)?
+            }
+            QueryRequest::History { id, heights } => {
+                handlers::history(database.as_ref(), id, heights, &settings)?
+            }
+        };
+
+        session
+            .send(&response)
+            .await
+            .pot(ServeQueryError::ConnectionError, here!())?;
+
+        session.end();
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        account::{Entry, Operation, Outcome},
+        commit::Payload,
+        database::Receipt,
+        prepare::Continuity,
+        processing::{test::System, ProcessorSettings},
+        signup::{IdRequest, SignupSettings},
+    };
+
+    use std::time::Duration;
+
+    use talk::crypto::{primitives::hash, KeyChain};
+
+    use tokio::time;
+
+    #[tokio::test]
+    async fn unknown_account() {
+        let System {
+            brokers,
+            processors,
+            ..
+        } = System::setup(4, 1).await;
+
+        let replica = processors[0].0.keycard().identity();
+
+        let summaries = brokers[0].summaries(replica, vec![0, 1, 2]).await;
+        assert_eq!(summaries, vec![None, None, None]);
+
+        let proof = brokers[0].account_proof(0).await;
+
+        assert_eq!(proof.validate(0).unwrap(), None);
+        assert!(proof.state().is_none());
+    }
+
+    #[tokio::test]
+    async fn unsupported_motion() {
+        let System {
+            brokers,
+            processors,
+            ..
+        } = System::setup(4, 1).await;
+
+        let replica = processors[0].0.keycard().identity();
+        let motion = hash::hash(&0u64).unwrap();
+
+        let (tally, shard) = brokers[0].motion(replica, motion).await;
+
+        assert_eq!(tally, 0);
+        assert!(shard.is_none());
+    }
+
+    #[tokio::test]
+    async fn unknown_history() {
+        let System {
+            brokers,
+            processors,
+            ..
+        } = System::setup(4, 1).await;
+
+        let replica = processors[0].0.keycard().identity();
+
+        let receipts = brokers[0].history(replica, 0, 0..u64::MAX).await;
+        assert!(receipts.is_empty());
+    }
+
+    #[tokio::test]
+    async fn history() {
+        let mut settings = ProcessorSettings::default();
+        settings.collect.interval = Duration::from_millis(100);
+
+        let System {
+            view,
+            brokers,
+            processors,
+            ..
+        } = System::setup_with_settings(4, 1, settings).await;
+
+        let allocator = processors[0].0.keycard().identity();
+
+        let client = KeyChain::random();
+        let request = IdRequest::new(
+            &client,
+            &view,
+            allocator,
+            SignupSettings::default().work_difficulty,
+        );
+
+        let assignment = brokers[0].signup(vec![request]).await.remove(0).unwrap();
+        let id = assignment.id();
+
+        // Commit one `Payload` at each height from 1 to 3
+
+        let mut continuity = None;
+
+        for height in 1..=3 {
+            let payload = Payload::new(
+                Entry { id, height },
+                Operation::support(hash::hash(&height).unwrap()),
+            );
+
+            let completion = brokers[0]
+                .settle(&[(client.clone(), assignment.clone(), payload, continuity)])
+                .await
+                .remove(0);
+
+            continuity = Some(Continuity::Completion(completion));
+        }
+
+        let replica = processors[0].0.keycard().identity();
+
+        fn heights(receipts: &[Receipt]) -> Vec<u64> {
+            receipts
+                .iter()
+                .map(|receipt| {
+                    assert!(matches!(receipt.outcome(), Outcome::Success));
+                    receipt.entry().height
+                })
+                .collect()
+        }
+
+        let receipts = brokers[0].history(replica, id, 0..u64::MAX).await;
+        assert_eq!(heights(&receipts), vec![1, 2, 3]);
+
+        let receipts = brokers[0].history(replica, id, 2..3).await;
+        assert_eq!(heights(&receipts), vec![2]);
+
+        // Reversed ranges select no `Receipt`
+        let receipts = brokers[0].history(replica, id, 3..2).await;
+        assert!(receipts.is_empty());
+
+        // Committed batches are garbage collected, `Receipt`s are kept
+
+        time::sleep(Duration::from_millis(500)).await;
+
+        let receipts = brokers[0].history(replica, id, 0..u64::MAX).await;
+        assert_eq!(heights(&receipts), vec![1, 2, 3]);
+    }
+}
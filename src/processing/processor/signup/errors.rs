@@ -6,6 +6,8 @@ pub(in crate::processing::processor::signup) enum ServeSignupError {
     ConnectionError,
     #[doom(description("Database void"))]
     DatabaseVoid,
+    #[doom(description("Failed to persist database"))]
+    PersistFailed,
     #[doom(description("Invalid request"))]
     InvalidRequest,
     #[doom(description("Foreign view"))]
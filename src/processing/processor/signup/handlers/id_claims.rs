@@ -1,6 +1,6 @@
 use crate::{
     crypto::Identify,
-    database::Database,
+    database::{storage::Record, Database},
     processing::{
         messages::SignupResponse, processor::signup::errors::ServeSignupError,
         processor_settings::Signup,
@@ -73,7 +73,11 @@ pub(in crate::processing::processor::signup) fn id_claims(
                     // `claim.id()` will be inserted twice in `database.signup.claimed`
                     // (which is harmless) and the `IdAssignment` will be repeated
                     let _ = transaction.insert(claim.id());
-                    Ok(IdAssignment::certify(&keychain, &claim))
+                    let assignment = IdAssignment::certify(&keychain, &claim);
+
+                    database.journal.record(Record::Claim(claim));
+
+                    Ok(assignment)
                 } else {
                     // `claim.id()` was previously claimed by another client: return
                     // the relevant `IdClaim` as proof of conflict
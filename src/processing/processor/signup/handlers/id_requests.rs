@@ -1,6 +1,6 @@
 use crate::{
     crypto::Identify,
-    database::Database,
+    database::{storage::Record, Database},
     processing::{
         messages::SignupResponse, processor::signup::errors::ServeSignupError,
         processor_settings::Signup,
@@ -151,5 +151,10 @@ fn allocate_id(
         .allocations
         .insert(request.client().identity(), id);
 
+    database.journal.record(Record::Allocation {
+        identity: request.client().identity(),
+        id,
+    });
+
     IdAllocation::new(&keychain, &request, id)
 }
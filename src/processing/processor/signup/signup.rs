@@ -80,6 +80,12 @@ impl Processor {
             }
         };
 
+        // Persist allocations and claims before `response` vouches for them
+
+        Processor::persist(database.as_ref())
+            .await
+            .pot(ServeSignupError::PersistFailed, here!())?;
+
         session
             .send(&response)
             .await
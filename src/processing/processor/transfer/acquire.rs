@@ -0,0 +1,626 @@
+use buckets::{Buckets, Split};
+
+use crate::{
+    account::{Account, AccountSummary, Id},
+    database::{
+        prepare::{Advertisement, BatchHolder, Pipeline, PrepareHandle, State},
+        storage::Record,
+        Database, Zebras,
+    },
+    discovery::Client,
+    prepare::Equivocation,
+    processing::{
+        messages::{TransferRequest, TransferResponse},
+        processor::transfer::errors::{AcquireError, FetchError},
+        Processor, ProcessorSettings,
+    },
+    signup::{IdAssignment, IdClaim},
+    view::View,
+};
+
+use doomstack::{here, Doom, ResultExt, Top};
+
+use futures::stream::{FuturesUnordered, StreamExt};
+
+use rayon::prelude::*;
+
+use serde::de::DeserializeOwned;
+
+use std::collections::HashMap;
+
+use talk::{
+    crypto::{primitives::hash::Hash, Identity},
+    net::{Session, SessionConnector},
+};
+
+use zebra::database::{
+    Collection, CollectionStatus, CollectionTransaction, Question, Table, TableStatus,
+    TableTransaction,
+};
+
+// Local zebra structures, against which remote frontiers are diffed
+struct Snapshot {
+    imminent: Table<Id, AccountSummary>,
+    advertisements: Table<Id, Advertisement>,
+    claimed: Collection<Id>,
+}
+
+// Elements a single member of the source view holds, and the joiner lacks
+struct Frontier {
+    accounts: Vec<(Id, Account)>,
+    pipelines: Vec<(Id, Pipeline)>,
+    claims: Vec<IdClaim>,
+    assignments: Vec<IdAssignment>,
+}
+
+impl Processor {
+    /// Acquires into `database` the state held by the members of `source`
+    /// (`connector` must be registered to `source`'s transfer context).
+    ///
+    /// The frontiers of a quorum of members are fetched. `IdAssignment`s are
+    /// certified, and adopted as soon as any member provides them. `Account`s,
+    /// prepare states and `IdClaim`s, instead, are adopted only if vouched for
+    /// by a plurality of members, and hence by at least one correct member.
+    pub(crate) async fn acquire(
+        discovery: &Client,
+        source: &View,
+        database: &mut Database,
+        connector: &SessionConnector,
+        settings: &ProcessorSettings,
+    ) -> Result<(), Top<AcquireError>> {
+        let work_difficulty = settings.signup.signup_settings.work_difficulty;
+
+        // Local advertisements are diffed against remote ones: they must be up to date
+        database.prepare.flush_advertisements();
+
+        let frontiers = {
+            let mut fetches = source
+                .members()
+                .keys()
+                .map(|member| {
+                    Processor::fetch(
+                        discovery,
+                        &database.families,
+                        connector,
+                        *member,
+                        Snapshot {
+                            imminent: database.imminent.clone(),
+                            advertisements: database.prepare.advertisements.clone(),
+                            claimed: database.signup.claimed.clone(),
+                        },
+                        work_difficulty,
+                    )
+                })
+                .collect::<FuturesUnordered<_>>();
+
+            let mut frontiers = Vec::new();
+
+            while let Some(result) = fetches.next().await {
+                if let Ok(frontier) = result {
+                    frontiers.push(frontier);
+
+                    if frontiers.len() == source.quorum() {
+                        break;
+                    }
+                }
+            }
+
+            frontiers
+        };
+
+        if frontiers.len() < source.quorum() {
+            return AcquireError::QuorumUnreachable.fail().spot(here!());
+        }
+
+        // Tally `frontiers`
+
+        let mut accounts = Vec::new();
+        let mut pipelines = Vec::new();
+        let mut claims = Vec::new();
+        let mut assignments = HashMap::new();
+
+        for frontier in frontiers {
+            accounts.extend(frontier.accounts);
+            pipelines.extend(frontier.pipelines);
+            claims.extend(frontier.claims.into_iter().map(|claim| (claim.id(), claim)));
+
+            assignments.extend(
+                frontier
+                    .assignments
+                    .into_iter()
+                    .map(|assignment| (assignment.id(), assignment)),
+            );
+        }
+
+        let plurality = source.plurality();
+
+        // The highest vouched `Account` is adopted (a correct member might lag behind)
+        let accounts = vouched(accounts, Account::summarize, plurality)
+            .into_iter()
+            .filter_map(|(id, accounts)| {
+                accounts
+                    .into_iter()
+                    .max_by_key(Account::height)
+                    .map(|account| (id, account))
+            })
+            .collect::<Vec<_>>();
+
+        // The highest vouched `Pipeline` is adopted. `State::Equivocated` is not
+        // transferred: its `Equivocation` is fetched upon synchronization (see
+        // `Processor::synchronize`)
+        let pipelines = vouched(
+            pipelines,
+            |pipeline| {
+                pipeline
+                    .iter()
+                    .map(|(height, commitment, _)| (height, commitment))
+                    .collect::<Vec<_>>()
+            },
+            plurality,
+        )
+        .into_iter()
+        .filter_map(|(id, pipelines)| {
+            pipelines
+                .into_iter()
+                .max_by_key(|pipeline| pipeline.highest().0)
+                .map(|pipeline| (id, pipeline))
+        })
+        .collect::<Vec<_>>();
+
+        let claims = vouched(claims, IdClaim::client, plurality)
+            .into_iter()
+            .filter_map(|(_, mut claims)| claims.pop())
+            .collect::<Vec<_>>();
+
+        // Update `database`
+
+        let adopted = buckets::apply_sparse(
+            &mut database.accounts,
+            Split::with_key(accounts, |(id, _)| *id),
+            |accounts, (id, account)| {
+                // A local `Account` is replaced only if it lags behind
+                if accounts
+                    .get(&id)
+                    .map_or(false, |local| local.height() >= account.height())
+                {
+                    return None;
+                }
+
+                let replaced = accounts
+                    .insert(id, account.clone())
+                    .map(|local| local.motions())
+                    .unwrap_or_default();
+
+                Some((id, account, replaced))
+            },
+        );
+
+        let mut transaction = TableTransaction::new();
+
+        for (id, account, replaced) in adopted {
+            database.motions.update(&replaced, &account.motions());
+
+            transaction.set(id, account.summarize()).unwrap();
+            database.journal.record(Record::Account { id, account });
+        }
+
+        database.imminent.execute(transaction);
+
+        fn fields(
+            database: &mut Database,
+        ) -> (
+            &mut Buckets<HashMap<Id, State>>,
+            &HashMap<Hash, BatchHolder>,
+        ) {
+            (&mut database.prepare.states, &database.prepare.batches)
+        }
+
+        let (states, batches) = fields(database);
+
+        let adopted = buckets::apply_sparse_attached(
+            states,
+            batches,
+            Split::with_key(pipelines, |(id, _)| *id),
+            |states, batches, (id, pipeline)| {
+                let local = match states.get(&id) {
+                    Some(State::Consistent(local)) => local,
+                    // `State::Equivocated` is absorbing and must not be updated
+                    Some(State::Equivocated(_)) => return None,
+                    None => {
+                        let state = State::Consistent(pipeline);
+                        states.insert(id, state.clone());
+
+                        return Some((id, state, Vec::new()));
+                    }
+                };
+
+                // A vouched `Prepare` conflicting with a local one proves an equivocation
+                let conflict = pipeline.iter().find_map(|(height, commitment, handle)| {
+                    local
+                        .get(height)
+                        .filter(|(local_commitment, _)| *local_commitment != commitment)
+                        .map(|(_, local_handle)| (handle.extract(batches), local_handle))
+                });
+
+                // A local `Pipeline` is replaced only if it lags behind
+                let state = match conflict {
+                    Some((extract, local_handle)) => State::Equivocated(Equivocation::new(
+                        extract,
+                        local_handle.extract(batches),
+                    )),
+                    None if pipeline.highest().0 > local.highest().0 => State::Consistent(pipeline),
+                    None => return None,
+                };
+
+                // Either way, no local handle is referenced any longer
+                let released = local
+                    .handles()
+                    .filter_map(PrepareHandle::batched)
+                    .collect::<Vec<_>>();
+
+                states.insert(id, state.clone());
+
+                Some((id, state, released))
+            },
+        );
+
+        for (id, state, released) in adopted {
+            for (root, index) in released {
+                if let Some(holder) = database.prepare.batches.get_mut(&root) {
+                    holder.unref(index);
+                }
+            }
+
+            database.prepare.stale.insert(id);
+            database.journal.record(Record::PrepareState { id, state });
+        }
+
+        database.prepare.flush_advertisements();
+
+        let mut transaction = CollectionTransaction::new();
+
+        for claim in claims {
+            // Previously stored `IdClaim`s are retained
+            if !database.signup.claims.contains_key(&claim.id()) {
+                let _ = transaction.insert(claim.id());
+
+                database.journal.record(Record::Claim(claim.clone()));
+                database.signup.claims.insert(claim.id(), claim);
+            }
+        }
+
+        database.signup.claimed.execute(transaction);
+
+        let adopted = buckets::apply_sparse(
+            &mut database.assignments,
+            Split::with_key(assignments, |(id, _)| *id),
+            |assignments, (id, assignment)| {
+                if assignments.contains_key(&id) {
+                    return None;
+                }
+
+                assignments.insert(id, assignment.clone());
+                Some(assignment)
+            },
+        );
+
+        database
+            .journal
+            .extend(adopted.into_iter().map(Record::Assignment));
+
+        Ok(())
+    }
+
+    async fn fetch(
+        discovery: &Client,
+        families: &Zebras,
+        connector: &SessionConnector,
+        member: Identity,
+        mut snapshot: Snapshot,
+        work_difficulty: u64,
+    ) -> Result<Frontier, Top<FetchError>> {
+        let mut session = connector
+            .connect(member)
+            .await
+            .pot(FetchError::ConnectFailed, here!())?;
+
+        session
+            .send(&TransferRequest::Frontier)
+            .await
+            .pot(FetchError::ConnectionError, here!())?;
+
+        // Receive `member`'s zebra structures, in the order they are sent
+        // by `Processor::serve_transfer`
+
+        let mut remote_imminent = sync(
+            &mut session,
+            families.ids_to_account_summaries.receive(),
+            |receiver, answer| match receiver
+                .learn(answer)
+                .pot(FetchError::MalformedAnswer, here!())?
+            {
+                TableStatus::Complete(table) => Ok(Ok(table)),
+                TableStatus::Incomplete(receiver, question) => Ok(Err((receiver, question))),
+            },
+        )
+        .await?;
+
+        let mut remote_advertisements = sync(
+            &mut session,
+            families.ids_to_prepare_advertisements.receive(),
+            |receiver, answer| match receiver
+                .learn(answer)
+                .pot(FetchError::MalformedAnswer, here!())?
+            {
+                TableStatus::Complete(table) => Ok(Ok(table)),
+                TableStatus::Incomplete(receiver, question) => Ok(Err((receiver, question))),
+            },
+        )
+        .await?;
+
+        let mut remote_claimed =
+            sync(
+                &mut session,
+                families.ids.receive(),
+                |receiver, answer| match receiver
+                    .learn(answer)
+                    .pot(FetchError::MalformedAnswer, here!())?
+                {
+                    CollectionStatus::Complete(collection) => Ok(Ok(collection)),
+                    CollectionStatus::Incomplete(receiver, question) => {
+                        Ok(Err((receiver, question)))
+                    }
+                },
+            )
+            .await?;
+
+        // Diff local and remote structures to identify the elements
+        // the joiner is missing (or holds in a different version)
+
+        let account_ids = Table::diff(&mut snapshot.imminent, &mut remote_imminent)
+            .into_iter()
+            .filter_map(|(id, (_, remote))| remote.map(|_| id))
+            .collect::<Vec<_>>();
+
+        // `State::Equivocated` is not transferred (see `Processor::acquire`)
+        let state_ids = Table::diff(&mut snapshot.advertisements, &mut remote_advertisements)
+            .into_iter()
+            .filter_map(|(id, (_, remote))| match remote {
+                Some(Advertisement::Consistent { .. }) => Some(id),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let claim_ids = Collection::diff(&mut snapshot.claimed, &mut remote_claimed).1;
+
+        // Retrieve missing elements
+
+        let accounts =
+            match request(&mut session, TransferRequest::Accounts(account_ids.clone())).await? {
+                TransferResponse::Accounts(accounts) if accounts.len() == account_ids.len() => {
+                    account_ids
+                        .iter()
+                        .copied()
+                        .zip(accounts)
+                        .filter_map(|(id, account)| account.map(|account| (id, account)))
+                        .collect::<Vec<_>>()
+                }
+                _ => return FetchError::UnexpectedResponse.fail().spot(here!()),
+            };
+
+        let pipelines =
+            match request(&mut session, TransferRequest::States(state_ids.clone())).await? {
+                TransferResponse::States(states) if states.len() == state_ids.len() => state_ids
+                    .iter()
+                    .copied()
+                    .zip(states)
+                    .filter_map(|(id, state)| match state {
+                        Some(State::Consistent(pipeline)) => Some((id, pipeline)),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>(),
+                _ => return FetchError::UnexpectedResponse.fail().spot(here!()),
+            };
+
+        // Every `Prepare` in a transferred `Pipeline` must come with its `Extract`
+        // (and, if provided, a valid `CommitProof`)
+        pipelines
+            .par_iter()
+            .map(|(id, pipeline)| {
+                pipeline
+                    .iter()
+                    .map(|(height, commitment, handle)| match handle {
+                        PrepareHandle::Standalone(extract, proof)
+                            if extract.id() == *id
+                                && extract.height() == height
+                                && extract.commitment() == commitment =>
+                        {
+                            extract
+                                .validate(discovery)
+                                .pot(FetchError::InvalidExtract, here!())?;
+
+                            if let Some(proof) = proof {
+                                proof
+                                    .validate(discovery, extract.prepare())
+                                    .pot(FetchError::InvalidCommitProof, here!())?;
+                            }
+
+                            Ok(())
+                        }
+                        _ => FetchError::MalformedResponse.fail().spot(here!()),
+                    })
+                    .collect::<Result<(), Top<FetchError>>>()
+            })
+            .collect::<Result<(), Top<FetchError>>>()?;
+
+        let claims = match request(&mut session, TransferRequest::Claims(claim_ids.clone())).await?
+        {
+            TransferResponse::Claims(claims) if claims.len() == claim_ids.len() => claims,
+            _ => return FetchError::UnexpectedResponse.fail().spot(here!()),
+        };
+
+        let claims = claim_ids
+            .iter()
+            .zip(claims)
+            .filter_map(|(id, claim)| claim.map(|claim| (*id, claim)))
+            .collect::<Vec<_>>();
+
+        claims
+            .par_iter()
+            .map(|(id, claim)| {
+                if claim.id() != *id {
+                    return FetchError::MalformedResponse.fail().spot(here!());
+                }
+
+                claim
+                    .validate(work_difficulty)
+                    .pot(FetchError::InvalidClaim, here!())
+            })
+            .collect::<Result<(), Top<FetchError>>>()?;
+
+        let claims = claims
+            .into_iter()
+            .map(|(_, claim)| claim)
+            .collect::<Vec<_>>();
+
+        // An `IdAssignment` can exist only for a claimed `Id`
+        let assignment_ids = claim_ids;
+
+        let assignments = match request(
+            &mut session,
+            TransferRequest::Assignments(assignment_ids.clone()),
+        )
+        .await?
+        {
+            TransferResponse::Assignments(assignments)
+                if assignments.len() == assignment_ids.len() =>
+            {
+                assignments
+            }
+            _ => return FetchError::UnexpectedResponse.fail().spot(here!()),
+        };
+
+        let assignments = assignment_ids
+            .iter()
+            .zip(assignments)
+            .filter_map(|(id, assignment)| assignment.map(|assignment| (*id, assignment)))
+            .collect::<Vec<_>>();
+
+        assignments
+            .par_iter()
+            .map(|(id, assignment)| {
+                if assignment.id() != *id {
+                    return FetchError::MalformedResponse.fail().spot(here!());
+                }
+
+                assignment
+                    .validate(discovery)
+                    .pot(FetchError::InvalidAssignment, here!())
+            })
+            .collect::<Result<(), Top<FetchError>>>()?;
+
+        let assignments = assignments
+            .into_iter()
+            .map(|(_, assignment)| assignment)
+            .collect::<Vec<_>>();
+
+        session
+            .send(&TransferRequest::End)
+            .await
+            .pot(FetchError::ConnectionError, here!())?;
+
+        session.end();
+
+        Ok(Frontier {
+            accounts,
+            pipelines,
+            claims,
+            assignments,
+        })
+    }
+}
+
+async fn sync<R, A, T, F>(
+    session: &mut Session,
+    mut receiver: R,
+    learn: F,
+) -> Result<T, Top<FetchError>>
+where
+    A: DeserializeOwned + Send + Sync,
+    F: Fn(R, A) -> Result<Result<T, (R, Question)>, Top<FetchError>>,
+{
+    let structure = loop {
+        let answer = session
+            .receive::<A>()
+            .await
+            .pot(FetchError::ConnectionError, here!())?;
+
+        match learn(receiver, answer)? {
+            Ok(structure) => break structure,
+            Err((next, question)) => {
+                receiver = next;
+
+                session
+                    .send(&Some(question))
+                    .await
+                    .pot(FetchError::ConnectionError, here!())?;
+            }
+        }
+    };
+
+    session
+        .send::<Option<Question>>(&None)
+        .await
+        .pot(FetchError::ConnectionError, here!())?;
+
+    Ok(structure)
+}
+
+async fn request(
+    session: &mut Session,
+    request: TransferRequest,
+) -> Result<TransferResponse, Top<FetchError>> {
+    session
+        .send(&request)
+        .await
+        .pot(FetchError::ConnectionError, here!())?;
+
+    session
+        .receive::<TransferResponse>()
+        .await
+        .pot(FetchError::ConnectionError, here!())
+}
+
+// Groups `reports` by `Id`, retaining (one representative of) each value
+// whose `fingerprint` is reported at least `plurality` times
+fn vouched<T, K, F>(reports: Vec<(Id, T)>, fingerprint: F, plurality: usize) -> HashMap<Id, Vec<T>>
+where
+    K: PartialEq,
+    F: Fn(&T) -> K,
+{
+    let mut tallies: HashMap<Id, Vec<(K, T, usize)>> = HashMap::new();
+
+    for (id, value) in reports {
+        let key = fingerprint(&value);
+        let tally = tallies.entry(id).or_default();
+
+        match tally.iter_mut().find(|(other, _, _)| *other == key) {
+            Some((_, _, votes)) => *votes += 1,
+            None => tally.push((key, value, 1)),
+        }
+    }
+
+    tallies
+        .into_iter()
+        .map(|(id, tally)| {
+            let values = tally
+                .into_iter()
+                .filter(|(_, _, votes)| *votes >= plurality)
+                .map(|(_, value, _)| value)
+                .collect::<Vec<_>>();
+
+            (id, values)
+        })
+        .filter(|(_, values)| !values.is_empty())
+        .collect()
+}
@@ -0,0 +1,41 @@
+use doomstack::Doom;
+
+#[derive(Doom)]
+pub(in crate::processing::processor::transfer) enum ServeTransferError {
+    #[doom(description("Connection error"))]
+    ConnectionError,
+    #[doom(description("Database void"))]
+    DatabaseVoid,
+    #[doom(description("Malformed `Question`"))]
+    MalformedQuestion,
+    #[doom(description("Unexpected request"))]
+    UnexpectedRequest,
+}
+
+#[derive(Doom)]
+pub(crate) enum AcquireError {
+    #[doom(description("Failed to acquire a quorum of frontiers from the source view"))]
+    QuorumUnreachable,
+}
+
+#[derive(Doom)]
+pub(in crate::processing::processor::transfer) enum FetchError {
+    #[doom(description("Failed to connect"))]
+    ConnectFailed,
+    #[doom(description("Connection error"))]
+    ConnectionError,
+    #[doom(description("Malformed `Answer`"))]
+    MalformedAnswer,
+    #[doom(description("Unexpected response"))]
+    UnexpectedResponse,
+    #[doom(description("Malformed response"))]
+    MalformedResponse,
+    #[doom(description("Invalid `IdClaim`"))]
+    InvalidClaim,
+    #[doom(description("Invalid `IdAssignment`"))]
+    InvalidAssignment,
+    #[doom(description("Invalid `Extract`"))]
+    InvalidExtract,
+    #[doom(description("Invalid `CommitProof`"))]
+    InvalidCommitProof,
+}
@@ -0,0 +1,3 @@
+mod acquire;
+mod errors;
+mod transfer;
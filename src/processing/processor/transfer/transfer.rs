@@ -0,0 +1,406 @@
+use buckets::{Buckets, Split};
+
+use crate::{
+    account::Id,
+    database::{
+        prepare::{BatchHolder, PrepareHandle, State},
+        Database,
+    },
+    processing::{
+        messages::{TransferRequest, TransferResponse},
+        processor::transfer::errors::ServeTransferError,
+        Processor,
+    },
+};
+
+use doomstack::{here, Doom, ResultExt, Top};
+
+use serde::Serialize;
+
+use std::{collections::HashMap, sync::Arc};
+
+use talk::{
+    crypto::primitives::hash::Hash,
+    net::{Listener, Session, SessionListener},
+    sync::{fuse::Fuse, voidable::Voidable},
+};
+
+use zebra::database::Question;
+
+impl Processor {
+    pub(in crate::processing) async fn run_transfer<L>(
+        database: Arc<Voidable<Database>>,
+        listener: L,
+    ) where
+        L: Listener,
+    {
+        let mut listener = SessionListener::new(listener);
+        let fuse = Fuse::new();
+
+        loop {
+            let (_, session) = listener.accept().await;
+            let database = database.clone();
+
+            fuse.spawn(async move {
+                let _ = Processor::serve_transfer(database, session).await;
+            });
+        }
+    }
+
+    async fn serve_transfer(
+        database: Arc<Voidable<Database>>,
+        mut session: Session,
+    ) -> Result<(), Top<ServeTransferError>> {
+        let request = session
+            .receive::<TransferRequest>()
+            .await
+            .pot(ServeTransferError::ConnectionError, here!())?;
+
+        if !matches!(request, TransferRequest::Frontier) {
+            return ServeTransferError::UnexpectedRequest.fail().spot(here!());
+        }
+
+        // Snapshot the frontier of `database`: `prepare.advertisements` is
+        // flushed first, as it is updated lazily
+
+        let (imminent, advertisements, claimed) = {
+            let mut database = database
+                .lock()
+                .pot(ServeTransferError::DatabaseVoid, here!())?;
+
+            database.prepare.flush_advertisements();
+
+            (
+                database.imminent.clone(),
+                database.prepare.advertisements.clone(),
+                database.signup.claimed.clone(),
+            )
+        };
+
+        // Synchronize each zebra structure in turn (zebra `Answer`s are sent
+        // raw, with the joiner ending each synchronization with `None`)
+
+        let mut sender = imminent.send();
+        let hello = sender.hello();
+
+        sync(&mut session, hello, |question| {
+            sender
+                .answer(question)
+                .pot(ServeTransferError::MalformedQuestion, here!())
+        })
+        .await?;
+
+        let mut sender = advertisements.send();
+        let hello = sender.hello();
+
+        sync(&mut session, hello, |question| {
+            sender
+                .answer(question)
+                .pot(ServeTransferError::MalformedQuestion, here!())
+        })
+        .await?;
+
+        let mut sender = claimed.send();
+        let hello = sender.hello();
+
+        sync(&mut session, hello, |question| {
+            sender
+                .answer(question)
+                .pot(ServeTransferError::MalformedQuestion, here!())
+        })
+        .await?;
+
+        // Serve the elements the joiner is missing, until `TransferRequest::End`
+
+        loop {
+            let request = session
+                .receive::<TransferRequest>()
+                .await
+                .pot(ServeTransferError::ConnectionError, here!())?;
+
+            let response = match request {
+                TransferRequest::Frontier => {
+                    return ServeTransferError::UnexpectedRequest.fail().spot(here!());
+                }
+                TransferRequest::Accounts(ids) => {
+                    let ids = Split::with_key(ids, |id| *id);
+
+                    let accounts = {
+                        let mut database = database
+                            .lock()
+                            .pot(ServeTransferError::DatabaseVoid, here!())?;
+
+                        database
+                            .accounts
+                            .apply(ids, |accounts, id| accounts.get(&id).cloned())
+                    }
+                    .join();
+
+                    TransferResponse::Accounts(accounts)
+                }
+                TransferRequest::Assignments(ids) => {
+                    let ids = Split::with_key(ids, |id| *id);
+
+                    let assignments = {
+                        let mut database = database
+                            .lock()
+                            .pot(ServeTransferError::DatabaseVoid, here!())?;
+
+                        database
+                            .assignments
+                            .apply(ids, |assignments, id| assignments.get(&id).cloned())
+                    }
+                    .join();
+
+                    TransferResponse::Assignments(assignments)
+                }
+                TransferRequest::Claims(ids) => {
+                    let database = database
+                        .lock()
+                        .pot(ServeTransferError::DatabaseVoid, here!())?;
+
+                    let claims = ids
+                        .iter()
+                        .map(|id| database.signup.claims.get(id).cloned())
+                        .collect::<Vec<_>>();
+
+                    TransferResponse::Claims(claims)
+                }
+                TransferRequest::States(ids) => {
+                    let ids = Split::with_key(ids, |id| *id);
+
+                    let states = {
+                        let mut database = database
+                            .lock()
+                            .pot(ServeTransferError::DatabaseVoid, here!())?;
+
+                        fn fields(
+                            database: &mut Database,
+                        ) -> (
+                            &mut Buckets<HashMap<Id, State>>,
+                            &HashMap<Hash, BatchHolder>,
+                        ) {
+                            (&mut database.prepare.states, &database.prepare.batches)
+                        }
+
+                        let (states, batches) = fields(&mut database);
+
+                        // The joiner does not hold the batches referenced by `states`:
+                        // every `Batched` handle is replaced by its `Extract` (and `CommitProof`)
+                        buckets::apply_attached(states, batches, ids, |states, batches, id| {
+                            let mut state = states.get(&id)?.clone();
+
+                            if let State::Consistent(pipeline) = &mut state {
+                                for handle in pipeline.handles_mut() {
+                                    if let Some((root, index)) = handle.batched() {
+                                        let holder = &batches[&root];
+
+                                        *handle = PrepareHandle::Standalone(
+                                            holder.extract(index),
+                                            holder.commit_proof(index),
+                                        );
+                                    }
+                                }
+                            }
+
+                            Some(state)
+                        })
+                        .join()
+                    };
+
+                    TransferResponse::States(states)
+                }
+                TransferRequest::End => break,
+            };
+
+            session
+                .send(&response)
+                .await
+                .pot(ServeTransferError::ConnectionError, here!())?;
+        }
+
+        session.end();
+
+        Ok(())
+    }
+}
+
+async fn sync<A, F>(
+    session: &mut Session,
+    hello: A,
+    mut answer: F,
+) -> Result<(), Top<ServeTransferError>>
+where
+    A: Serialize + Send + Sync,
+    F: FnMut(&Question) -> Result<A, Top<ServeTransferError>>,
+{
+    let mut next = hello;
+
+    loop {
+        session
+            .send(&next)
+            .await
+            .pot(ServeTransferError::ConnectionError, here!())?;
+
+        let question = session
+            .receive::<Option<Question>>()
+            .await
+            .pot(ServeTransferError::ConnectionError, here!())?;
+
+        match question {
+            Some(question) => next = answer(&question)?,
+            None => return Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use buckets::Split;
+
+    use crate::{
+        account::Entry,
+        database::Database,
+        prepare::Prepare,
+        processing::{
+            test::{System, TestBroker},
+            Processor,
+        },
+        signup::{IdRequest, SignupSettings},
+    };
+
+    use talk::{
+        crypto::{primitives::hash, KeyChain},
+        net::test::System as NetSystem,
+    };
+
+    #[tokio::test]
+    async fn claims() {
+        let System {
+            view,
+            discovery_server: _discovery_server,
+            discovery_client,
+            brokers,
+            processors,
+        } = System::setup(4, 1).await;
+
+        let allocator = processors[0].0.keycard().identity();
+
+        let client = KeyChain::random();
+        let request = IdRequest::new(
+            &client,
+            &view,
+            allocator,
+            SignupSettings::default().work_difficulty,
+        );
+
+        let assignment = brokers[0].signup(vec![request]).await.remove(0).unwrap();
+
+        let mut database = Database::new();
+        brokers[0].acquire(&discovery_client, &mut database).await;
+
+        let claim = database.signup.claims.get(&assignment.id()).unwrap();
+        assert_eq!(claim.client(), client.keycard());
+
+        // Acquiring again leaves `database` unchanged
+        brokers[0].acquire(&discovery_client, &mut database).await;
+        assert_eq!(database.signup.claims.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn prepare_states() {
+        let System {
+            view,
+            discovery_server: _discovery_server,
+            discovery_client,
+            brokers,
+            processors,
+        } = System::setup(4, 1).await;
+
+        let allocator = processors[0].0.keycard().identity();
+
+        let client = KeyChain::random();
+        let request = IdRequest::new(
+            &client,
+            &view,
+            allocator,
+            SignupSettings::default().work_difficulty,
+        );
+
+        let assignment = brokers[0].signup(vec![request]).await.remove(0).unwrap();
+        let id = assignment.id();
+
+        let prepare = Prepare::new(Entry { id, height: 1 }, hash::hash(&0u32).unwrap());
+
+        let witness = brokers[0]
+            .witness(&[(client.clone(), assignment.clone(), prepare.clone())])
+            .await;
+
+        for (keychain, _) in processors.iter() {
+            let (_, shard) = brokers[0]
+                .prepare(
+                    keychain.keycard().identity(),
+                    &[prepare.clone()],
+                    &witness,
+                    &[None],
+                )
+                .await;
+
+            assert!(shard.exceptions().is_empty());
+        }
+
+        let mut database = Database::new();
+        brokers[0].acquire(&discovery_client, &mut database).await;
+
+        let acquired = database
+            .prepare
+            .states
+            .apply(Split::with_key(vec![id], |id| *id), |states, id| {
+                states.contains_key(&id)
+            })
+            .join();
+
+        assert_eq!(acquired, vec![true]);
+
+        // The joiner stands in for a member of `view`, and is reached through a separate network
+        let joiner = processors[0].0.clone();
+        let broker = KeyChain::random();
+
+        let NetSystem {
+            mut connectors,
+            mut listeners,
+            ..
+        } = NetSystem::setup_with_keychains(vec![joiner.clone(), broker.clone()]).await;
+
+        let _processor = Processor::new(
+            joiner.clone(),
+            discovery_client.clone(),
+            view.clone(),
+            database,
+            connectors.remove(0),
+            listeners.remove(0),
+            Default::default(),
+        );
+
+        let broker = TestBroker::new(broker, view.clone(), connectors.remove(0));
+
+        // A `Prepare` conflicting with the acquired state is witnessed, but excepted
+        // by the joiner (which would otherwise sign both `Prepare`s)
+        let conflicting = Prepare::new(Entry { id, height: 1 }, hash::hash(&1u32).unwrap());
+
+        let witness = brokers[0]
+            .witness(&[(client, assignment, conflicting.clone())])
+            .await;
+
+        let (_, shard) = broker
+            .prepare(
+                joiner.keycard().identity(),
+                &[conflicting],
+                &witness,
+                &[None],
+            )
+            .await;
+
+        assert!(shard.exceptions().contains(&id));
+    }
+}
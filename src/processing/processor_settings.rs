@@ -1,19 +1,70 @@
-use crate::signup::SignupSettings;
+use crate::{account::AccountSettings, signup::SignupSettings};
+
+use serde::Deserialize;
+
+use std::time::Duration;
 
 use talk::link::context::ListenDispatcherSettings;
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
 pub(crate) struct ProcessorSettings {
+    #[serde(skip)]
     pub listen_dispatcher_settings: ListenDispatcherSettings,
     pub signup: Signup,
+    pub commit: Commit,
+    pub query: Query,
+    pub persist: Persist,
+    pub collect: Collect,
+    pub advertise: Advertise,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
 pub(crate) struct Signup {
     pub signup_settings: SignupSettings,
     pub priority_attempts: usize,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub(crate) struct Commit {
+    pub account_settings: AccountSettings,
+    // Interval at which a batch waits for its pipelined payloads
+    // to become applicable (see `AccountSettings::pipeline_width`)
+    pub pipeline_interval: Duration,
+    // Maximum time a batch waits for its pipelined payloads
+    pub pipeline_timeout: Duration,
+    // Number of supporting accounts for a motion to pass
+    pub pass_threshold: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub(crate) struct Query {
+    // Maximum number of `Receipt`s in a `History` response
+    pub max_receipts: usize,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub(crate) struct Persist {
+    pub flush_interval: Duration,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub(crate) struct Collect {
+    pub interval: Duration,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub(crate) struct Advertise {
+    // Interval between synchronizations of prepare advertisements with peers
+    pub interval: Duration,
+}
+
 impl Default for Signup {
     fn default() -> Self {
         Signup {
@@ -22,3 +73,44 @@ impl Default for Signup {
         }
     }
 }
+
+impl Default for Commit {
+    fn default() -> Self {
+        Commit {
+            account_settings: AccountSettings::default(),
+            pipeline_interval: Duration::from_millis(10),
+            pipeline_timeout: Duration::from_secs(5),
+            pass_threshold: 1024,
+        }
+    }
+}
+
+impl Default for Query {
+    fn default() -> Self {
+        Query { max_receipts: 1024 }
+    }
+}
+
+impl Default for Persist {
+    fn default() -> Self {
+        Persist {
+            flush_interval: Duration::from_millis(100),
+        }
+    }
+}
+
+impl Default for Collect {
+    fn default() -> Self {
+        Collect {
+            interval: Duration::from_secs(10),
+        }
+    }
+}
+
+impl Default for Advertise {
+    fn default() -> Self {
+        Advertise {
+            interval: Duration::from_secs(1),
+        }
+    }
+}
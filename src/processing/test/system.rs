@@ -1,7 +1,7 @@
 use crate::{
     database::Database,
     discovery::{self, Client, Mode, Server},
-    processing::{test::TestBroker, Processor},
+    processing::{test::TestBroker, Processor, ProcessorSettings},
     view::View,
 };
 
@@ -19,6 +19,14 @@ pub(crate) struct System {
 
 impl System {
     pub async fn setup(processors: usize, brokers: usize) -> Self {
+        System::setup_with_settings(processors, brokers, Default::default()).await
+    }
+
+    pub async fn setup_with_settings(
+        processors: usize,
+        brokers: usize,
+        settings: ProcessorSettings,
+    ) -> Self {
         let (install_generator, discovery_server, _, mut discovery_clients, _) =
             discovery::test::setup(processors, processors, Mode::Full).await;
 
@@ -55,7 +63,7 @@ impl System {
                         Database::new(),
                         connectors.remove(0),
                         listeners.remove(0),
-                        Default::default(),
+                        settings.clone(),
                     ),
                 )
             })
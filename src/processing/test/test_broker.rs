@@ -1,24 +1,54 @@
 use crate::{
-    crypto::Identify,
-    processing::messages::{SignupRequest, SignupResponse},
+    account::{AccountSummary, Id},
+    commit::{
+        BatchCompletion, BatchCompletionAggregator, CommitProof, Completion, CompletionProof,
+        Payload, WitnessStatement as CommitWitnessStatement,
+    },
+    crypto::{Aggregator, Certificate, Identify},
+    database::{Database, Receipt},
+    discovery::Client,
+    motion::PassShard,
+    prepare::{
+        BatchCommit, BatchCommitShard, Continuity, Prepare, ReductionStatement, WitnessStatement,
+    },
+    processing::{
+        messages::{
+            CommitRequest, CommitResponse, PrepareRequest, PrepareResponse, QueryRequest,
+            QueryResponse, SignupRequest, SignupResponse,
+        },
+        Processor,
+    },
+    query::{AccountProof, AccountProofAggregator, AccountProofShard},
     signup::{
-        IdAllocation, IdAssignment, IdAssignmentAggregator, IdClaim, IdRequest, SignupSettings,
+        IdAllocation, IdAssignment, IdAssignmentAggregator, IdClaim, IdRequest, KeyRotation,
+        SignupSettings,
     },
     view::View,
 };
 
 use futures::stream::{FuturesUnordered, StreamExt};
 
+use std::ops::Range;
+
 use talk::{
-    crypto::{primitives::multi::Signature as MultiSignature, Identity, KeyChain},
+    crypto::{
+        primitives::{hash::Hash, multi::Signature as MultiSignature},
+        Identity, KeyChain,
+    },
     link::context::ConnectDispatcher,
     net::{test::TestConnector, SessionConnector},
 };
 
+use zebra::vector::Vector;
+
 pub(crate) struct TestBroker {
     keychain: KeyChain,
     view: View,
     signup_connector: SessionConnector,
+    prepare_connector: SessionConnector,
+    commit_connector: SessionConnector,
+    query_connector: SessionConnector,
+    transfer_connector: SessionConnector,
 }
 
 impl TestBroker {
@@ -28,10 +58,26 @@ impl TestBroker {
         let signup_context = format!("{:?}::processor::signup", view.identifier());
         let signup_connector = SessionConnector::new(dispatcher.register(signup_context));
 
+        let prepare_context = format!("{:?}::processor::prepare", view.identifier());
+        let prepare_connector = SessionConnector::new(dispatcher.register(prepare_context));
+
+        let commit_context = format!("{:?}::processor::commit", view.identifier());
+        let commit_connector = SessionConnector::new(dispatcher.register(commit_context));
+
+        let query_context = format!("{:?}::processor::query", view.identifier());
+        let query_connector = SessionConnector::new(dispatcher.register(query_context));
+
+        let transfer_context = format!("{:?}::processor::transfer", view.identifier());
+        let transfer_connector = SessionConnector::new(dispatcher.register(transfer_context));
+
         Self {
             keychain,
             view,
             signup_connector,
+            prepare_connector,
+            commit_connector,
+            query_connector,
+            transfer_connector,
         }
     }
 
@@ -196,4 +242,459 @@ impl TestBroker {
             .map(|aggregator| aggregator.map(|aggregator| aggregator.finalize()))
             .collect::<Vec<_>>()
     }
+
+    /// Collects witness shards for the batch of `prepares` (each signed by the
+    /// accompanying `KeyChain`, with the accompanying `IdAssignment` provided
+    /// upon request) until a plurality of members witnessed it.
+    pub async fn witness(&self, prepares: &[(KeyChain, IdAssignment, Prepare)]) -> Certificate {
+        let prepares = prepares
+            .iter()
+            .map(|(keychain, assignment, prepare)| {
+                (keychain.clone(), assignment.clone(), None, prepare.clone())
+            })
+            .collect::<Vec<_>>();
+
+        self.witness_rotated(prepares.as_slice()).await
+    }
+
+    /// Like `witness`, additionally providing upon request the accompanying
+    /// `KeyRotation` (if any) along with each `IdAssignment`.
+    pub async fn witness_rotated(
+        &self,
+        prepares: &[(KeyChain, IdAssignment, Option<KeyRotation>, Prepare)],
+    ) -> Certificate {
+        let batch = Vector::new(
+            prepares
+                .iter()
+                .map(|(_, _, _, prepare)| prepare.clone())
+                .collect::<Vec<_>>(),
+        )
+        .unwrap();
+
+        let mut unordered = self
+            .view
+            .members()
+            .iter()
+            .map(|(witness_identity, witness_keycard)| async move {
+                let shard = self
+                    .witness_shard(*witness_identity, prepares, &[])
+                    .await
+                    .unwrap();
+
+                (witness_keycard.clone(), shard)
+            })
+            .collect::<FuturesUnordered<_>>();
+
+        let mut aggregator =
+            Aggregator::new(self.view.clone(), WitnessStatement::new(batch.root()));
+
+        while aggregator.multiplicity() < self.view.plurality() {
+            let (witness, shard) = unordered.next().await.unwrap();
+            aggregator.add(&witness, shard).unwrap();
+        }
+
+        aggregator.finalize_plurality().1
+    }
+
+    /// Submits the batch of `prepares` (signed as in `witness_rotated`) to `replica`
+    /// alone, returning `replica`'s witness shard (`None` if `replica` refuses it).
+    /// Should `replica` lag behind the commits of some `Id`, the `AccountProof` of
+    /// that `Id` is taken from `proofs` (if available), or collected from the members.
+    pub async fn witness_shard(
+        &self,
+        replica: Identity,
+        prepares: &[(KeyChain, IdAssignment, Option<KeyRotation>, Prepare)],
+        proofs: &[(Id, AccountProof)],
+    ) -> Option<MultiSignature> {
+        let batch = Vector::new(
+            prepares
+                .iter()
+                .map(|(_, _, _, prepare)| prepare.clone())
+                .collect::<Vec<_>>(),
+        )
+        .unwrap();
+
+        let reduction_statement = ReductionStatement::new(batch.root());
+
+        let reduction_signature = MultiSignature::aggregate(
+            prepares
+                .iter()
+                .map(|(keychain, _, _, _)| keychain.multisign(&reduction_statement).unwrap()),
+        )
+        .unwrap();
+
+        let mut session = self.prepare_connector.connect(replica).await.unwrap();
+
+        session.send(&PrepareRequest::Batch(batch)).await.unwrap();
+
+        session
+            .send(&PrepareRequest::Signatures(
+                reduction_signature,
+                vec![None; prepares.len()],
+            ))
+            .await
+            .unwrap();
+
+        let mut response = session.receive().await.ok()?;
+
+        if let PrepareResponse::UnknownIds(ids) = response {
+            let assignments = ids
+                .iter()
+                .map(|id| {
+                    prepares
+                        .iter()
+                        .find(|(_, _, _, prepare)| prepare.id() == *id)
+                        .map(|(_, assignment, _, _)| assignment.clone())
+                        .unwrap()
+                })
+                .collect::<Vec<_>>();
+
+            session
+                .send(&PrepareRequest::Assignments(assignments))
+                .await
+                .unwrap();
+
+            response = session.receive().await.ok()?;
+        }
+
+        if let PrepareResponse::LaggingIds(ids) = response {
+            let mut rotations = Vec::new();
+
+            for id in ids {
+                let rotation = prepares
+                    .iter()
+                    .find(|(_, _, _, prepare)| prepare.id() == id)
+                    .map(|(_, _, rotation, _)| rotation.clone())
+                    .unwrap();
+
+                let proof = match proofs.iter().find(|(proven, _)| *proven == id) {
+                    Some((_, proof)) => proof.clone(),
+                    None => self.account_proof(id).await,
+                };
+
+                rotations.push((proof, rotation));
+            }
+
+            session
+                .send(&PrepareRequest::Rotations(rotations))
+                .await
+                .unwrap();
+
+            response = session.receive().await.ok()?;
+        }
+
+        // `session` is dropped before the witness is sent back
+        match response {
+            PrepareResponse::WitnessShard(shard) => Some(shard),
+            _ => panic!("unexpected response"),
+        }
+    }
+
+    /// Submits the batch of `prepares` (witnessed by `witness`) to `replica` alone,
+    /// returning the `Id`s whose continuity `replica` queried (if any), along with
+    /// `replica`'s `BatchCommitShard`. `continuities` is aligned with `prepares`.
+    pub async fn prepare(
+        &self,
+        replica: Identity,
+        prepares: &[Prepare],
+        witness: &Certificate,
+        continuities: &[Option<Continuity>],
+    ) -> (Vec<Id>, BatchCommitShard) {
+        let batch = Vector::new(prepares.to_vec()).unwrap();
+
+        let mut session = self.prepare_connector.connect(replica).await.unwrap();
+
+        session.send(&PrepareRequest::Batch(batch)).await.unwrap();
+
+        session
+            .send(&PrepareRequest::Witness(witness.clone()))
+            .await
+            .unwrap();
+
+        let mut missing = Vec::new();
+        let mut response = session.receive().await.unwrap();
+
+        if let PrepareResponse::MissingContinuities(ids) = response {
+            let requested = ids
+                .iter()
+                .map(|id| {
+                    let index = prepares
+                        .iter()
+                        .position(|prepare| prepare.id() == *id)
+                        .unwrap();
+
+                    continuities[index].clone()
+                })
+                .collect::<Vec<_>>();
+
+            session
+                .send(&PrepareRequest::Continuities(requested))
+                .await
+                .unwrap();
+
+            missing = ids;
+            response = session.receive().await.unwrap();
+        }
+
+        session.end();
+
+        match response {
+            PrepareResponse::CommitShard(shard) => (missing, shard),
+            _ => panic!("unexpected response"),
+        }
+    }
+
+    /// Attaches `commit` to its batch at every member (as prepare brokers do).
+    pub async fn attach(&self, commit: &BatchCommit) {
+        for replica in self.view.members().keys() {
+            let mut session = self.prepare_connector.connect(*replica).await.unwrap();
+
+            session
+                .send(&PrepareRequest::Commit(commit.clone()))
+                .await
+                .unwrap();
+
+            session.end();
+        }
+    }
+
+    /// Commits the batch of `payloads` at every member, providing `proofs` (aligned
+    /// with `payloads`) upon request, until a quorum of members completed the batch.
+    /// No element of `payloads` can have a dependency.
+    pub async fn commit(&self, payloads: &[Payload], proofs: &[CommitProof]) -> BatchCompletion {
+        let batch = Vector::new(payloads.to_vec()).unwrap();
+
+        let mut sessions = Vec::new();
+
+        let mut aggregator =
+            Aggregator::new(self.view.clone(), CommitWitnessStatement::new(batch.root()));
+
+        for (replica, keycard) in self.view.members() {
+            let mut session = self.commit_connector.connect(*replica).await.unwrap();
+
+            session
+                .send(&CommitRequest::Batch(batch.clone()))
+                .await
+                .unwrap();
+
+            session.send(&CommitRequest::WitnessRequest).await.unwrap();
+
+            let mut response = session.receive().await.unwrap();
+
+            if let CommitResponse::MissingCommitProofs(ids) = response {
+                let requested = ids
+                    .iter()
+                    .map(|id| {
+                        let index = payloads
+                            .iter()
+                            .position(|payload| payload.id() == *id)
+                            .unwrap();
+
+                        proofs[index].clone()
+                    })
+                    .collect::<Vec<_>>();
+
+                session
+                    .send(&CommitRequest::CommitProofs(requested))
+                    .await
+                    .unwrap();
+
+                response = session.receive().await.unwrap();
+            }
+
+            match response {
+                CommitResponse::WitnessShard(shard) => aggregator.add(keycard, shard).unwrap(),
+                _ => panic!("unexpected response"),
+            }
+
+            sessions.push((keycard.clone(), session));
+        }
+
+        let (_, witness) = aggregator.finalize();
+
+        let mut aggregator = BatchCompletionAggregator::new(self.view.clone(), batch.root());
+
+        for (keycard, mut session) in sessions {
+            session
+                .send(&CommitRequest::Witness(witness.clone()))
+                .await
+                .unwrap();
+
+            let response = session.receive().await.unwrap();
+            session.end();
+
+            match response {
+                CommitResponse::CompletionShard(shard) => aggregator.add(&keycard, shard),
+                _ => panic!("unexpected response"),
+            }
+        }
+
+        assert!(aggregator.complete());
+        aggregator.finalize()
+    }
+
+    /// Prepares the batch of `payloads` (each signed by the accompanying `KeyChain`,
+    /// and prepared with the accompanying `IdAssignment` and `Continuity`) at every
+    /// member, returning the `CommitProof` of each element of `payloads`.
+    pub async fn commit_proofs(
+        &self,
+        payloads: &[(KeyChain, IdAssignment, Payload, Option<Continuity>)],
+    ) -> Vec<CommitProof> {
+        let prepares = payloads
+            .iter()
+            .map(|(keychain, assignment, payload, _)| {
+                (keychain.clone(), assignment.clone(), payload.prepare())
+            })
+            .collect::<Vec<_>>();
+
+        let witness = self.witness(prepares.as_slice()).await;
+
+        let prepares = prepares
+            .into_iter()
+            .map(|(_, _, prepare)| prepare)
+            .collect::<Vec<_>>();
+
+        let continuities = payloads
+            .iter()
+            .map(|(_, _, _, continuity)| continuity.clone())
+            .collect::<Vec<_>>();
+
+        let mut shards = Vec::new();
+
+        for (replica, keycard) in self.view.members() {
+            let (_, shard) = self
+                .prepare(
+                    *replica,
+                    prepares.as_slice(),
+                    &witness,
+                    continuities.as_slice(),
+                )
+                .await;
+
+            assert!(shard.exceptions().is_empty());
+            shards.push((keycard.clone(), shard));
+        }
+
+        let prepares = Vector::new(prepares).unwrap();
+        let batch_commit = BatchCommit::new(self.view.clone(), prepares.root(), shards);
+        self.attach(&batch_commit).await;
+
+        (0..payloads.len())
+            .map(|index| CommitProof::new(batch_commit.clone(), prepares.prove(index)))
+            .collect()
+    }
+
+    /// Prepares then commits the batch of `payloads` (as in `commit_proofs`)
+    /// at every member, returning the `Completion` of each element of `payloads`.
+    pub async fn settle(
+        &self,
+        payloads: &[(KeyChain, IdAssignment, Payload, Option<Continuity>)],
+    ) -> Vec<Completion> {
+        let proofs = self.commit_proofs(payloads).await;
+
+        let payloads = payloads
+            .iter()
+            .map(|(_, _, payload, _)| payload.clone())
+            .collect::<Vec<_>>();
+
+        let batch_completion = self.commit(payloads.as_slice(), proofs.as_slice()).await;
+        let batch = Vector::new(payloads.clone()).unwrap();
+
+        payloads
+            .into_iter()
+            .enumerate()
+            .map(|(index, payload)| {
+                let proof = CompletionProof::new(batch_completion.clone(), batch.prove(index));
+                Completion::new(proof, payload)
+            })
+            .collect()
+    }
+
+    pub async fn summaries(&self, replica: Identity, ids: Vec<Id>) -> Vec<Option<AccountSummary>> {
+        let mut session = self.query_connector.connect(replica).await.unwrap();
+
+        session.send(&QueryRequest::Summaries(ids)).await.unwrap();
+
+        let response = session.receive().await.unwrap();
+        session.end();
+
+        match response {
+            QueryResponse::Summaries(summaries) => summaries,
+            _ => panic!("unexpected response"),
+        }
+    }
+
+    pub async fn account_proof_shard(&self, replica: Identity, id: Id) -> AccountProofShard {
+        let mut session = self.query_connector.connect(replica).await.unwrap();
+
+        session.send(&QueryRequest::Proof(id)).await.unwrap();
+
+        let response = session.receive().await.unwrap();
+        session.end();
+
+        match response {
+            QueryResponse::Proof(shard) => shard,
+            _ => panic!("unexpected response"),
+        }
+    }
+
+    pub async fn account_proof(&self, id: Id) -> AccountProof {
+        let mut aggregator = AccountProofAggregator::new(self.view.clone());
+
+        for (replica, keycard) in self.view.members() {
+            let shard = self.account_proof_shard(*replica, id).await;
+            shard.validate(&self.view, id, keycard).unwrap();
+
+            aggregator.add(keycard, shard);
+
+            if aggregator.complete() {
+                break;
+            }
+        }
+
+        aggregator.finalize()
+    }
+
+    pub async fn motion(&self, replica: Identity, motion: Hash) -> (u64, Option<PassShard>) {
+        let mut session = self.query_connector.connect(replica).await.unwrap();
+
+        session.send(&QueryRequest::Motion(motion)).await.unwrap();
+
+        let response = session.receive().await.unwrap();
+        session.end();
+
+        match response {
+            QueryResponse::Motion { tally, shard } => (tally, shard),
+            _ => panic!("unexpected response"),
+        }
+    }
+
+    pub async fn history(&self, replica: Identity, id: Id, heights: Range<u64>) -> Vec<Receipt> {
+        let mut session = self.query_connector.connect(replica).await.unwrap();
+
+        session
+            .send(&QueryRequest::History { id, heights })
+            .await
+            .unwrap();
+
+        let response = session.receive().await.unwrap();
+        session.end();
+
+        match response {
+            QueryResponse::History(receipts) => receipts,
+            _ => panic!("unexpected response"),
+        }
+    }
+
+    pub async fn acquire(&self, discovery: &Client, database: &mut Database) {
+        Processor::acquire(
+            discovery,
+            &self.view,
+            database,
+            &self.transfer_connector,
+            &Default::default(),
+        )
+        .await
+        .unwrap();
+    }
 }
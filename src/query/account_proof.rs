@@ -0,0 +1,336 @@
+use crate::{
+    account::{AccountSummary, CorrectState, Id, StateSummary},
+    crypto::{Aggregator, Certificate, Identify},
+    query::{AccountProofShard, ImminentStatement},
+    view::View,
+};
+
+use doomstack::{here, Doom, ResultExt, Top};
+
+use serde::{Deserialize, Serialize};
+
+use std::collections::HashMap;
+
+use talk::crypto::{primitives::hash::Hash, KeyCard};
+
+use zebra::map::Map;
+
+/// Proof of an `Id`'s imminent `AccountSummary`, by inclusion against the root of
+/// the `imminent` table. The root is certified by a plurality of a `View`'s members
+/// (hence by at least one correct member): an `AccountProof` can be verified without
+/// trusting any one replica.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct AccountProof {
+    view: Hash,
+    root: Hash,
+    inclusion: Map<Id, AccountSummary>,
+    state: Option<CorrectState>,
+    certificate: Certificate,
+}
+
+/// Collects `AccountProofShard`s, grouping them by root: replicas that
+/// lag behind each other attest different roots.
+pub(crate) struct AccountProofAggregator {
+    view: View,
+    candidates: HashMap<Hash, Candidate>,
+}
+
+struct Candidate {
+    aggregator: Aggregator<ImminentStatement>,
+    inclusion: Map<Id, AccountSummary>,
+    state: Option<CorrectState>,
+}
+
+#[derive(Doom)]
+pub(crate) enum AccountProofError {
+    #[doom(description("`View` unknown"))]
+    ViewUnknown,
+    #[doom(description("`Certificate` invalid"))]
+    CertificateInvalid,
+    #[doom(description("Inclusion does not match root"))]
+    RootMismatch,
+    #[doom(description("Inclusion invalid"))]
+    InclusionInvalid,
+    #[doom(description("`CorrectState` does not match `AccountSummary`"))]
+    StateMismatch,
+}
+
+impl AccountProof {
+    pub fn view(&self) -> Hash {
+        self.view
+    }
+
+    pub fn root(&self) -> Hash {
+        self.root
+    }
+
+    // `state` is meaningful only if `self` was validated beforehand
+    pub fn state(&self) -> Option<&CorrectState> {
+        self.state.as_ref()
+    }
+
+    /// Validates `self` for `id`, returning the proven `AccountSummary`
+    /// (`None` if `id` has no imminent history).
+    pub fn validate(&self, id: Id) -> Result<Option<AccountSummary>, Top<AccountProofError>> {
+        let view = View::get(self.view)
+            .ok_or(AccountProofError::ViewUnknown.into_top())
+            .spot(here!())?;
+
+        let statement = ImminentStatement::new(self.view, self.root);
+
+        self.certificate
+            .verify_plurality(&view, &statement)
+            .pot(AccountProofError::CertificateInvalid, here!())?;
+
+        include(self.root, &self.inclusion, self.state.as_ref(), id)
+    }
+}
+
+impl AccountProofAggregator {
+    pub fn new(view: View) -> Self {
+        AccountProofAggregator {
+            view,
+            candidates: HashMap::new(),
+        }
+    }
+
+    pub fn add(&mut self, replica: &KeyCard, shard: AccountProofShard) {
+        let (root, inclusion, state, signature) = shard.into_components();
+
+        let view = &self.view;
+
+        let candidate = self.candidates.entry(root).or_insert_with(|| Candidate {
+            aggregator: Aggregator::new(
+                view.clone(),
+                ImminentStatement::new(view.identifier(), root),
+            ),
+            inclusion,
+            state: None,
+        });
+
+        // Assuming that `shard` is valid, `signature` is valid
+        candidate.aggregator.add(replica, signature).unwrap();
+
+        // A replica withholds `state` while its `CorrectState` lags behind its
+        // `imminent` table (see `processing::processor::query::handlers::proof`)
+        if candidate.state.is_none() {
+            candidate.state = state;
+        }
+    }
+
+    pub fn complete(&self) -> bool {
+        self.candidates
+            .values()
+            .any(|candidate| candidate.aggregator.multiplicity() >= self.view.plurality())
+    }
+
+    pub fn finalize(self) -> AccountProof {
+        let view = self.view;
+
+        let candidate = self
+            .candidates
+            .into_iter()
+            .map(|(_, candidate)| candidate)
+            .find(|candidate| candidate.aggregator.multiplicity() >= view.plurality())
+            .expect("Called `AccountProofAggregator::finalize` on an incomplete aggregator");
+
+        let (statement, certificate) = candidate.aggregator.finalize_plurality();
+
+        AccountProof {
+            view: view.identifier(),
+            root: statement.root(),
+            inclusion: candidate.inclusion,
+            state: candidate.state,
+            certificate,
+        }
+    }
+}
+
+/// Verifies that `inclusion` matches `root`, and that `state` (if provided) is
+/// the one summarized in `inclusion` for `id`, returning `id`'s `AccountSummary`.
+pub(in crate::query) fn include(
+    root: Hash,
+    inclusion: &Map<Id, AccountSummary>,
+    state: Option<&CorrectState>,
+    id: Id,
+) -> Result<Option<AccountSummary>, Top<AccountProofError>> {
+    if inclusion.commit() != root {
+        return AccountProofError::RootMismatch.fail().spot(here!());
+    }
+
+    let summary = inclusion
+        .get(&id)
+        .pot(AccountProofError::InclusionInvalid, here!())?
+        .copied();
+
+    match (&summary, state) {
+        (_, None) => {}
+        (
+            Some(AccountSummary {
+                state: StateSummary::Correct(identifier),
+                ..
+            }),
+            Some(state),
+        ) if state.identifier() == *identifier => {}
+        _ => {
+            return AccountProofError::StateMismatch.fail().spot(here!());
+        }
+    }
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{
+        account::{Account, State},
+        database::Database,
+        discovery::{self, Mode},
+        view::test::InstallGenerator,
+    };
+
+    use zebra::database::TableTransaction;
+
+    async fn setup() -> (InstallGenerator, View) {
+        let (install_generator, _, _, _, _) = discovery::test::setup(4, 4, Mode::Full).await;
+        let view = install_generator.view(4);
+
+        (install_generator, view)
+    }
+
+    // Returns the inclusion of `ids`' `AccountSummary`s in an `imminent` table where
+    // every id in `ids` has a fresh `Account`, along with the `CorrectState` of `ids[0]`
+    fn imminent(ids: &[Id]) -> (Map<Id, AccountSummary>, CorrectState) {
+        let mut database = Database::new();
+        let mut transaction = TableTransaction::new();
+
+        let accounts = ids
+            .iter()
+            .map(|id| Account::new(*id, &Default::default()))
+            .collect::<Vec<_>>();
+
+        for (id, account) in ids.iter().zip(accounts.iter()) {
+            transaction.set(*id, account.summarize()).unwrap();
+        }
+
+        database.imminent.execute(transaction);
+
+        let inclusion = database.imminent.export([&ids[0]]).unwrap();
+
+        let state = match accounts[0].state() {
+            State::Correct(state) => state.clone(),
+            State::Corrupted(_) => unreachable!(),
+        };
+
+        (inclusion, state)
+    }
+
+    #[tokio::test]
+    async fn plurality() {
+        let (install_generator, view) = setup().await;
+
+        let (inclusion, state) = imminent(&[0, 1]);
+        let (lagging, _) = imminent(&[0]);
+
+        let mut aggregator = AccountProofAggregator::new(view.clone());
+
+        // Shards attesting a different root do not contribute to completion
+        let keychain = &install_generator.keychains[0];
+        let shard = AccountProofShard::new(keychain, view.identifier(), lagging, None);
+        shard.validate(&view, 0, &keychain.keycard()).unwrap();
+        aggregator.add(&keychain.keycard(), shard);
+
+        for keychain in install_generator
+            .keychains
+            .iter()
+            .skip(1)
+            .take(view.plurality())
+        {
+            assert!(!aggregator.complete());
+
+            let shard = AccountProofShard::new(
+                keychain,
+                view.identifier(),
+                inclusion.clone(),
+                Some(state.clone()),
+            );
+
+            shard.validate(&view, 0, &keychain.keycard()).unwrap();
+            aggregator.add(&keychain.keycard(), shard);
+        }
+
+        assert!(aggregator.complete());
+
+        let proof = aggregator.finalize();
+        let summary = proof.validate(0).unwrap().unwrap();
+
+        assert_eq!(summary.height, 0);
+        assert_eq!(summary.state, StateSummary::Correct(state.identifier()));
+        assert_eq!(proof.state().unwrap().identifier(), state.identifier());
+    }
+
+    #[tokio::test]
+    async fn not_enough_signers() {
+        let (install_generator, view) = setup().await;
+        let (inclusion, _) = imminent(&[0]);
+
+        let root = inclusion.commit();
+        let statement = ImminentStatement::new(view.identifier(), root);
+
+        let components = install_generator
+            .keychains
+            .iter()
+            .take(view.plurality() - 1)
+            .map(|keychain| {
+                (
+                    keychain.keycard().identity(),
+                    keychain.multisign(&statement).unwrap(),
+                )
+            });
+
+        let proof = AccountProof {
+            view: view.identifier(),
+            root,
+            inclusion,
+            state: None,
+            certificate: Certificate::aggregate(&view, components),
+        };
+
+        assert!(proof.validate(0).is_err());
+    }
+
+    #[tokio::test]
+    async fn forged_root() {
+        let (install_generator, view) = setup().await;
+
+        let (inclusion, _) = imminent(&[0]);
+        let (forged, _) = imminent(&[0, 1]);
+
+        let mut aggregator = AccountProofAggregator::new(view.clone());
+
+        for keychain in install_generator.keychains.iter().take(view.plurality()) {
+            let shard =
+                AccountProofShard::new(keychain, view.identifier(), inclusion.clone(), None);
+
+            aggregator.add(&keychain.keycard(), shard);
+        }
+
+        let proof = aggregator.finalize();
+        assert!(proof.validate(0).is_ok());
+
+        // Inclusion against a root that was never certified
+        let mut forgery = proof.clone();
+        forgery.root = forged.commit();
+        forgery.inclusion = forged.clone();
+
+        assert!(forgery.validate(0).is_err());
+
+        // Inclusion that does not match the certified root
+        let mut forgery = proof;
+        forgery.inclusion = forged;
+
+        assert!(forgery.validate(0).is_err());
+    }
+}
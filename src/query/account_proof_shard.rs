@@ -0,0 +1,89 @@
+use crate::{
+    account::{AccountSummary, CorrectState, Id},
+    crypto::Identify,
+    query::{account_proof, ImminentStatement},
+    view::View,
+};
+
+use doomstack::{here, Doom, ResultExt, Top};
+
+use serde::{Deserialize, Serialize};
+
+use talk::crypto::{
+    primitives::{hash::Hash, multi::Signature as MultiSignature},
+    KeyCard, KeyChain,
+};
+
+use zebra::map::Map;
+
+/// A single replica's attestation of the root of its `imminent` table, along
+/// with the inclusion of an `Id`'s `AccountSummary` against that root.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct AccountProofShard {
+    root: Hash,
+    inclusion: Map<Id, AccountSummary>,
+    state: Option<CorrectState>,
+    signature: MultiSignature,
+}
+
+#[derive(Doom)]
+pub(crate) enum AccountProofShardError {
+    #[doom(description("`Signature` invalid"))]
+    SignatureInvalid,
+    #[doom(description("Inclusion invalid"))]
+    InclusionInvalid,
+}
+
+impl AccountProofShard {
+    pub fn new(
+        keychain: &KeyChain,
+        view: Hash,
+        inclusion: Map<Id, AccountSummary>,
+        state: Option<CorrectState>,
+    ) -> Self {
+        let root = inclusion.commit();
+
+        let statement = ImminentStatement::new(view, root);
+        let signature = keychain.multisign(&statement).unwrap();
+
+        AccountProofShard {
+            root,
+            inclusion,
+            state,
+            signature,
+        }
+    }
+
+    pub fn root(&self) -> Hash {
+        self.root
+    }
+
+    pub fn into_components(
+        self,
+    ) -> (
+        Hash,
+        Map<Id, AccountSummary>,
+        Option<CorrectState>,
+        MultiSignature,
+    ) {
+        (self.root, self.inclusion, self.state, self.signature)
+    }
+
+    /// Validates `self` as issued by `replica` for `id`, returning the
+    /// `AccountSummary` it attests (`None` if `id` has no imminent history).
+    pub fn validate(
+        &self,
+        view: &View,
+        id: Id,
+        replica: &KeyCard,
+    ) -> Result<Option<AccountSummary>, Top<AccountProofShardError>> {
+        let statement = ImminentStatement::new(view.identifier(), self.root);
+
+        self.signature
+            .verify([replica], &statement)
+            .pot(AccountProofShardError::SignatureInvalid, here!())?;
+
+        account_proof::include(self.root, &self.inclusion, self.state.as_ref(), id)
+            .pot(AccountProofShardError::InclusionInvalid, here!())
+    }
+}
@@ -0,0 +1,26 @@
+use crate::crypto::Header;
+
+use serde::Serialize;
+
+use talk::crypto::{primitives::hash::Hash, Statement};
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct ImminentStatement {
+    view: Hash,
+    root: Hash,
+}
+
+impl ImminentStatement {
+    pub fn new(view: Hash, root: Hash) -> Self {
+        ImminentStatement { view, root }
+    }
+
+    pub fn root(&self) -> Hash {
+        self.root
+    }
+}
+
+impl Statement for ImminentStatement {
+    type Header = Header;
+    const HEADER: Header = Header::Imminent;
+}
@@ -0,0 +1,9 @@
+mod account_proof;
+mod account_proof_shard;
+mod imminent_statement;
+
+#[allow(unused_imports)]
+pub(crate) use account_proof::{AccountProof, AccountProofAggregator, AccountProofError};
+
+pub(crate) use account_proof_shard::AccountProofShard;
+pub(crate) use imminent_statement::ImminentStatement;
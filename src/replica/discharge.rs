@@ -0,0 +1,137 @@
+use crate::{
+    crypto::Identify,
+    replica::{
+        errors::{DischargeError, ServeDischargeError},
+        Replica, ReplicaSettings,
+    },
+    view::View,
+};
+
+use doomstack::{here, Doom, ResultExt, Top};
+
+use futures::stream::{FuturesUnordered, StreamExt};
+
+use talk::{
+    crypto::{primitives::hash::Hash, Identity},
+    net::{Listener, Session, SessionConnector, SessionListener},
+    sync::fuse::Fuse,
+};
+
+use tokio::{sync::watch::Receiver as WatchReceiver, time};
+
+impl Replica {
+    pub(in crate::replica) async fn run_discharge<L>(listener: L, prepared: WatchReceiver<usize>)
+    where
+        L: Listener,
+    {
+        let mut listener = SessionListener::new(listener);
+        let fuse = Fuse::new();
+
+        loop {
+            let (_, session) = listener.accept().await;
+            let prepared = prepared.clone();
+
+            fuse.spawn(async move {
+                let _ = Replica::serve_discharge(session, prepared).await;
+            });
+        }
+    }
+
+    async fn serve_discharge(
+        mut session: Session,
+        mut prepared: WatchReceiver<usize>,
+    ) -> Result<(), Top<ServeDischargeError>> {
+        let view = session
+            .receive::<Hash>()
+            .await
+            .pot(ServeDischargeError::ConnectionError, here!())?;
+
+        let view = View::get(view)
+            .ok_or(ServeDischargeError::UnknownView.into_top())
+            .spot(here!())?;
+
+        // `Discharge` is sent only once `view` (or a later `View`) is prepared
+        while *prepared.borrow_and_update() < view.height() {
+            prepared
+                .changed()
+                .await
+                .pot(ServeDischargeError::ReplicaDropped, here!())?;
+        }
+
+        session
+            .send(&())
+            .await
+            .pot(ServeDischargeError::ConnectionError, here!())?;
+
+        session.end();
+
+        Ok(())
+    }
+
+    // Returns once a quorum of `destination`'s members (including the local replica,
+    // if a member of `destination`: see `Replica::run`) is prepared for `destination`
+    pub(in crate::replica) async fn discharge(
+        identity: Identity,
+        destination: &View,
+        connector: &SessionConnector,
+        settings: &ReplicaSettings,
+    ) {
+        let mut discharges = if destination.members().contains_key(&identity) {
+            1
+        } else {
+            0
+        };
+
+        if discharges >= destination.quorum() {
+            return;
+        }
+
+        let mut requests = destination
+            .members()
+            .keys()
+            .copied()
+            .filter(|member| *member != identity)
+            .map(|member| async move {
+                while Replica::request_discharge(destination, member, connector)
+                    .await
+                    .is_err()
+                {
+                    time::sleep(settings.discharge_interval).await;
+                }
+            })
+            .collect::<FuturesUnordered<_>>();
+
+        while requests.next().await.is_some() {
+            discharges += 1;
+
+            if discharges >= destination.quorum() {
+                return;
+            }
+        }
+    }
+
+    async fn request_discharge(
+        view: &View,
+        member: Identity,
+        connector: &SessionConnector,
+    ) -> Result<(), Top<DischargeError>> {
+        let mut session = connector
+            .connect(member)
+            .await
+            .pot(DischargeError::ConnectFailed, here!())?;
+
+        session
+            .send(&view.identifier())
+            .await
+            .pot(DischargeError::ConnectionError, here!())?;
+
+        session
+            .receive::<()>()
+            .await
+            .pot(DischargeError::ConnectionError, here!())?;
+
+        session.end();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,63 @@
+use doomstack::Doom;
+
+#[derive(Doom)]
+pub(in crate::replica) enum ServeDischargeError {
+    #[doom(description("Connection error"))]
+    ConnectionError,
+    #[doom(description("Unknown `View`"))]
+    UnknownView,
+    #[doom(description("`Replica` dropped"))]
+    ReplicaDropped,
+}
+
+#[derive(Doom)]
+pub(in crate::replica) enum DischargeError {
+    #[doom(description("Failed to connect"))]
+    ConnectFailed,
+    #[doom(description("Connection error"))]
+    ConnectionError,
+}
+
+#[derive(Doom)]
+pub(in crate::replica) enum ServeResolutionError {
+    #[doom(description("Connection error"))]
+    ConnectionError,
+    #[doom(description("`View` is not the current one"))]
+    ViewNotCurrent,
+    #[doom(description("Not a member of the `View`"))]
+    ForeignView,
+    #[doom(description("`Pass` does not pertain to the `Change`"))]
+    MotionMismatch,
+    #[doom(description("`Pass` invalid"))]
+    PassInvalid,
+}
+
+#[derive(Doom)]
+pub(in crate::replica) enum ResolveError {
+    #[doom(description("Motion did not pass"))]
+    PassUnreached,
+    #[doom(description("Failed to collect a quorum of signatures"))]
+    ResolutionUnreached,
+}
+
+#[derive(Doom)]
+pub(in crate::replica) enum RequestPassError {
+    #[doom(description("Failed to connect"))]
+    ConnectFailed,
+    #[doom(description("Connection error"))]
+    ConnectionError,
+    #[doom(description("Motion did not pass yet"))]
+    MotionPending,
+    #[doom(description("Unexpected response"))]
+    UnexpectedResponse,
+    #[doom(description("`PassShard` invalid"))]
+    ShardInvalid,
+}
+
+#[derive(Doom)]
+pub(in crate::replica) enum RequestResolutionError {
+    #[doom(description("Failed to connect"))]
+    ConnectFailed,
+    #[doom(description("Connection error"))]
+    ConnectionError,
+}
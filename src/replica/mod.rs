@@ -0,0 +1,11 @@
+mod discharge;
+mod errors;
+mod replica;
+mod replica_settings;
+mod resolution;
+
+#[allow(unused_imports)]
+pub(crate) use replica::Replica;
+
+#[allow(unused_imports)]
+pub(crate) use replica_settings::ReplicaSettings;
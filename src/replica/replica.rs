@@ -0,0 +1,663 @@
+use crate::{
+    churn::Churn,
+    crypto::Identify,
+    database::Database,
+    discovery::Client,
+    processing::Processor,
+    replica::ReplicaSettings,
+    view::{Change, Install, View},
+    view_generator::ViewGenerator,
+};
+
+use std::{future, sync::Arc};
+
+use talk::{
+    crypto::{primitives::hash::Hash, KeyChain},
+    link::context::{ConnectDispatcher, ListenDispatcher},
+    net::{Connector, Listener, SessionConnector},
+    sync::fuse::Fuse,
+};
+
+use tokio::{
+    sync::{
+        mpsc::{self, UnboundedReceiver, UnboundedSender},
+        oneshot::{self, Receiver as OneshotReceiver, Sender as OneshotSender},
+        watch::{self, Receiver as WatchReceiver, Sender as WatchSender},
+    },
+    time,
+};
+
+type ChurnInlet = UnboundedSender<Churn>;
+type ChurnOutlet = UnboundedReceiver<Churn>;
+
+type ResolutionInlet = UnboundedSender<Change>;
+
+type ShutdownInlet = OneshotSender<OneshotSender<Database>>;
+type ShutdownOutlet = OneshotReceiver<OneshotSender<Database>>;
+
+pub(crate) struct Replica {
+    churn_inlet: ChurnInlet,
+    resolution_inlet: ResolutionInlet,
+    view_outlet: WatchReceiver<View>,
+    shutdown_inlet: ShutdownInlet,
+    _fuse: Fuse,
+}
+
+impl Replica {
+    pub fn new<C, L>(
+        keychain: KeyChain,
+        genesis: View,
+        discovery: Arc<Client>,
+        database: Database,
+        connector: C,
+        listener: L,
+        settings: ReplicaSettings,
+    ) -> Self
+    where
+        C: Connector,
+        L: Listener,
+    {
+        let connect_dispatcher = Arc::new(ConnectDispatcher::new(connector));
+        let listen_dispatcher =
+            ListenDispatcher::new(listener, settings.listen_dispatcher_settings.clone());
+
+        // Dischargement is not scoped by `View`: a replica's preparation
+        // progresses monotonically across `View`s
+
+        let discharge_context = "replica::discharge".to_owned();
+
+        let discharge_connector =
+            SessionConnector::new(connect_dispatcher.register(discharge_context.clone()));
+
+        let discharge_listener = listen_dispatcher.register(discharge_context);
+
+        // Like dischargement, resolution requests carry their `View`
+
+        let resolution_context = "replica::resolution".to_owned();
+
+        let resolution_connector = Arc::new(SessionConnector::new(
+            connect_dispatcher.register(resolution_context.clone()),
+        ));
+
+        let resolution_listener = listen_dispatcher.register(resolution_context);
+
+        let (prepared_inlet, prepared_outlet) = watch::channel(genesis.height());
+        let (view_inlet, view_outlet) = watch::channel(genesis.clone());
+        let (churn_inlet, churn_outlet) = mpsc::unbounded_channel();
+        let (resolution_inlet, resolution_outlet) = mpsc::unbounded_channel();
+        let (shutdown_inlet, shutdown_outlet) = oneshot::channel();
+
+        let fuse = Fuse::new();
+
+        fuse.spawn(async move {
+            Replica::run_discharge(discharge_listener, prepared_outlet).await;
+        });
+
+        {
+            let keychain = keychain.clone();
+            let discovery = discovery.clone();
+            let view_outlet = view_outlet.clone();
+
+            fuse.spawn(async move {
+                Replica::run_resolution(keychain, discovery, view_outlet, resolution_listener)
+                    .await;
+            });
+        }
+
+        {
+            let view_outlet = view_outlet.clone();
+            let connect_dispatcher = connect_dispatcher.clone();
+            let churn_inlet = churn_inlet.clone();
+            let settings = settings.clone();
+
+            fuse.spawn(async move {
+                Replica::run_resolve(
+                    view_outlet,
+                    connect_dispatcher,
+                    resolution_connector,
+                    churn_inlet,
+                    resolution_outlet,
+                    settings,
+                )
+                .await;
+            });
+        }
+
+        fuse.spawn(async move {
+            Replica::run(
+                keychain,
+                genesis,
+                discovery,
+                database,
+                connect_dispatcher,
+                listen_dispatcher,
+                discharge_connector,
+                prepared_inlet,
+                view_inlet,
+                churn_outlet,
+                shutdown_outlet,
+                settings,
+            )
+            .await;
+        });
+
+        Replica {
+            churn_inlet,
+            resolution_inlet,
+            view_outlet,
+            shutdown_inlet,
+            _fuse: fuse,
+        }
+    }
+
+    /// Returns the `View` the replica is currently operating in.
+    pub fn view(&self) -> View {
+        self.view_outlet.borrow().clone()
+    }
+
+    /// Queues `churn` for proposal at the next installed `View`
+    /// the replica is a member of.
+    pub fn propose(&self, churn: Churn) {
+        let _ = self.churn_inlet.send(churn);
+    }
+
+    /// Requests the admission (`Change::Join`) or removal (`Change::Leave`)
+    /// voted by the governance motion `change.identifier()`. Once the motion
+    /// passes, a `Resolution` for `change` is collected and proposed as churn.
+    pub fn resolve(&self, change: Change) {
+        let _ = self.resolution_inlet.send(change);
+    }
+
+    /// Stops the replica, returning its `Database` (any running `Processor`
+    /// is shut down). `Record`s not yet flushed are left in its journal.
+    pub async fn shutdown(self) -> Database {
+        let (reply_inlet, reply_outlet) = oneshot::channel();
+
+        // `run` returns only upon receiving `reply_inlet`: neither of the
+        // following can fail
+        let _ = self.shutdown_inlet.send(reply_inlet);
+        reply_outlet.await.unwrap()
+    }
+
+    async fn run(
+        keychain: KeyChain,
+        genesis: View,
+        discovery: Arc<Client>,
+        database: Database,
+        connect_dispatcher: Arc<ConnectDispatcher>,
+        listen_dispatcher: ListenDispatcher,
+        discharge_connector: SessionConnector,
+        prepared_inlet: WatchSender<usize>,
+        view_inlet: WatchSender<View>,
+        mut churn_outlet: ChurnOutlet,
+        mut shutdown_outlet: ShutdownOutlet,
+        settings: ReplicaSettings,
+    ) {
+        let identity = keychain.keycard().identity();
+        let fuse = Fuse::new();
+
+        let mut view = genesis;
+
+        // The `Install` through which `view` was reached (`None` for genesis),
+        // and whether or not `view` is installed (i.e., `install` is tailless)
+        let mut install: Option<Hash> = None;
+        let mut installed = true;
+
+        let mut pending = Vec::new();
+
+        // `database` is held by `processor` while `processor` is running
+        let mut database = Some(database);
+        let mut processor: Option<Processor> = None;
+
+        loop {
+            let mut generator = None;
+
+            if view.members().contains_key(&identity) {
+                // `Processor` contexts are not nested, so that brokers can reach `processor`
+                processor = Some(Processor::with_dispatcher(
+                    keychain.clone(),
+                    discovery.clone(),
+                    view.clone(),
+                    database.take().unwrap(),
+                    &connect_dispatcher,
+                    &listen_dispatcher,
+                    settings.processor_settings.clone(),
+                ));
+
+                let generator_context = format!("{:?}::replica::view_generator", view.identifier());
+
+                generator = Some(ViewGenerator::new(
+                    view.clone(),
+                    keychain.clone(),
+                    discovery.clone(),
+                    connect_dispatcher.register(generator_context.clone()),
+                    listen_dispatcher.register(generator_context),
+                    settings.view_generator_settings.clone(),
+                ));
+            }
+
+            let _ = view_inlet.send(view.clone());
+
+            // A `View` that is not installed is immediately superseded by its tail
+            let mut proposed = false;
+
+            if let (Some(generator), Some(install)) = (generator.as_mut(), install) {
+                if !installed {
+                    generator.propose_tail(install);
+                    proposed = true;
+                }
+            }
+
+            let mut decided = false;
+
+            let transition = loop {
+                if let (Some(generator), Some(install)) = (generator.as_mut(), install) {
+                    if !proposed && !pending.is_empty() {
+                        generator.propose_churn(install, pending.drain(..));
+                        proposed = true;
+                    }
+                }
+
+                tokio::select! {
+                    reply = &mut shutdown_outlet => {
+                        // If `reply` is an error, `self` was dropped without shutdown
+                        if let Ok(reply) = reply {
+                            Replica::surrender(processor, database, reply);
+                        }
+
+                        return;
+                    }
+                    transition = discovery.beyond(view.height()) => break transition,
+                    Some(churn) = churn_outlet.recv() => pending.push(churn),
+                    install = Replica::decide(&mut generator), if !decided => {
+                        decided = true;
+
+                        let discovery = discovery.clone();
+
+                        fuse.spawn(async move {
+                            discovery.publish(install).await;
+                        });
+                    }
+                }
+            };
+
+            drop(generator);
+
+            let source = transition.source().clone();
+            let destination = transition.destination().clone();
+
+            // If `view` fell behind `source`, `processor` holds no relevant state
+            if source.identifier() != view.identifier() {
+                if let Some(processor) = processor.take() {
+                    database = Some(processor.shutdown());
+                }
+            }
+
+            // Prepare: a replica joining `destination` acquires the state of `source`
+            if destination.members().contains_key(&identity) && processor.is_none() {
+                let mut acquired = database.take().unwrap();
+
+                // `acquired` is left untouched unless `Replica::prepare` completes
+                let shutdown = tokio::select! {
+                    reply = &mut shutdown_outlet => Some(reply),
+                    _ = Replica::prepare(
+                        &discovery,
+                        &source,
+                        &destination,
+                        &mut acquired,
+                        &connect_dispatcher,
+                        &settings,
+                    ) => None,
+                };
+
+                if let Some(reply) = shutdown {
+                    if let Ok(reply) = reply {
+                        Replica::surrender(None, Some(acquired), reply);
+                    }
+
+                    return;
+                }
+
+                database = Some(acquired);
+            }
+
+            let _ = prepared_inlet.send(destination.height());
+
+            // Discharge: `source`'s `Processor` keeps serving (in particular, state
+            // transfer) until a quorum of `destination`'s members is prepared
+            if processor.is_some() {
+                tokio::select! {
+                    reply = &mut shutdown_outlet => {
+                        // If `reply` is an error, `self` was dropped without shutdown
+                        if let Ok(reply) = reply {
+                            Replica::surrender(processor, database, reply);
+                        }
+
+                        return;
+                    }
+                    _ = Replica::discharge(identity, &destination, &discharge_connector, &settings) => {}
+                }
+
+                database = processor.take().map(Processor::shutdown);
+            }
+
+            install = Some(transition.install());
+            installed = transition.tailless();
+            view = destination;
+        }
+    }
+
+    async fn prepare(
+        discovery: &Client,
+        source: &View,
+        destination: &View,
+        database: &mut Database,
+        connect_dispatcher: &ConnectDispatcher,
+        settings: &ReplicaSettings,
+    ) {
+        // State is acquired from `source` or, if `source` was already discharged,
+        // from `destination`, whose members hold (at least) the state of `source`
+
+        let connectors = [source, destination]
+            .iter()
+            .map(|view| {
+                let context = format!("{:?}::processor::transfer", view.identifier());
+                (
+                    *view,
+                    SessionConnector::new(connect_dispatcher.register(context)),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        loop {
+            for (view, connector) in connectors.iter() {
+                if Processor::acquire(
+                    discovery,
+                    view,
+                    database,
+                    connector,
+                    &settings.processor_settings,
+                )
+                .await
+                .is_ok()
+                {
+                    return;
+                }
+            }
+
+            time::sleep(settings.acquire_interval).await;
+        }
+    }
+
+    // Hands the local `Database` over to `Replica::shutdown`
+    fn surrender(
+        processor: Option<Processor>,
+        database: Option<Database>,
+        reply: OneshotSender<Database>,
+    ) {
+        let database = match processor {
+            Some(processor) => processor.shutdown(),
+            None => database.unwrap(),
+        };
+
+        let _ = reply.send(database);
+    }
+
+    async fn decide(generator: &mut Option<ViewGenerator>) -> Install {
+        match generator {
+            Some(generator) => generator.decide().await,
+            None => future::pending().await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{
+        account::{Entry, Operation},
+        commit::Payload,
+        discovery::{self, Mode},
+        prepare::Continuity,
+        processing::test::TestBroker,
+        signup::{IdRequest, SignupSettings},
+    };
+
+    use std::{iter, time::Duration};
+
+    use talk::{crypto::primitives::hash, net::test::System as NetSystem};
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn join() {
+        let (install_generator, _discovery_server, _, mut discovery_clients, _) =
+            discovery::test::setup(5, 4, Mode::Full).await;
+
+        let genesis = install_generator.view(4);
+        let keychains = install_generator.keychains.clone();
+        let joiner = keychains[4].keycard().identity();
+
+        let brokers = vec![KeyChain::random(), KeyChain::random()];
+
+        let NetSystem {
+            mut connectors,
+            mut listeners,
+            ..
+        } = NetSystem::setup_with_keychains(keychains.iter().chain(brokers.iter()).cloned()).await;
+
+        let replicas = keychains
+            .iter()
+            .cloned()
+            .map(|keychain| {
+                Replica::new(
+                    keychain,
+                    genesis.clone(),
+                    Arc::new(discovery_clients.next().unwrap()),
+                    Database::new(),
+                    connectors.remove(0),
+                    listeners.remove(0),
+                    Default::default(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        // The fifth replica joins the genesis members
+        let install = install_generator.install(4, 5, []);
+        let destination = install.clone().into_transition().destination().clone();
+
+        let mut brokers = brokers
+            .into_iter()
+            .zip(vec![genesis.clone(), destination.clone()])
+            .map(|(keychain, view)| TestBroker::new(keychain, view, connectors.remove(0)))
+            .collect::<Vec<_>>();
+
+        let destination_broker = brokers.pop().unwrap();
+        let genesis_broker = brokers.pop().unwrap();
+
+        // Before the join: an account is signed up and settles its first operation
+
+        let client = KeyChain::random();
+
+        let request = IdRequest::new(
+            &client,
+            &genesis,
+            keychains[0].keycard().identity(),
+            SignupSettings::default().work_difficulty,
+        );
+
+        let assignment = genesis_broker
+            .signup(vec![request])
+            .await
+            .remove(0)
+            .unwrap();
+
+        let id = assignment.id();
+
+        let payload = Payload::new(
+            Entry { id, height: 1 },
+            Operation::support(hash::hash(&0u32).unwrap()),
+        );
+
+        let completion = genesis_broker
+            .settle(&[(client.clone(), assignment.clone(), payload, None)])
+            .await
+            .remove(0);
+
+        discovery_clients.next().unwrap().publish(install).await;
+
+        for replica in replicas.iter() {
+            while replica.view().identifier() != destination.identifier() {
+                time::sleep(Duration::from_millis(100)).await;
+            }
+        }
+
+        // After the join: the joiner allocates a new account, then settles operations
+        // that depend on the state it acquired
+
+        let newcomer = KeyChain::random();
+
+        let request = IdRequest::new(
+            &newcomer,
+            &destination,
+            joiner,
+            SignupSettings::default().work_difficulty,
+        );
+
+        let newcomer_assignment = destination_broker
+            .signup(vec![request])
+            .await
+            .remove(0)
+            .unwrap();
+
+        let newcomer_id = newcomer_assignment.id();
+
+        let mut payloads = vec![
+            (
+                client,
+                assignment,
+                Payload::new(
+                    Entry { id, height: 2 },
+                    Operation::support(hash::hash(&1u32).unwrap()),
+                ),
+                Some(Continuity::Completion(completion)),
+            ),
+            (
+                newcomer,
+                newcomer_assignment,
+                Payload::new(
+                    Entry {
+                        id: newcomer_id,
+                        height: 1,
+                    },
+                    Operation::support(hash::hash(&1u32).unwrap()),
+                ),
+                None,
+            ),
+        ];
+
+        payloads.sort_by_key(|(_, _, payload, _)| payload.id());
+
+        // `settle` checks that every member (including the joiner) prepares
+        // the batch without exceptions, and that a quorum completes it
+        destination_broker.settle(payloads.as_slice()).await;
+
+        let summaries = destination_broker
+            .summaries(joiner, vec![id, newcomer_id])
+            .await
+            .into_iter()
+            .map(|summary| summary.unwrap().height)
+            .collect::<Vec<_>>();
+
+        assert_eq!(summaries, vec![2, 1]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn resolution() {
+        let (install_generator, _discovery_server, _, mut discovery_clients, _) =
+            discovery::test::setup(6, 4, Mode::Full).await;
+
+        let genesis = install_generator.view(4);
+        let keychains = install_generator.keychains.clone();
+
+        let broker = KeyChain::random();
+
+        let NetSystem {
+            mut connectors,
+            mut listeners,
+            ..
+        } = NetSystem::setup_with_keychains(keychains.iter().chain(iter::once(&broker)).cloned())
+            .await;
+
+        // A single supporting account passes any motion
+        let mut settings = ReplicaSettings::default();
+        settings.processor_settings.commit.pass_threshold = 1;
+        settings.resolve_interval = Duration::from_millis(100);
+
+        let replicas = keychains
+            .iter()
+            .cloned()
+            .map(|keychain| {
+                Replica::new(
+                    keychain,
+                    genesis.clone(),
+                    Arc::new(discovery_clients.next().unwrap()),
+                    Database::new(),
+                    connectors.remove(0),
+                    listeners.remove(0),
+                    settings.clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        // Churn is proposed only in installed, non-genesis `View`s: the fifth replica
+        // joins first, the sixth replica then joins by resolution
+        let install = install_generator.install(4, 5, []);
+        let view = install.clone().into_transition().destination().clone();
+
+        discovery_clients.next().unwrap().publish(install).await;
+
+        for replica in replicas.iter().take(5) {
+            while replica.view().identifier() != view.identifier() {
+                time::sleep(Duration::from_millis(100)).await;
+            }
+        }
+
+        let candidate = keychains[5].keycard();
+        let change = Change::Join(candidate.clone());
+
+        // An account supports the motion voting `change`
+
+        let broker = TestBroker::new(broker, view.clone(), connectors.remove(0));
+        let client = KeyChain::random();
+
+        let request = IdRequest::new(
+            &client,
+            &view,
+            keychains[0].keycard().identity(),
+            SignupSettings::default().work_difficulty,
+        );
+
+        let assignment = broker.signup(vec![request]).await.remove(0).unwrap();
+
+        let payload = Payload::new(
+            Entry {
+                id: assignment.id(),
+                height: 1,
+            },
+            Operation::support(change.identifier()),
+        );
+
+        broker.settle(&[(client, assignment, payload, None)]).await;
+
+        for replica in replicas.iter().take(5) {
+            replica.resolve(change.clone());
+        }
+
+        // Every replica (including the candidate) reaches a `View` that includes the candidate
+        for replica in replicas.iter() {
+            while !replica.view().members().contains_key(&candidate.identity()) {
+                time::sleep(Duration::from_millis(100)).await;
+            }
+        }
+    }
+}
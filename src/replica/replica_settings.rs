@@ -0,0 +1,33 @@
+use crate::{processing::ProcessorSettings, view_generator::ViewGeneratorSettings};
+
+use serde::Deserialize;
+
+use std::time::Duration;
+
+use talk::link::context::ListenDispatcherSettings;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub(crate) struct ReplicaSettings {
+    #[serde(skip)]
+    pub listen_dispatcher_settings: ListenDispatcherSettings,
+    pub processor_settings: ProcessorSettings,
+    #[serde(skip)]
+    pub view_generator_settings: ViewGeneratorSettings,
+    pub acquire_interval: Duration,
+    pub discharge_interval: Duration,
+    pub resolve_interval: Duration,
+}
+
+impl Default for ReplicaSettings {
+    fn default() -> Self {
+        ReplicaSettings {
+            listen_dispatcher_settings: Default::default(),
+            processor_settings: Default::default(),
+            view_generator_settings: Default::default(),
+            acquire_interval: Duration::from_secs(1),
+            discharge_interval: Duration::from_secs(1),
+            resolve_interval: Duration::from_secs(5),
+        }
+    }
+}
@@ -0,0 +1,320 @@
+use crate::{
+    churn::{Churn, Resolution, ResolutionAggregator},
+    crypto::Identify,
+    discovery::Client,
+    motion::{Pass, PassAggregator, PassShard},
+    processing::messages::{QueryRequest, QueryResponse},
+    replica::{
+        errors::{RequestPassError, RequestResolutionError, ResolveError, ServeResolutionError},
+        Replica, ReplicaSettings,
+    },
+    view::{Change, View},
+};
+
+use doomstack::{here, Doom, ResultExt, Top};
+
+use futures::stream::{FuturesUnordered, StreamExt};
+
+use std::sync::Arc;
+
+use talk::{
+    crypto::{
+        primitives::{hash::Hash, multi::Signature as MultiSignature},
+        KeyCard, KeyChain,
+    },
+    link::context::ConnectDispatcher,
+    net::{Listener, Session, SessionConnector, SessionListener},
+    sync::fuse::Fuse,
+};
+
+use tokio::{
+    sync::{
+        mpsc::{UnboundedReceiver, UnboundedSender},
+        watch::Receiver as WatchReceiver,
+    },
+    time,
+};
+
+impl Replica {
+    pub(in crate::replica) async fn run_resolution<L>(
+        keychain: KeyChain,
+        discovery: Arc<Client>,
+        view_outlet: WatchReceiver<View>,
+        listener: L,
+    ) where
+        L: Listener,
+    {
+        let mut listener = SessionListener::new(listener);
+        let fuse = Fuse::new();
+
+        loop {
+            let (_, session) = listener.accept().await;
+
+            let keychain = keychain.clone();
+            let discovery = discovery.clone();
+            let view_outlet = view_outlet.clone();
+
+            fuse.spawn(async move {
+                let _ = Replica::serve_resolution(keychain, discovery, view_outlet, session).await;
+            });
+        }
+    }
+
+    async fn serve_resolution(
+        keychain: KeyChain,
+        discovery: Arc<Client>,
+        view_outlet: WatchReceiver<View>,
+        mut session: Session,
+    ) -> Result<(), Top<ServeResolutionError>> {
+        let (view, change, pass) = session
+            .receive::<(Hash, Change, Pass)>()
+            .await
+            .pot(ServeResolutionError::ConnectionError, here!())?;
+
+        // Resolutions are certified only in the replica's current `View`: a `Resolution`
+        // collected in a superseded `View` could re-apply a `Change` already installed
+        let current = view_outlet.borrow().clone();
+
+        if view != current.identifier() {
+            return ServeResolutionError::ViewNotCurrent.fail().spot(here!());
+        }
+
+        let view = current;
+
+        if !view.members().contains_key(&keychain.keycard().identity()) {
+            return ServeResolutionError::ForeignView.fail().spot(here!());
+        }
+
+        // The motion voting `change` is identified by `change` itself
+        if pass.motion() != change.identifier() {
+            return ServeResolutionError::MotionMismatch.fail().spot(here!());
+        }
+
+        pass.validate(discovery.as_ref())
+            .pot(ServeResolutionError::PassInvalid, here!())?;
+
+        let signature = Resolution::certify(&keychain, change);
+
+        session
+            .send(&signature)
+            .await
+            .pot(ServeResolutionError::ConnectionError, here!())?;
+
+        session.end();
+
+        Ok(())
+    }
+
+    // Resolves each `Change` received from `resolution_outlet`, then queues
+    // the resulting `Churn` for proposal (see `Replica::propose`)
+    pub(in crate::replica) async fn run_resolve(
+        view_outlet: WatchReceiver<View>,
+        connect_dispatcher: Arc<ConnectDispatcher>,
+        resolution_connector: Arc<SessionConnector>,
+        churn_inlet: UnboundedSender<Churn>,
+        mut resolution_outlet: UnboundedReceiver<Change>,
+        settings: ReplicaSettings,
+    ) {
+        let fuse = Fuse::new();
+
+        while let Some(change) = resolution_outlet.recv().await {
+            let view_outlet = view_outlet.clone();
+            let connect_dispatcher = connect_dispatcher.clone();
+            let resolution_connector = resolution_connector.clone();
+            let churn_inlet = churn_inlet.clone();
+            let settings = settings.clone();
+
+            fuse.spawn(async move {
+                let resolution = Replica::resolve(
+                    view_outlet,
+                    change,
+                    connect_dispatcher.as_ref(),
+                    resolution_connector.as_ref(),
+                    &settings,
+                )
+                .await;
+
+                let _ = churn_inlet.send(Churn::Resolution(resolution.into()));
+            });
+        }
+    }
+
+    // Retries (in the replica's current `View`) until `change`'s motion passes
+    async fn resolve(
+        view_outlet: WatchReceiver<View>,
+        change: Change,
+        connect_dispatcher: &ConnectDispatcher,
+        resolution_connector: &SessionConnector,
+        settings: &ReplicaSettings,
+    ) -> Resolution {
+        loop {
+            let view = view_outlet.borrow().clone();
+
+            let context = format!("{:?}::processor::query", view.identifier());
+            let query_connector = SessionConnector::new(connect_dispatcher.register(context));
+
+            if let Ok(resolution) = Replica::resolve_attempt(
+                &view,
+                change.clone(),
+                &query_connector,
+                resolution_connector,
+            )
+            .await
+            {
+                return resolution;
+            }
+
+            time::sleep(settings.resolve_interval).await;
+        }
+    }
+
+    async fn resolve_attempt(
+        view: &View,
+        change: Change,
+        query_connector: &SessionConnector,
+        resolution_connector: &SessionConnector,
+    ) -> Result<Resolution, Top<ResolveError>> {
+        let motion = change.identifier();
+
+        // Collect a `Pass` for `motion` from a plurality of `view`'s members
+
+        let mut aggregator = PassAggregator::new(view.clone(), motion);
+
+        let mut requests = view
+            .members()
+            .values()
+            .map(|member| async move {
+                let shard = Replica::request_pass(view, motion, member, query_connector).await;
+                (member, shard)
+            })
+            .collect::<FuturesUnordered<_>>();
+
+        while let Some((member, shard)) = requests.next().await {
+            if let Ok(shard) = shard {
+                aggregator.add(member, shard);
+
+                if aggregator.complete() {
+                    break;
+                }
+            }
+        }
+
+        if !aggregator.complete() {
+            return ResolveError::PassUnreached.fail().spot(here!());
+        }
+
+        let pass = aggregator.finalize();
+
+        // Collect a `Resolution` for `change` from a quorum of `view`'s members
+
+        let mut aggregator = ResolutionAggregator::new(view.clone(), change.clone());
+
+        let mut requests = view
+            .members()
+            .values()
+            .map(|member| {
+                let change = change.clone();
+                let pass = pass.clone();
+
+                async move {
+                    let signature = Replica::request_resolution(
+                        view,
+                        change,
+                        pass,
+                        member,
+                        resolution_connector,
+                    )
+                    .await;
+
+                    (member, signature)
+                }
+            })
+            .collect::<FuturesUnordered<_>>();
+
+        while let Some((member, signature)) = requests.next().await {
+            if let Ok(signature) = signature {
+                // Invalid signatures are ignored
+                let _ = aggregator.add(member, signature);
+
+                if aggregator.complete() {
+                    break;
+                }
+            }
+        }
+
+        if !aggregator.complete() {
+            return ResolveError::ResolutionUnreached.fail().spot(here!());
+        }
+
+        Ok(aggregator.finalize())
+    }
+
+    async fn request_pass(
+        view: &View,
+        motion: Hash,
+        member: &KeyCard,
+        connector: &SessionConnector,
+    ) -> Result<PassShard, Top<RequestPassError>> {
+        let mut session = connector
+            .connect(member.identity())
+            .await
+            .pot(RequestPassError::ConnectFailed, here!())?;
+
+        session
+            .send(&QueryRequest::Motion(motion))
+            .await
+            .pot(RequestPassError::ConnectionError, here!())?;
+
+        let response = session
+            .receive::<QueryResponse>()
+            .await
+            .pot(RequestPassError::ConnectionError, here!())?;
+
+        session.end();
+
+        let shard = match response {
+            QueryResponse::Motion {
+                shard: Some(shard), ..
+            } => shard,
+            QueryResponse::Motion { shard: None, .. } => {
+                return RequestPassError::MotionPending.fail().spot(here!());
+            }
+            _ => {
+                return RequestPassError::UnexpectedResponse.fail().spot(here!());
+            }
+        };
+
+        shard
+            .validate(view, motion, member)
+            .pot(RequestPassError::ShardInvalid, here!())?;
+
+        Ok(shard)
+    }
+
+    async fn request_resolution(
+        view: &View,
+        change: Change,
+        pass: Pass,
+        member: &KeyCard,
+        connector: &SessionConnector,
+    ) -> Result<MultiSignature, Top<RequestResolutionError>> {
+        let mut session = connector
+            .connect(member.identity())
+            .await
+            .pot(RequestResolutionError::ConnectFailed, here!())?;
+
+        session
+            .send(&(view.identifier(), change, pass))
+            .await
+            .pot(RequestResolutionError::ConnectionError, here!())?;
+
+        let signature = session
+            .receive::<MultiSignature>()
+            .await
+            .pot(RequestResolutionError::ConnectionError, here!())?;
+
+        session.end();
+
+        Ok(signature)
+    }
+}
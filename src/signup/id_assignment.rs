@@ -21,7 +21,7 @@ use talk::crypto::{
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub(crate) struct IdAssignment {
+pub struct IdAssignment {
     view: Hash,
     assignment: Assignment,
     certificate: Certificate,
@@ -36,7 +36,7 @@ struct Assignment {
 pub(crate) struct IdAssignmentAggregator(Aggregator<Assignment>);
 
 #[derive(Doom)]
-pub(crate) enum IdAssignmentError {
+pub enum IdAssignmentError {
     #[doom(description("Assignment signed in an unknown `View`"))]
     ViewUnknown,
     #[doom(description("Certificate invalid"))]
@@ -44,7 +44,7 @@ pub(crate) enum IdAssignmentError {
 }
 
 impl IdAssignment {
-    pub fn certify(keychain: &KeyChain, claim: &IdClaim) -> MultiSignature {
+    pub(crate) fn certify(keychain: &KeyChain, claim: &IdClaim) -> MultiSignature {
         keychain
             .multisign(&Assignment {
                 id: claim.id(),
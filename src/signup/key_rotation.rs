@@ -0,0 +1,67 @@
+use crate::{
+    account::{Id, Operation},
+    commit::Completion,
+    discovery::Client,
+};
+
+use doomstack::{here, Doom, ResultExt, Top};
+
+use serde::{Deserialize, Serialize};
+
+use talk::crypto::KeyCard;
+
+/// Proves that the `KeyCard` of an `Id` was rotated (see `Operation::Rotate`).
+/// Like an `IdAssignment`, a `KeyRotation` binds an `Id` to a `KeyCard`, but
+/// only from `height()` on.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyRotation(Completion);
+
+#[derive(Doom)]
+pub enum KeyRotationError {
+    #[doom(description("`Completion` is not for a `Rotate` operation"))]
+    NotRotation,
+    #[doom(description("`Completion` invalid"))]
+    CompletionInvalid,
+}
+
+impl KeyRotation {
+    pub fn new(completion: Completion) -> Result<Self, Top<KeyRotationError>> {
+        match completion.operation() {
+            Operation::Rotate(_) => Ok(KeyRotation(completion)),
+            _ => KeyRotationError::NotRotation.fail().spot(here!()),
+        }
+    }
+
+    pub fn id(&self) -> Id {
+        self.0.id()
+    }
+
+    /// Returns the first height whose `Prepare` is signed by `self.keycard()`.
+    pub fn height(&self) -> u64 {
+        self.0.height() + 1
+    }
+
+    pub fn keycard(&self) -> &KeyCard {
+        match self.0.operation() {
+            Operation::Rotate(rotate) => rotate.keycard(),
+            // `self` is either built by `new` or checked by `validate`
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn completion(&self) -> &Completion {
+        &self.0
+    }
+
+    pub fn validate(&self, discovery: &Client) -> Result<(), Top<KeyRotationError>> {
+        if !matches!(self.0.operation(), Operation::Rotate(_)) {
+            return KeyRotationError::NotRotation.fail().spot(here!());
+        }
+
+        self.0
+            .validate(discovery)
+            .pot(KeyRotationError::CompletionInvalid, here!())?;
+
+        Ok(())
+    }
+}
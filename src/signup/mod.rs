@@ -3,13 +3,14 @@ mod id_allocation;
 mod id_assignment;
 mod id_claim;
 mod id_request;
+mod key_rotation;
 mod signup_settings;
 
 #[allow(unused_imports)]
 pub(crate) use id_allocation::IdAllocation;
 
-#[allow(unused_imports)]
-pub(crate) use id_assignment::IdAssignment;
+pub use id_assignment::{IdAssignment, IdAssignmentError};
+
 #[allow(unused_imports)]
 pub(crate) use id_assignment::IdAssignmentAggregator;
 
@@ -18,4 +19,5 @@ pub(crate) use id_claim::IdClaim;
 
 #[allow(unused_imports)]
 pub(crate) use id_request::IdRequest;
-pub(crate) use signup_settings::SignupSettings;
+pub use key_rotation::{KeyRotation, KeyRotationError};
+pub use signup_settings::SignupSettings;
@@ -1,5 +1,8 @@
-#[derive(Debug, Clone)]
-pub(crate) struct SignupSettings {
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SignupSettings {
     pub work_difficulty: u64,
 }
 
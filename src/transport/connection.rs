@@ -0,0 +1,233 @@
+use crate::transport::Transport;
+
+use doomstack::{here, Doom, ResultExt, Top};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use std::net::SocketAddr;
+
+use talk::{
+    crypto::{Identity, KeyCard, KeyChain},
+    net::{PlainConnection, SecureConnection},
+};
+
+use tokio::net::TcpStream;
+
+/// A connection between a client and a broker, over either `Transport`.
+pub(crate) enum Connection {
+    Plain(PlainConnection),
+    Secure(SecureConnection),
+}
+
+#[derive(Doom)]
+pub(crate) enum ConnectionError {
+    #[doom(description("Failed to establish a connection"))]
+    ConnectFailed,
+    #[doom(description("Failed to secure the connection"))]
+    SecureFailed,
+    #[doom(description("Failed to authenticate the connection"))]
+    AuthenticateFailed,
+    #[doom(description("Broker authenticated with an unexpected `Identity`"))]
+    BrokerMismatch,
+    #[doom(description("Connection error"))]
+    ConnectionError,
+}
+
+impl Connection {
+    /// Connects to the broker at `address`. On a `Secure` transport, the broker
+    /// must authenticate as `broker`, while the client authenticates as `keychain`
+    /// (an ephemeral `KeyChain` can be provided to remain unauthenticated).
+    pub async fn connect(
+        address: SocketAddr,
+        broker: Identity,
+        keychain: &KeyChain,
+        transport: Transport,
+    ) -> Result<Self, Top<ConnectionError>> {
+        let stream = TcpStream::connect(address)
+            .await
+            .map_err(|_| ConnectionError::ConnectFailed.into_top())
+            .spot(here!())?;
+
+        let connection: PlainConnection = stream.into();
+
+        match transport {
+            Transport::Plain => Ok(Connection::Plain(connection)),
+            Transport::Secure => {
+                let mut connection = connection
+                    .secure()
+                    .await
+                    .pot(ConnectionError::SecureFailed, here!())?;
+
+                let remote = connection
+                    .authenticate(keychain)
+                    .await
+                    .pot(ConnectionError::AuthenticateFailed, here!())?;
+
+                if remote.identity() != broker {
+                    return ConnectionError::BrokerMismatch.fail().spot(here!());
+                }
+
+                Ok(Connection::Secure(connection))
+            }
+        }
+    }
+
+    /// Accepts a client on `stream`. On a `Secure` transport, the broker
+    /// authenticates as `keychain`, and the client's `KeyCard` is returned.
+    pub async fn accept(
+        stream: TcpStream,
+        keychain: &KeyChain,
+        transport: Transport,
+    ) -> Result<(Self, Option<KeyCard>), Top<ConnectionError>> {
+        let connection: PlainConnection = stream.into();
+
+        match transport {
+            Transport::Plain => Ok((Connection::Plain(connection), None)),
+            Transport::Secure => {
+                let mut connection = connection
+                    .secure()
+                    .await
+                    .pot(ConnectionError::SecureFailed, here!())?;
+
+                let remote = connection
+                    .authenticate(keychain)
+                    .await
+                    .pot(ConnectionError::AuthenticateFailed, here!())?;
+
+                Ok((Connection::Secure(connection), Some(remote)))
+            }
+        }
+    }
+
+    pub async fn send<M>(&mut self, message: &M) -> Result<(), Top<ConnectionError>>
+    where
+        M: Serialize + Send + Sync,
+    {
+        match self {
+            Connection::Plain(connection) => connection
+                .send(message)
+                .await
+                .pot(ConnectionError::ConnectionError, here!()),
+            Connection::Secure(connection) => connection
+                .send(message)
+                .await
+                .pot(ConnectionError::ConnectionError, here!()),
+        }
+    }
+
+    pub async fn receive<M>(&mut self) -> Result<M, Top<ConnectionError>>
+    where
+        M: DeserializeOwned + Send + Sync,
+    {
+        match self {
+            Connection::Plain(connection) => connection
+                .receive()
+                .await
+                .pot(ConnectionError::ConnectionError, here!()),
+            Connection::Secure(connection) => connection
+                .receive()
+                .await
+                .pot(ConnectionError::ConnectionError, here!()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::net::Ipv4Addr;
+
+    use tokio::net::TcpListener;
+
+    async fn serve(transport: Transport) -> (SocketAddr, KeyChain) {
+        let keychain = KeyChain::random();
+
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        {
+            let keychain = keychain.clone();
+
+            tokio::spawn(async move {
+                let (stream, _) = listener.accept().await.unwrap();
+
+                let (mut connection, client) = Connection::accept(stream, &keychain, transport)
+                    .await
+                    .unwrap();
+
+                let message = connection.receive::<u64>().await.unwrap();
+                let client = client.map(|client| client.identity());
+
+                connection.send(&(message + 1, client)).await.unwrap();
+            });
+        }
+
+        (address, keychain)
+    }
+
+    #[tokio::test]
+    async fn secure() {
+        let (address, broker) = serve(Transport::Secure).await;
+        let client = KeyChain::random();
+
+        let mut connection = Connection::connect(
+            address,
+            broker.keycard().identity(),
+            &client,
+            Transport::Secure,
+        )
+        .await
+        .unwrap();
+
+        connection.send(&41u64).await.unwrap();
+
+        let (message, identity) = connection
+            .receive::<(u64, Option<Identity>)>()
+            .await
+            .unwrap();
+
+        assert_eq!(message, 42);
+        assert_eq!(identity, Some(client.keycard().identity()));
+    }
+
+    #[tokio::test]
+    async fn plain() {
+        let (address, broker) = serve(Transport::Plain).await;
+        let client = KeyChain::random();
+
+        let mut connection = Connection::connect(
+            address,
+            broker.keycard().identity(),
+            &client,
+            Transport::Plain,
+        )
+        .await
+        .unwrap();
+
+        connection.send(&41u64).await.unwrap();
+
+        let (message, identity) = connection
+            .receive::<(u64, Option<Identity>)>()
+            .await
+            .unwrap();
+
+        assert_eq!(message, 42);
+        assert_eq!(identity, None);
+    }
+
+    #[tokio::test]
+    async fn impersonation() {
+        let (address, _) = serve(Transport::Secure).await;
+        let impersonated = KeyChain::random();
+
+        assert!(Connection::connect(
+            address,
+            impersonated.keycard().identity(),
+            &KeyChain::random(),
+            Transport::Secure,
+        )
+        .await
+        .is_err());
+    }
+}
@@ -0,0 +1,6 @@
+mod connection;
+mod transport;
+
+pub(crate) use connection::Connection;
+
+pub use transport::Transport;
@@ -0,0 +1,18 @@
+use serde::Deserialize;
+
+/// The transport over which clients and brokers communicate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum Transport {
+    /// Encrypted connection, on which the broker authenticates by its `KeyCard`
+    /// (and the client, optionally, by its account's `KeyCard`).
+    Secure,
+    /// Cleartext connection: any on-path party can read, drop or reorder
+    /// messages. Only meant for testing.
+    Plain,
+}
+
+impl Default for Transport {
+    fn default() -> Self {
+        Transport::Secure
+    }
+}
@@ -10,7 +10,7 @@ use talk::crypto::{
 };
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, StdHash, Serialize, Deserialize)]
-pub(crate) enum Change {
+pub enum Change {
     Join(KeyCard),
     Leave(KeyCard), // TODO: Refactor to `Leave(Identity)`
 }
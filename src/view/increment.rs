@@ -2,4 +2,4 @@ use crate::view::Change;
 
 use std::collections::BTreeSet;
 
-pub(crate) type Increment = BTreeSet<Change>;
+pub type Increment = BTreeSet<Change>;
@@ -17,7 +17,7 @@ use talk::crypto::{
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(remote = "Self")]
-pub(crate) struct Install {
+pub struct Install {
     statement: Statement,
     certificate: Certificate,
 }
@@ -64,7 +64,11 @@ impl Install {
     }
 
     pub fn into_transition(self) -> Transition {
-        Transition::new(self.statement.source, self.statement.increments)
+        Transition::new(
+            self.identifier(),
+            self.statement.source,
+            self.statement.increments,
+        )
     }
 
     fn check(&self) -> Result<(), Top<InstallError>> {
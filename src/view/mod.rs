@@ -10,12 +10,10 @@ use store::{FAMILY, VIEWS};
 #[cfg(test)]
 pub(crate) mod test;
 
-pub(crate) use change::Change;
-pub(crate) use increment::Increment;
-pub(crate) use install::Install;
+pub use change::Change;
+pub use increment::Increment;
+pub use install::Install;
 #[allow(unused_imports)]
 pub(crate) use install::InstallAggregator;
-pub(crate) use transition::Transition;
-pub(crate) use view::View;
-#[allow(unused_imports)]
-pub(crate) use view::ViewError;
+pub use transition::Transition;
+pub use view::{View, ViewError};
@@ -3,14 +3,15 @@ use crate::view::{Increment, View};
 use talk::crypto::primitives::hash::Hash;
 
 #[derive(Clone)]
-pub(crate) struct Transition {
+pub struct Transition {
+    install: Hash,
     source: View,
     destination: View,
     tail: Vec<View>,
 }
 
 impl Transition {
-    pub(in crate::view) fn new(source: Hash, increments: Vec<Increment>) -> Self {
+    pub(in crate::view) fn new(install: Hash, source: Hash, increments: Vec<Increment>) -> Self {
         let source =
             View::get(source).expect("An `Install` message was accepted with unknown `source`");
 
@@ -31,12 +32,17 @@ impl Transition {
         }
 
         Transition {
+            install,
             source,
             destination,
             tail,
         }
     }
 
+    pub fn install(&self) -> Hash {
+        self.install
+    }
+
     pub fn source(&self) -> &View {
         &self.source
     }
@@ -15,7 +15,7 @@ use talk::crypto::{primitives::hash::Hash, Identity, KeyCard};
 use zebra::database::{Collection, CollectionTransaction};
 
 #[derive(Clone)]
-pub(crate) struct View {
+pub struct View {
     data: Arc<Data>,
 }
 
@@ -26,7 +26,7 @@ struct Data {
 }
 
 #[derive(Doom)]
-pub(crate) enum ViewError {
+pub enum ViewError {
     #[doom(description("Extension results in a member joining more than once"))]
     DoubleJoin,
     #[doom(description("Extension results in a member leaving before joining"))]
@@ -0,0 +1,30 @@
+#!/bin/sh
+# Boots a local testnet (a discovery server, four replicas and a signup broker)
+# from the repository root. Ctrl-C shuts every node down gracefully.
+
+set -e
+
+cargo build --release --bins
+
+mkdir -p testnet/keys testnet/data
+
+if [ ! -f testnet/keys/genesis.bin ]; then
+    target/release/carbon-keygen 4 testnet/keys
+    mkdir -p testnet/keys/broker
+    target/release/carbon-keygen 1 testnet/keys/broker
+    mv testnet/keys/broker/keychain-0.bin testnet/keys/keychain-broker.bin
+    rm -r testnet/keys/broker
+fi
+
+trap 'kill -INT 0' INT
+
+target/release/carbon-discovery testnet/discovery.toml &
+sleep 1
+
+for i in 0 1 2 3; do
+    target/release/carbon-replica testnet/replica-$i.toml &
+done
+
+target/release/carbon-broker signup testnet/broker.toml &
+
+wait